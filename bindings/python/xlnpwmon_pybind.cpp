@@ -16,6 +16,7 @@ void add_stats_to_dict(py::dict& d, const pm_stats_t& s) {
     d["avg"] = s.avg;
     d["total"] = s.total;
     d["count"] = s.count;
+    d["stddev"] = s.stddev;
 }
 
 /**
@@ -123,6 +124,7 @@ public:
         total["status"] = std::string(data.total.status);
         total["warning_threshold"] = data.total.warning_threshold;
         total["critical_threshold"] = data.total.critical_threshold;
+        total["timestamp_ns"] = data.total.timestamp_ns;
         result["total"] = total;
 
         py::list sensors;
@@ -138,6 +140,7 @@ public:
                 sensor["status"] = std::string(data.sensors[i].status);
                 sensor["warning_threshold"] = data.sensors[i].warning_threshold;
                 sensor["critical_threshold"] = data.sensors[i].critical_threshold;
+                sensor["timestamp_ns"] = data.sensors[i].timestamp_ns;
                 sensors.append(sensor);
             }
         }
@@ -341,6 +344,8 @@ PYBIND11_MODULE(_core, m) {
         .value("ERROR_FILE_ACCESS", PM_ERROR_FILE_ACCESS)
         .value("ERROR_MEMORY", PM_ERROR_MEMORY)
         .value("ERROR_THREAD", PM_ERROR_THREAD)
+        .value("ERROR_INVALID_ARGUMENT", PM_ERROR_INVALID_ARGUMENT)
+        .value("ERROR_TIMEOUT", PM_ERROR_TIMEOUT)
         .export_values();
 
     // 导出传感器类型枚举
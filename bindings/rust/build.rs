@@ -1,33 +1,190 @@
+//! Builds or locates the xlnpwmon C library
+//!
+//! By default the bundled sources in `vendor/` are compiled and linked
+//! statically. With the `system-lib` feature an installed `libxlnpwmon` is
+//! linked dynamically instead; it is looked up in this order:
+//!
+//! 1. `XLNPWMON_LIB_DIR`, if set, is added to the native search path.
+//! 2. Otherwise pkg-config is queried for `xlnpwmon` (which honors
+//!    `PKG_CONFIG_PATH` and `PKG_CONFIG_SYSROOT_DIR`).
+//! 3. If pkg-config finds nothing, the linker's default search path is used.
+//!
+//! The installed library must be built from the same version as this crate.
+//!
+//! With the `prebuilt` feature nothing is compiled either: a static archive
+//! built with the user's own toolchain, e.g. a Vitis cross compiler and
+//! sysroot, is linked from the directory in `XLNPWMON_STATIC_LIB_DIR`. The
+//! archive is `libxlnpwmon.a`, or `libxlnpwmon_static.a` as built by the
+//! `xlnpwmon_static` CMake target. It must be built from `src/xlnpwmon.c` of
+//! the same version as this crate, without `XLNPWMON_MOCK`, and so export
+//! every `pm_*` function of `include/xlnpwmon/xlnpwmon.h` outside its mock
+//! section; `nm -g --defined-only libxlnpwmon.a | grep ' T pm_'` lists what an
+//! archive exports. It takes precedence over `system-lib`.
+//!
+//! The `mock` feature always builds the bundled sources, with the hwmon
+//! access replaced by synthetic sensors (`XLNPWMON_MOCK`), and takes
+//! precedence over `prebuilt` and `system-lib`.
+//!
+//! The bundled sources follow the optimization level and debug info of the
+//! Cargo profile; the `neon` feature also enables NEON on ARM targets.
+//!
+//! The `bindgen` feature additionally generates the raw declarations of the
+//! bundled header for `xlnpwmon::sys`, whichever library is linked.
+
 fn main() {
-    // let target = std::env::var("TARGET").unwrap_or_else(|_| String::from(""));
-    // let host = std::env::var("HOST").unwrap_or_else(|_| String::from(""));
-    
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=XLNPWMON_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=XLNPWMON_STATIC_LIB_DIR");
+
+    let mock = std::env::var_os("CARGO_FEATURE_MOCK").is_some();
+    if mock {
+        build_bundled(mock);
+    } else if std::env::var_os("CARGO_FEATURE_PREBUILT").is_some() {
+        link_prebuilt();
+    } else if std::env::var_os("CARGO_FEATURE_SYSTEM_LIB").is_some() {
+        link_system_lib();
+    } else {
+        build_bundled(mock);
+    }
+
+    println!("cargo:rustc-link-lib=pthread");
+    println!("cargo:rustc-link-lib=m");
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings(mock);
+}
+
+/// Writes the declarations of the bundled header to `$OUT_DIR/bindings.rs`,
+/// which `src/sys.rs` includes
+#[cfg(feature = "bindgen")]
+fn generate_bindings(mock: bool) {
+    let header = "vendor/include/xlnpwmon/xlnpwmon.h";
+    println!("cargo:rerun-if-changed={}", header);
+
+    let mut builder = bindgen::Builder::default()
+        .header(header)
+        .allowlist_function("pm_.*")
+        .allowlist_type("pm_.*")
+        .allowlist_var("(PM|XLNPWMON)_.*")
+        // Layouts are checked against the hand-written structs instead
+        .layout_tests(false);
+    if mock {
+        builder = builder.clang_arg("-DXLNPWMON_MOCK");
+    }
+
+    let out = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("bindings.rs");
+    builder
+        .generate()
+        .expect("failed to generate bindings for xlnpwmon.h")
+        .write_to_file(out)
+        .expect("failed to write the generated bindings");
+}
+
+/// Archive names looked up by the `prebuilt` feature, without `lib` and `.a`
+const PREBUILT_NAMES: [&str; 2] = ["xlnpwmon", "xlnpwmon_static"];
+
+fn link_prebuilt() {
+    let dir = match std::env::var_os("XLNPWMON_STATIC_LIB_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => panic!(
+            "the `prebuilt` feature needs XLNPWMON_STATIC_LIB_DIR set to the directory of a \
+             prebuilt libxlnpwmon.a"
+        ),
+    };
+    let name = PREBUILT_NAMES
+        .iter()
+        .find(|name| dir.join(format!("lib{}.a", name)).is_file())
+        .unwrap_or_else(|| {
+            panic!(
+                "XLNPWMON_STATIC_LIB_DIR={} contains neither libxlnpwmon.a nor libxlnpwmon_static.a",
+                dir.display()
+            )
+        });
+    println!("cargo:rerun-if-changed={}", dir.join(format!("lib{}.a", name)).display());
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib=static={}", name);
+}
+
+fn link_system_lib() {
+    if let Some(dir) = std::env::var_os("XLNPWMON_LIB_DIR") {
+        println!("cargo:rustc-link-search=native={}", dir.to_string_lossy());
+        println!("cargo:rustc-link-lib=dylib=xlnpwmon");
+        return;
+    }
+
+    #[cfg(feature = "system-lib")]
+    {
+        // pkg-config emits the link-search and link-lib lines itself
+        if pkg_config::Config::new().probe("xlnpwmon").is_ok() {
+            return;
+        }
+    }
+
+    println!("cargo:rustc-link-lib=dylib=xlnpwmon");
+}
+
+fn build_bundled(mock: bool) {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let host = std::env::var("HOST").unwrap_or_default();
+
     let mut build = cc::Build::new();
-    
+
     // 基本配置
     build.file("vendor/src/xlnpwmon.c")
         .include("vendor/include")
         .flag("-std=c99")
         .flag("-Wall")
         .flag("-Wextra");
-    
-    // // 如果是交叉编译到 ARM64
-    // if target.contains("aarch64") && !host.contains("aarch64") {
-    //     build.compiler("aarch64-linux-gnu-gcc");
-    //     println!("cargo:rustc-link-search=native=/usr/aarch64-linux-gnu/lib");
-    //     println!("cargo:rustc-link-lib=ncurses");
-    // } else {
-    //     println!("cargo:rustc-link-lib=ncurses");
-    // }
-    
+    if mock {
+        build.define("XLNPWMON_MOCK", None);
+    }
+
+    // Optimize like the Rust code of the profile, so release builds run the
+    // sampling loop and the statistics at -O2/-O3 and debug builds stay at
+    // -O0 with symbols. Warnings are reported, never turned into errors.
+    let opt_level = std::env::var("OPT_LEVEL").unwrap_or_else(|_| "0".to_string());
+    let debug = std::env::var("DEBUG").map_or(false, |debug| debug != "false" && debug != "0");
+    build.opt_level_str(&opt_level)
+        .debug(debug)
+        .warnings_into_errors(false);
+
+    // NEON for the statistics accumulation. It is part of the baseline on
+    // aarch64, where this only makes it explicit in case CFLAGS select an
+    // older -march, but must be enabled on 32-bit ARM.
+    if std::env::var_os("CARGO_FEATURE_NEON").is_some() {
+        match std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+            Ok("aarch64") => {
+                build.flag_if_supported("-march=armv8-a+simd");
+            }
+            Ok("arm") => {
+                build.flag_if_supported("-mfpu=neon");
+            }
+            _ => println!("cargo:warning=the `neon` feature only applies to ARM targets, ignoring it"),
+        }
+    }
+
+    // Cross-compiling, e.g. from an x86_64 host to an aarch64 Zynq/Versal
+    // board: cc picks the compiler for TARGET, in this order:
+    //   CC_<target> (e.g. CC_aarch64_unknown_linux_gnu=aarch64-linux-gnu-gcc),
+    //   TARGET_CC, CC, and finally the conventional `<triple>-gcc`.
+    // The target's C library must be installed for that compiler, and Cargo
+    // needs a matching linker, e.g.
+    //   CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER=aarch64-linux-gnu-gcc
+    if target != host {
+        let target_env = target.replace('-', "_");
+        println!("cargo:rerun-if-env-changed=CC_{}", target);
+        println!("cargo:rerun-if-env-changed=CC_{}", target_env);
+        println!("cargo:rerun-if-env-changed=TARGET_CC");
+        println!("cargo:rerun-if-env-changed=CFLAGS_{}", target_env);
+    }
+
     build.static_flag(true)
          .compile("xlnpwmon");
 
     println!("cargo:rustc-link-search=native={}", std::env::var("OUT_DIR").unwrap());
     println!("cargo:rustc-link-lib=static=xlnpwmon");
-    println!("cargo:rustc-link-lib=pthread");
 
     println!("cargo:rerun-if-changed=src/lib.rs");
     println!("cargo:rerun-if-changed=vendor/src/xlnpwmon.c");
-    println!("cargo:rerun-if-changed=vendor/include/xlnpwmon.h");
-} 
\ No newline at end of file
+    println!("cargo:rerun-if-changed=vendor/include/xlnpwmon/xlnpwmon.h");
+}
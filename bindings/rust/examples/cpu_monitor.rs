@@ -40,47 +40,19 @@ where
     // 设置采样频率为1000Hz
     monitor.set_sampling_frequency(1000)?;
     
-    // 重置统计数据
-    monitor.reset_statistics()?;
-    
-    // 开始采样
+    // 在采样期间执行任务，并等待一小段时间确保数据采集完整
     println!("开始功耗采样...");
-    monitor.start_sampling()?;
-    
-    // 执行任务
-    task_func();
-    
-    // 等待一小段时间确保数据采集完整
-    thread::sleep(Duration::from_micros(500000));
-    
-    // 停止采样
-    monitor.stop_sampling()?;
+    let ((), stats) = monitor.measure(|| {
+        task_func();
+        thread::sleep(Duration::from_micros(500000));
+    })?;
     
-    // 获取统计数据
-    let stats = monitor.get_statistics()?;
-    
-    // 打印总功耗统计信息
+    // 能耗 = 功率采样之和 / 采样频率
+    let frequency = monitor.get_sampling_frequency()?;
+
+    // 打印本次运行的统计报告
     println!("\n功耗统计信息:");
-    println!("总功耗:");
-    println!("  最小值: {:.2} W", stats.total.power.min);
-    println!("  最大值: {:.2} W", stats.total.power.max);
-    println!("  平均值: {:.2} W", stats.total.power.avg);
-    println!("  总能耗: {:.2} J", stats.total.power.total);
-    println!("  采样次数: {}", stats.total.power.count);
-    
-    // 打印各个传感器的功耗信息
-    println!("\n各传感器功耗信息:");
-    let sensor_count = monitor.get_sensor_count()?;
-    for i in 0..sensor_count {
-        let sensor = unsafe { &*stats.sensors.add(i as usize) };
-        let name = String::from_utf8_lossy(&sensor.name).trim_matches('\0').to_string();
-        println!("\n传感器: {}", name);
-        println!("  最小值: {:.2} W", sensor.power.min);
-        println!("  最大值: {:.2} W", sensor.power.max);
-        println!("  平均值: {:.2} W", sensor.power.avg);
-        println!("  总能耗: {:.2} J", sensor.power.total);
-        println!("  采样次数: {}", sensor.power.count);
-    }
+    print!("{}", stats.report(frequency));
     
     Ok(())
 }
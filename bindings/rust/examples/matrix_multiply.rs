@@ -1,8 +1,9 @@
-use xlnpwmon::PowerMonitor;
+use xlnpwmon::{OwnedPowerData, PowerMonitor};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
-use ndarray::{Array2, s};
-use ndarray::linalg::Dot;
+use ndarray::Array2;
 use rand::Rng;
 
 /// Matrix size for multiplication
@@ -17,23 +18,21 @@ fn matrix_multiply_thread(thread_id: usize) {
     println!("Thread {} starting execution...", thread_id);
 
     // Using ndarray for matrix multiplication
-    let mut rng = rand::thread_rng();
+    let mut rng = rand::rng();
     let mut a = Array2::zeros((MATRIX_SIZE, MATRIX_SIZE));
     let mut b = Array2::zeros((MATRIX_SIZE, MATRIX_SIZE));
     
     // Filling with random values
     for i in 0..MATRIX_SIZE {
         for j in 0..MATRIX_SIZE {
-            a[[i, j]] = rng.gen_range(-1.0..1.0);
-            b[[i, j]] = rng.gen_range(-1.0..1.0);
+            a[[i, j]] = rng.random_range(-1.0..1.0);
+            b[[i, j]] = rng.random_range(-1.0..1.0);
         }
     }
 
     // Performing multiple matrix multiplications
-    let mut c = Array2::zeros((MATRIX_SIZE, MATRIX_SIZE));
     for _ in 0..NUM_ITERATIONS {
-        c = a.dot(&b);
-        a = c.clone();
+        a = a.dot(&b);
     }
 
     println!("Thread {} completed execution", thread_id);
@@ -58,15 +57,30 @@ fn main() {
     
     // Recording start time
     let start_time = std::time::Instant::now();
+
+    // Annotating the workload phases
+    let mut phases = monitor.phase_recorder();
+    phases.mark("multiply").unwrap();
     
     // Creating and starting threads
+    let finished = Arc::new(AtomicUsize::new(0));
     let mut handles = vec![];
     for i in 0..NUM_THREADS {
+        let finished = Arc::clone(&finished);
         let handle = thread::spawn(move || {
             matrix_multiply_thread(i);
+            finished.fetch_add(1, Ordering::SeqCst);
         });
         handles.push(handle);
     }
+
+    // Collecting a power time series while the threads run
+    let mut series: Vec<(Duration, OwnedPowerData)> = Vec::new();
+    while finished.load(Ordering::SeqCst) < NUM_THREADS {
+        let sample = monitor.get_latest_data().unwrap().to_owned();
+        series.push((start_time.elapsed(), sample));
+        thread::sleep(Duration::from_millis(100));
+    }
     
     // Waiting for all threads to complete
     for handle in handles {
@@ -78,33 +92,37 @@ fn main() {
     println!("\nTotal execution time: {:.2} seconds", total_time);
     
     // Waiting a short period to ensure data collection is complete
+    phases.mark("cooldown").unwrap();
     thread::sleep(Duration::from_micros(500000));
+    let phases = phases.finish().unwrap();
     
     // Stopping sampling
     monitor.stop_sampling().unwrap();
+
+    // Printing the collected time series
+    println!("\nTotal Power Time Series ({} samples):", series.len());
+    for (elapsed, sample) in &series {
+        println!("  {:>6.2} s  {:>8.2} W", elapsed.as_secs_f64(), sample.total.power);
+    }
+
+    // Printing per-phase power consumption
+    println!("\nPower Consumption per Phase:");
+    for phase in &phases {
+        println!(
+            "  {:<10} {:>6.2} s  {:>8.2} W avg  {:>8.2} J  ({} samples)",
+            phase.name,
+            phase.duration.as_secs_f64(),
+            phase.avg_power,
+            phase.energy,
+            phase.samples
+        );
+    }
     
     // Getting statistics
-    let stats = monitor.get_statistics().unwrap();
+    let stats = monitor.get_statistics_owned().unwrap();
+    let frequency = monitor.get_sampling_frequency().unwrap();
     
-    // Printing total power consumption statistics
+    // Printing the run report
     println!("\nPower Consumption Statistics:");
-    println!("Total Power Consumption:");
-    println!("  Minimum Value: {:.2} W", stats.total.power.min);
-    println!("  Maximum Value: {:.2} W", stats.total.power.max);
-    println!("  Average Value: {:.2} W", stats.total.power.avg);
-    println!("  Total Energy Consumption: {:.2} J", stats.total.power.total);
-    println!("  Sample Count: {}", stats.total.power.count);
-    
-    // Printing power consumption information for each sensor
-    println!("\nPower Consumption Information for Each Sensor:");
-    for i in 0..stats.sensor_count {
-        let sensor = unsafe { &*stats.sensors.add(i as usize) };
-        let name = String::from_utf8_lossy(&sensor.name).trim_matches('\0').to_string();
-        println!("\nSensor: {}", name);
-        println!("  Minimum Value: {:.2} W", sensor.power.min);
-        println!("  Maximum Value: {:.2} W", sensor.power.max);
-        println!("  Average Value: {:.2} W", sensor.power.avg);
-        println!("  Total Energy Consumption: {:.2} J", sensor.power.total);
-        println!("  Sample Count: {}", sensor.power.count);
-    }
+    print!("{}", stats.report(frequency));
 } 
\ No newline at end of file
@@ -0,0 +1,24 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use xlnpwmon::PowerMonitor;
+
+fn main() {
+    let monitor = Arc::new(PowerMonitor::new().unwrap());
+
+    // 设置采样频率为10Hz
+    monitor.set_sampling_frequency(10).unwrap();
+
+    // /metrics 只读取最新数据，因此必须先开始采样
+    monitor.start_sampling().unwrap();
+
+    // 在 9100 端口提供 Prometheus 指标，阻塞当前线程
+    let addr: SocketAddr = "0.0.0.0:9100".parse().unwrap();
+    println!("Serving metrics on http://{}/metrics", addr);
+    if let Err(e) = xlnpwmon::serve_metrics(Arc::clone(&monitor), addr) {
+        eprintln!("错误: {}", e);
+    }
+
+    // 停止采样
+    monitor.stop_sampling().unwrap();
+}
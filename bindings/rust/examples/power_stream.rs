@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use xlnpwmon::PowerMonitor;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let monitor = PowerMonitor::new().unwrap();
+    
+    // 设置采样频率为10Hz
+    monitor.set_sampling_frequency(10).unwrap();
+    
+    // 开始采样，guard 离开作用域时自动停止
+    let sampling = monitor.start_sampling_guarded().unwrap();
+    let mut stream = sampling.sample_stream();
+    
+    // 在5秒内打印每个样本的总功耗
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(data) => println!("{}", data.total),
+                Err(e) => eprintln!("错误: {:?}", e),
+            }
+        }
+    })
+    .await;
+    
+    // 停止采样，流随之结束
+    sampling.stop().unwrap();
+}
@@ -0,0 +1,94 @@
+//! Idle baselines for attributing power to a workload
+//!
+//! A board draws static power whether or not a workload runs. Capturing the
+//! average power of a quiet window as a [`Baseline`] and subtracting it from
+//! later statistics leaves only the dynamic power the workload added.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{Error, OwnedPowerStats, PowerMonitor, Stats};
+
+/// Average idle power of the board and of every sensor
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Baseline {
+    /// Average total power in watts
+    pub total_power: f64,
+    /// Average power of each sensor in watts, keyed by sensor name
+    pub sensors: BTreeMap<String, f64>,
+}
+
+impl Baseline {
+    /// Creates a baseline from the average power in `stats`
+    ///
+    /// Sensors without samples are left out.
+    pub fn from_stats(stats: &OwnedPowerStats) -> Self {
+        Self {
+            total_power: stats.total.power.avg,
+            sensors: stats
+                .sensors
+                .iter()
+                .filter(|sensor| sensor.power.count > 0)
+                .map(|sensor| (sensor.name().into_owned(), sensor.power.avg))
+                .collect(),
+        }
+    }
+
+    /// Returns the baseline power of a sensor, if it was captured
+    pub fn power(&self, name: &str) -> Option<f64> {
+        self.sensors.get(name).copied()
+    }
+}
+
+/// Subtracts a constant power from statistics, clamping at zero
+fn subtract(stats: &mut Stats, baseline: f64) {
+    if stats.count == 0 {
+        return;
+    }
+    stats.min = (stats.min - baseline).max(0.0);
+    stats.max = (stats.max - baseline).max(0.0);
+    stats.avg = (stats.avg - baseline).max(0.0);
+    stats.total = (stats.total - baseline * stats.count as f64).max(0.0);
+}
+
+impl OwnedPowerStats {
+    /// Returns a copy with the baseline power subtracted
+    ///
+    /// The min, max, average and sum of the power statistics of every sensor
+    /// in the baseline, and of the total, are reduced by the baseline power;
+    /// values that would become negative are clamped to zero. Voltage and
+    /// current statistics, sample counts and sensors missing from the
+    /// baseline are left untouched.
+    pub fn minus_baseline(&self, baseline: &Baseline) -> Self {
+        let mut stats = self.clone();
+        subtract(&mut stats.total.power, baseline.total_power);
+        for sensor in &mut stats.sensors {
+            if let Some(power) = baseline.power(&sensor.name()) {
+                subtract(&mut sensor.power, power);
+            }
+        }
+        stats
+    }
+}
+
+impl PowerMonitor {
+    /// Captures the average power over a quiet window
+    ///
+    /// Samples for `duration` like [`PowerMonitor::sample_for`], which resets
+    /// the statistics. The window should span several sampling periods and
+    /// nothing else should load the board meanwhile.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Length of the idle window
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Baseline)` - The average idle power
+    /// * `Err(Error::AlreadyRunning)` - If sampling is already active
+    /// * `Err(Error)` - An error code if sampling could not be controlled
+    pub fn capture_baseline(&self, duration: Duration) -> Result<Baseline, Error> {
+        Ok(Baseline::from_stats(&self.sample_for(duration)?))
+    }
+}
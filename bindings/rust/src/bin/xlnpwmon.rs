@@ -0,0 +1,153 @@
+//! Command-line power monitor
+//!
+//! Samples all sensors and prints a table of the latest readings followed by
+//! the total power and its statistics over the capture:
+//!
+//! ```text
+//! xlnpwmon [--freq <hz>] [--duration <seconds>] [--csv <path>] [--watch]
+//! ```
+//!
+//! Without `--watch` the table is printed once, after `--duration` seconds
+//! (default 1). With `--watch` it is redrawn twice a second until the
+//! duration has passed, or until interrupted if no duration is given.
+
+use std::env;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use xlnpwmon::{Error, PowerMonitor, DEFAULT_FREQUENCY_HZ};
+
+const USAGE: &str = "usage: xlnpwmon [--freq <hz>] [--duration <seconds>] [--csv <path>] [--watch]";
+/// Capture length without `--duration` outside watch mode
+const DEFAULT_DURATION: Duration = Duration::from_secs(1);
+/// Time between redraws in watch mode
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+/// Moves the cursor home and clears the terminal
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// Parsed command-line options
+struct Options {
+    frequency_hz: i32,
+    duration: Option<Duration>,
+    csv: Option<String>,
+    watch: bool,
+}
+
+/// Parses the arguments following the program name
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        frequency_hz: DEFAULT_FREQUENCY_HZ,
+        duration: None,
+        csv: None,
+        watch: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--freq" => {
+                let value = args.next().ok_or("--freq needs a value")?;
+                options.frequency_hz = value
+                    .parse()
+                    .map_err(|_| format!("invalid frequency: {}", value))?;
+            }
+            "--duration" => {
+                let value = args.next().ok_or("--duration needs a value")?;
+                let seconds: f64 = value
+                    .parse()
+                    .ok()
+                    .filter(|s: &f64| s.is_finite() && *s >= 0.0)
+                    .ok_or_else(|| format!("invalid duration: {}", value))?;
+                options.duration = Some(Duration::from_secs_f64(seconds));
+            }
+            "--csv" => {
+                options.csv = Some(args.next().ok_or("--csv needs a path")?);
+            }
+            "--watch" => options.watch = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {}\n{}", other, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+/// Prints the latest reading of every sensor and the total power statistics
+fn print_table(monitor: &PowerMonitor) -> Result<(), Error> {
+    let sensors = monitor.latest_sensors()?;
+    let latest = monitor.get_latest_owned()?;
+    let stats = monitor.get_statistics_owned()?;
+
+    println!(
+        "{:<16} {:<8} {:>10} {:>10} {:>10}  STATUS",
+        "SENSOR", "TYPE", "VOLTAGE", "CURRENT", "POWER"
+    );
+    for sensor in &sensors {
+        let status = if sensor.online { sensor.status.as_str() } else { "OFFLINE" };
+        println!(
+            "{:<16} {:<8} {:>8.3} V {:>8.3} A {:>8.3} W  {}",
+            sensor.name,
+            format!("{:?}", sensor.kind),
+            sensor.voltage,
+            sensor.current,
+            sensor.power,
+            status
+        );
+    }
+    let power = &stats.total.power;
+    println!();
+    println!(
+        "Total: {:.3} W (avg {:.3} W, min {:.3} W, max {:.3} W over {} samples)",
+        latest.total.power, power.avg, power.min, power.max, power.count
+    );
+    Ok(())
+}
+
+/// Turns a library error into a message saying what failed
+fn describe(what: &'static str) -> impl Fn(Error) -> String {
+    move |e| format!("{}: {}", what, e)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let monitor = PowerMonitor::new().map_err(describe("failed to initialize"))?;
+    monitor
+        .set_sampling_frequency(options.frequency_hz)
+        .map_err(describe("failed to set the frequency"))?;
+    let recorder = match &options.csv {
+        Some(path) => Some(monitor.record_csv(path).map_err(describe("failed to create the CSV file"))?),
+        None => None,
+    };
+
+    let sampling = monitor.start_sampling_guarded().map_err(describe("failed to start sampling"))?;
+    let start = Instant::now();
+    if options.watch {
+        loop {
+            let remaining = options.duration.map(|d| d.saturating_sub(start.elapsed()));
+            thread::sleep(remaining.map_or(REFRESH_INTERVAL, |r| r.min(REFRESH_INTERVAL)));
+            print!("{}", CLEAR_SCREEN);
+            print_table(&monitor).map_err(describe("failed to read the sensors"))?;
+            if remaining.map_or(false, |r| r <= REFRESH_INTERVAL) {
+                break;
+            }
+        }
+        sampling.stop().map_err(describe("failed to stop sampling"))?;
+    } else {
+        thread::sleep(options.duration.unwrap_or(DEFAULT_DURATION));
+        sampling.stop().map_err(describe("failed to stop sampling"))?;
+        print_table(&monitor).map_err(describe("failed to read the sensors"))?;
+    }
+
+    if let Some(recorder) = recorder {
+        recorder.finish().map_err(describe("failed to write the CSV file"))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(run);
+    if let Err(message) = result {
+        eprintln!("xlnpwmon: {}", message);
+        process::exit(1);
+    }
+}
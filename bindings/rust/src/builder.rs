@@ -0,0 +1,278 @@
+//! Builder for configured [`PowerMonitor`] instances
+
+use std::collections::BTreeMap;
+
+use crate::device::InitSource;
+use crate::owned::Processing;
+use crate::{Error, FullScale, MonitorConfig, PowerMonitor, SensorThresholds};
+
+/// Builds a [`PowerMonitor`] with optional processing settings
+///
+/// All settings are applied by [`PowerMonitorBuilder::build`], which only
+/// returns a monitor once every one of them has been accepted, so no
+/// partially configured monitor is ever observable.
+///
+/// ```no_run
+/// use xlnpwmon::PowerMonitorBuilder;
+///
+/// let monitor = PowerMonitorBuilder::new()
+///     .sampling_frequency(100)
+///     .history_capacity(1000)
+///     .quantize(2)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PowerMonitorBuilder {
+    quantize: Option<u32>,
+    reset_on_start: bool,
+    sampling_frequency: Option<i32>,
+    groups: BTreeMap<String, Vec<String>>,
+    full_scales: BTreeMap<String, FullScale>,
+    clipping_policy: Option<(f64, usize)>,
+    history_capacity: usize,
+    sensors: Option<Vec<String>>,
+    thresholds: BTreeMap<String, SensorThresholds>,
+}
+
+/// The common settings of a [`PowerMonitor`]
+///
+/// Covers the sampling frequency, the history capacity and a sensor
+/// allowlist; use [`PowerMonitorBuilder`] for the remaining settings.
+/// [`PowerMonitor::new`] is a shorthand for
+/// `PowerMonitorConfig::default().build()`.
+///
+/// ```no_run
+/// use xlnpwmon::PowerMonitorConfig;
+///
+/// let monitor = PowerMonitorConfig::new()
+///     .frequency_hz(100)
+///     .history_capacity(1000)
+///     .sensors(&["VCCINT", "TOTAL_POWER"])
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerMonitorConfig {
+    frequency_hz: Option<i32>,
+    history_capacity: usize,
+    sensors: Option<Vec<String>>,
+}
+
+impl PowerMonitorConfig {
+    /// Creates a configuration with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sampling frequency in Hz, see [`PowerMonitor::set_sampling_frequency`]
+    pub fn frequency_hz(mut self, frequency_hz: i32) -> Self {
+        self.frequency_hz = Some(frequency_hz);
+        self
+    }
+
+    /// Sets the number of samples retained, see [`PowerMonitor::set_history_capacity`]
+    pub fn history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity;
+        self
+    }
+
+    /// Restricts processed outputs to the named sensors, see [`PowerMonitorBuilder::sensors`]
+    pub fn sensors(mut self, names: &[&str]) -> Self {
+        self.sensors = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Initializes the library and applies the configured settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerMonitor)` - A new, configured power monitor instance
+    /// * `Err(Error::InvalidFrequency)` - If the sampling frequency is out of
+    ///   range; a non-positive one is rejected before the library is
+    ///   initialized
+    /// * `Err(Error::NoSensors)` - If a sensor in the allowlist does not exist
+    /// * `Err(Error)` - An error code if initialization fails or another
+    ///   setting is rejected
+    pub fn build(self) -> Result<PowerMonitor, Error> {
+        PowerMonitorBuilder::from(self).build()
+    }
+}
+
+impl From<PowerMonitorConfig> for PowerMonitorBuilder {
+    fn from(config: PowerMonitorConfig) -> Self {
+        Self {
+            sampling_frequency: config.frequency_hz,
+            history_capacity: config.history_capacity,
+            sensors: config.sensors,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for PowerMonitorBuilder {
+    fn default() -> Self {
+        Self {
+            quantize: None,
+            reset_on_start: true,
+            sampling_frequency: None,
+            groups: BTreeMap::new(),
+            full_scales: BTreeMap::new(),
+            clipping_policy: None,
+            history_capacity: 0,
+            sensors: None,
+            thresholds: BTreeMap::new(),
+        }
+    }
+}
+
+impl PowerMonitorBuilder {
+    /// Creates a builder with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder that reproduces an exported configuration
+    ///
+    /// See [`PowerMonitor::export_config`].
+    pub fn from_config(config: MonitorConfig) -> Self {
+        Self {
+            quantize: config.quantize,
+            reset_on_start: config.reset_on_start,
+            sampling_frequency: config.sampling_frequency_hz,
+            groups: config.groups,
+            full_scales: config.full_scales,
+            clipping_policy: Some((config.clipping_fraction, config.clipping_window)),
+            history_capacity: config.history_capacity,
+            sensors: config.sensors,
+            thresholds: config.thresholds,
+        }
+    }
+
+    /// Rounds processed voltage, current and power values to `decimals` places
+    ///
+    /// This only affects the processed path, i.e. owned outputs such as
+    /// [`PowerMonitor::get_latest_owned`] and
+    /// [`PowerMonitor::get_statistics_owned`]. The raw
+    /// [`PowerMonitor::get_latest_data`] and [`PowerMonitor::get_statistics`]
+    /// accessors always report unmodified values.
+    pub fn quantize(mut self, decimals: u32) -> Self {
+        self.quantize = Some(decimals);
+        self
+    }
+
+    /// Resets statistics every time sampling starts (default: `true`)
+    ///
+    /// With the default, each [`PowerMonitor::start_sampling`] begins a clean
+    /// capture, so statistics are never polluted by a previous run. Pass
+    /// `false` to accumulate statistics across several start/stop cycles.
+    /// Monitors created with [`PowerMonitor::new`] use the same default.
+    pub fn reset_on_start(mut self, reset: bool) -> Self {
+        self.reset_on_start = reset;
+        self
+    }
+
+    /// Sets the sampling frequency in Hz, see [`PowerMonitor::set_sampling_frequency`]
+    pub fn sampling_frequency(mut self, frequency_hz: i32) -> Self {
+        self.sampling_frequency = Some(frequency_hz);
+        self
+    }
+
+    /// Defines a sensor group, see [`PowerMonitor::define_group`]
+    pub fn group(mut self, group_name: &str, members: &[&str]) -> Self {
+        let members = members.iter().map(|m| m.to_string()).collect();
+        self.groups.insert(group_name.to_string(), members);
+        self
+    }
+
+    /// Sets the full scale of a sensor, see [`PowerMonitor::set_sensor_full_scale`]
+    pub fn sensor_full_scale(mut self, name: &str, v_fs: f64, i_fs: f64) -> Self {
+        self.full_scales.insert(
+            name.to_string(),
+            FullScale {
+                voltage: v_fs,
+                current: i_fs,
+            },
+        );
+        self
+    }
+
+    /// Sets the thresholds of a sensor, see [`PowerMonitor::set_sensor_thresholds`]
+    pub fn sensor_thresholds(mut self, name: &str, warning: f64, critical: f64) -> Self {
+        self.thresholds.insert(name.to_string(), SensorThresholds { warning, critical });
+        self
+    }
+
+    /// Sets the clipping detection policy, see [`PowerMonitor::set_clipping_policy`]
+    pub fn clipping_policy(mut self, fraction: f64, window: usize) -> Self {
+        self.clipping_policy = Some((fraction, window));
+        self
+    }
+
+    /// Sets the number of samples retained, see [`PowerMonitor::set_history_capacity`]
+    pub fn history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity;
+        self
+    }
+
+    /// Restricts processed outputs to the named sensors
+    ///
+    /// Like [`PowerMonitorBuilder::quantize`], this only affects the
+    /// processed path: sensors not in the list are left out of owned outputs
+    /// such as [`PowerMonitor::get_latest_owned`] and
+    /// [`PowerMonitor::get_statistics_owned`]. The virtual totals are still
+    /// computed over every sensor, so list them explicitly to keep them.
+    pub fn sensors(mut self, names: &[&str]) -> Self {
+        self.sensors = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Initializes the library and applies the configured settings
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerMonitor)` - A new, configured power monitor instance
+    /// * `Err(Error::InvalidFrequency)` - If the sampling frequency is out of
+    ///   range; a non-positive one is rejected before the library is
+    ///   initialized
+    /// * `Err(Error::NoSensors)` - If a sensor in the allowlist does not exist
+    /// * `Err(Error)` - An error code if initialization fails or another
+    ///   setting is rejected
+    pub fn build(self) -> Result<PowerMonitor, Error> {
+        if matches!(self.sampling_frequency, Some(frequency_hz) if frequency_hz <= 0) {
+            return Err(Error::InvalidFrequency);
+        }
+        let mut monitor = PowerMonitor::from_source(InitSource::Default)?;
+        monitor.reset_on_start = self.reset_on_start;
+        if let Some(frequency_hz) = self.sampling_frequency {
+            monitor.set_sampling_frequency(frequency_hz)?;
+        }
+        if let Some(sensors) = &self.sensors {
+            let names = monitor.sensor_names()?;
+            if !sensors.iter().all(|sensor| names.contains(sensor)) {
+                return Err(Error::NoSensors);
+            }
+        }
+        monitor.processing = Processing {
+            quantize: self.quantize,
+            sensors: self.sensors,
+        };
+        if self.history_capacity > 0 {
+            monitor.set_history_capacity(self.history_capacity)?;
+        }
+        for (name, members) in &self.groups {
+            let members: Vec<&str> = members.iter().map(String::as_str).collect();
+            monitor.define_group(name, &members)?;
+        }
+        for (name, full_scale) in &self.full_scales {
+            monitor.set_sensor_full_scale(name, full_scale.voltage, full_scale.current)?;
+        }
+        if let Some((fraction, window)) = self.clipping_policy {
+            monitor.set_clipping_policy(fraction, window)?;
+        }
+        for (name, thresholds) in &self.thresholds {
+            monitor.set_sensor_thresholds(name, thresholds.warning, thresholds.critical)?;
+        }
+        Ok(monitor)
+    }
+}
@@ -0,0 +1,222 @@
+//! Detection of clipped (railed) sensor readings
+//!
+//! Power monitors such as the INA3221 have a finite measurement range. A
+//! reading pinned at the top of that range means the real value is larger
+//! than what was recorded, which silently invalidates statistics and energy
+//! totals. The [`ClippingDetector`] watches every sample for sensors sitting
+//! near their configured full scale for a sustained number of samples.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Error, PowerMonitor, SensorData};
+
+/// Default fraction of full scale at which a reading counts as pinned
+pub const DEFAULT_CLIPPING_FRACTION: f64 = 0.98;
+
+/// Default number of consecutive pinned samples before a sensor is reported
+pub const DEFAULT_CLIPPING_WINDOW: usize = 10;
+
+/// Full-scale range of a sensor
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullScale {
+    /// Full-scale bus voltage in volts
+    pub voltage: f64,
+    /// Full-scale current in amperes
+    pub current: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Watch {
+    full_scale: FullScale,
+    run: usize,
+    clipped: bool,
+}
+
+/// Tracks sustained near-full-scale readings per sensor
+///
+/// A sensor is reported once its voltage or current magnitude has been at
+/// or above `fraction` of the full scale for `window` consecutive samples.
+/// The report is latched until [`ClippingDetector::clear`] is called, since
+/// any clipped sample already affects the accumulated statistics.
+#[derive(Debug, Clone)]
+pub struct ClippingDetector {
+    fraction: f64,
+    window: usize,
+    watches: HashMap<String, Watch>,
+}
+
+impl Default for ClippingDetector {
+    fn default() -> Self {
+        Self {
+            fraction: DEFAULT_CLIPPING_FRACTION,
+            window: DEFAULT_CLIPPING_WINDOW,
+            watches: HashMap::new(),
+        }
+    }
+}
+
+impl ClippingDetector {
+    /// Creates a detector with the default fraction and window
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the detection policy
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - Fraction of full scale that counts as pinned, in (0, 1]
+    /// * `window` - Consecutive pinned samples required, at least 1
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If either value is out of range
+    pub fn set_policy(&mut self, fraction: f64, window: usize) -> Result<(), Error> {
+        if !(fraction > 0.0 && fraction <= 1.0) || window == 0 {
+            return Err(Error::InvalidArgument);
+        }
+        self.fraction = fraction;
+        self.window = window;
+        Ok(())
+    }
+
+    /// Sets the full-scale voltage and current of a sensor
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If a full-scale value is not positive
+    pub fn set_full_scale(&mut self, name: &str, voltage_fs: f64, current_fs: f64) -> Result<(), Error> {
+        if !(voltage_fs > 0.0 && current_fs > 0.0) {
+            return Err(Error::InvalidArgument);
+        }
+        self.watches.insert(
+            name.to_string(),
+            Watch {
+                full_scale: FullScale {
+                    voltage: voltage_fs,
+                    current: current_fs,
+                },
+                run: 0,
+                clipped: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the fraction of full scale that counts as pinned
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Returns the number of consecutive pinned samples required
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the configured full scales, keyed by sensor name
+    pub fn full_scales(&self) -> BTreeMap<String, FullScale> {
+        self.watches
+            .iter()
+            .map(|(name, watch)| (name.clone(), watch.full_scale))
+            .collect()
+    }
+
+    /// Feeds one sample of sensor readings into the detector
+    pub fn observe(&mut self, sensors: &[SensorData]) {
+        if self.watches.is_empty() {
+            return;
+        }
+        for sensor in sensors {
+            let name = sensor.name();
+            let watch = match self.watches.get_mut(name.as_ref()) {
+                Some(watch) => watch,
+                None => continue,
+            };
+            let pinned = sensor.online
+                && (sensor.voltage.abs() >= self.fraction * watch.full_scale.voltage
+                    || sensor.current.abs() >= self.fraction * watch.full_scale.current);
+            watch.run = if pinned { watch.run + 1 } else { 0 };
+            if watch.run >= self.window {
+                watch.clipped = true;
+            }
+        }
+    }
+
+    /// Returns the names of the sensors that have clipped, sorted by name
+    pub fn clipped(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .watches
+            .iter()
+            .filter(|(_, watch)| watch.clipped)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Clears all latched reports and pinned-sample runs
+    pub fn clear(&mut self) {
+        for watch in self.watches.values_mut() {
+            watch.run = 0;
+            watch.clipped = false;
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Sets the full-scale voltage and current of a sensor for clipping detection
+    ///
+    /// Only sensors with a configured full scale are checked, see
+    /// [`PowerMonitor::clipping_report`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name
+    /// * `v_fs` - Full-scale bus voltage in volts
+    /// * `i_fs` - Full-scale current in amperes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If `name` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If a full-scale value is not positive
+    pub fn set_sensor_full_scale(&self, name: &str, v_fs: f64, i_fs: f64) -> Result<(), Error> {
+        if !self.sensor_names()?.iter().any(|sensor| sensor == name) {
+            return Err(Error::NoSensors);
+        }
+        self.clipping
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_full_scale(name, v_fs, i_fs)
+    }
+
+    /// Sets the fraction of full scale and the number of consecutive samples
+    /// after which a sensor is reported as clipped
+    ///
+    /// The defaults are [`DEFAULT_CLIPPING_FRACTION`] and
+    /// [`DEFAULT_CLIPPING_WINDOW`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If `fraction` is outside (0, 1] or `window` is 0
+    pub fn set_clipping_policy(&self, fraction: f64, window: usize) -> Result<(), Error> {
+        self.clipping
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .set_policy(fraction, window)
+    }
+
+    /// Lists the sensors whose readings have been pinned near full scale
+    ///
+    /// A sensor is listed once it sat at the configured fraction of its full
+    /// scale for the configured window of consecutive samples. Reports stay
+    /// latched until [`PowerMonitor::reset_statistics`], because the affected
+    /// statistics are unreliable until then.
+    pub fn clipping_report(&self) -> Vec<String> {
+        self.clipping.lock().unwrap_or_else(|e| e.into_inner()).clipped()
+    }
+}
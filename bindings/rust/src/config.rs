@@ -0,0 +1,89 @@
+//! Exportable monitor configuration
+//!
+//! A [`MonitorConfig`] captures every setting of a [`PowerMonitor`] that can
+//! be reproduced on another machine. With the `serde` feature it can be
+//! stored in a file and version-controlled next to the workload it measures.
+
+use std::collections::BTreeMap;
+
+use crate::{FullScale, PowerMonitor, SensorThresholds, DEFAULT_CLIPPING_FRACTION, DEFAULT_CLIPPING_WINDOW};
+
+/// The reproducible settings of a [`PowerMonitor`]
+///
+/// Obtained from [`PowerMonitor::export_config`] and turned back into a
+/// monitor with [`crate::PowerMonitorBuilder::from_config`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorConfig {
+    /// Sampling frequency in Hz, `None` keeps [`crate::DEFAULT_FREQUENCY_HZ`]
+    pub sampling_frequency_hz: Option<i32>,
+    /// Decimal places processed outputs are rounded to
+    pub quantize: Option<u32>,
+    /// Whether statistics are reset when sampling starts
+    pub reset_on_start: bool,
+    /// Named sensor groups and their members
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// Full scales used for clipping detection, keyed by sensor name
+    pub full_scales: BTreeMap<String, FullScale>,
+    /// Fraction of full scale at which a reading counts as pinned
+    pub clipping_fraction: f64,
+    /// Consecutive pinned samples before a sensor counts as clipped
+    pub clipping_window: usize,
+    /// Number of samples retained in the history, 0 if disabled
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub history_capacity: usize,
+    /// Sensors kept in processed outputs, `None` to keep all
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sensors: Option<Vec<String>>,
+    /// Warning and critical thresholds, keyed by sensor name
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thresholds: BTreeMap<String, SensorThresholds>,
+}
+
+impl Default for MonitorConfig {
+    /// The configuration of a monitor created with [`PowerMonitor::new`]
+    fn default() -> Self {
+        Self {
+            sampling_frequency_hz: None,
+            quantize: None,
+            reset_on_start: true,
+            groups: BTreeMap::new(),
+            full_scales: BTreeMap::new(),
+            clipping_fraction: DEFAULT_CLIPPING_FRACTION,
+            clipping_window: DEFAULT_CLIPPING_WINDOW,
+            history_capacity: 0,
+            sensors: None,
+            thresholds: BTreeMap::new(),
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Exports the current settings of this monitor
+    ///
+    /// The sampling frequency is `None` only if it cannot be read back from
+    /// the library, and the thresholds are empty only if none are set or
+    /// they cannot be read back.
+    pub fn export_config(&self) -> MonitorConfig {
+        let groups = self
+            .groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, members)| (name.clone(), members.clone()))
+            .collect();
+        let clipping = self.clipping.lock().unwrap_or_else(|e| e.into_inner());
+        MonitorConfig {
+            sampling_frequency_hz: self.get_sampling_frequency().ok(),
+            quantize: self.processing.quantize,
+            reset_on_start: self.reset_on_start,
+            groups,
+            full_scales: clipping.full_scales(),
+            clipping_fraction: clipping.fraction(),
+            clipping_window: clipping.window(),
+            history_capacity: self.history_capacity(),
+            sensors: self.processing.sensors.clone(),
+            thresholds: self.sensor_thresholds().unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Causes of failed file accesses behind an [`Error`]
+//!
+//! Error codes say that a file could not be accessed, not which one or why.
+//! The cause of the last such failure is kept per thread, like `errno`, and
+//! returned by [`Error::context`] for the error it belongs to.
+
+use std::cell::RefCell;
+use std::io;
+use std::path::Path;
+
+use crate::{pm_get_last_file_error, Error};
+
+/// Room for a sysfs path, as `PATH_MAX` on Linux
+const MAX_PATH_LEN: usize = 4096;
+
+/// Cause of the most recent error of this thread
+struct ErrorContext {
+    /// Code of the error the cause belongs to
+    code: i32,
+    /// OS error number, if the failure came from the OS
+    errno: Option<i32>,
+    /// What failed and why, usually a path and the OS message
+    message: String,
+}
+
+thread_local! {
+    static LAST_CONTEXT: RefCell<Option<ErrorContext>> = const { RefCell::new(None) };
+}
+
+fn set_context(context: Option<ErrorContext>) {
+    LAST_CONTEXT.with(|last| *last.borrow_mut() = context);
+}
+
+/// Discards the cause of an earlier error, for an error that has none
+pub(crate) fn clear_context() {
+    set_context(None);
+}
+
+/// Takes the failed file access recorded by the C library for the error
+/// code a call just returned
+pub(crate) fn record_c_context(code: i32) {
+    let mut errnum = 0;
+    let mut path = vec![0u8; MAX_PATH_LEN];
+    let result = unsafe { pm_get_last_file_error(&mut errnum, path.as_mut_ptr().cast(), path.len() as i32) };
+    let context = if result == 0 && errnum != 0 {
+        let len = path.iter().position(|&b| b == 0).unwrap_or(path.len());
+        Some(ErrorContext {
+            code,
+            errno: Some(errnum),
+            message: format!(
+                "{}: {}",
+                String::from_utf8_lossy(&path[..len]),
+                io::Error::from_raw_os_error(errnum)
+            ),
+        })
+    } else {
+        None
+    };
+    set_context(context);
+}
+
+/// Turns a failed access of `path` into `Error::FileAccess`, keeping the
+/// cause for [`Error::context`]
+pub(crate) fn file_access_error(path: &Path, error: io::Error) -> Error {
+    set_context(Some(ErrorContext {
+        code: i32::from(Error::FileAccess),
+        errno: error.raw_os_error(),
+        message: format!("{}: {}", path.display(), error),
+    }));
+    Error::FileAccess
+}
+
+/// Attaches a cause that did not come from the OS to `error`, for
+/// [`Error::context`]
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+pub(crate) fn with_context(error: Error, message: String) -> Error {
+    set_context(Some(ErrorContext {
+        code: error.code(),
+        errno: None,
+        message,
+    }));
+    error
+}
+
+/// Takes the OS error number behind `error`, if it was recorded,
+/// discarding the recorded cause
+pub(crate) fn take_os_errno(error: &Error) -> Option<i32> {
+    LAST_CONTEXT.with(|last| {
+        let mut last = last.borrow_mut();
+        match &*last {
+            Some(context) if context.code == error.code() => last.take().and_then(|context| context.errno),
+            _ => None,
+        }
+    })
+}
+
+impl Error {
+    /// Gets the path and cause behind the error, if known
+    ///
+    /// Available for errors caused by a file that could not be accessed,
+    /// e.g. `NoSensors` or `FileAccess` from [`crate::PowerMonitor::new`] or
+    /// `FileAccess` from [`crate::PowerMonitor::record_csv`], and for preset
+    /// files rejected by `PowerMonitor::apply_thresholds_from_file`. Like
+    /// `errno`, the cause is kept per thread and only for the most recent
+    /// error returned by this crate: any later failed library call discards
+    /// it, as does converting the error into an [`std::io::Error`]. Call
+    /// this on the thread that got the error, before making further calls.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The path and the OS message, e.g.
+    ///   `/sys/class/hwmon: Permission denied (os error 13)`
+    /// * `None` - If no cause was recorded for this error
+    pub fn context(&self) -> Option<String> {
+        LAST_CONTEXT.with(|last| match &*last.borrow() {
+            Some(context) if context.code == self.code() => Some(context.message.clone()),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! Recording of every sample to a CSV file
+//!
+//! A [`CsvRecorder`] hooks into the per-sample callback and appends one row
+//! per sensor for every sample the sampling thread takes, so a capture can
+//! be analysed offline in Python or a spreadsheet.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::sampler::ListenerId;
+use crate::context::file_access_error;
+use crate::{Error, PowerMonitor};
+
+/// Header line of the recorded files
+const CSV_HEADER: &str = "timestamp,sensor,voltage,current,power";
+
+/// The output file and the first error that occurred while writing it
+struct CsvSink {
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+/// Writes every sample to a CSV file until dropped
+///
+/// Created by [`PowerMonitor::record_csv`]. Each row holds the sample
+/// timestamp in seconds since the monitor was created, the sensor name and
+/// its voltage, current and power. Recording stops when the recorder is
+/// dropped, which flushes and closes the file but ignores errors; use
+/// [`CsvRecorder::finish`] to check for them.
+#[must_use = "recording stops as soon as the recorder is dropped"]
+pub struct CsvRecorder<'a> {
+    monitor: &'a PowerMonitor,
+    listener: ListenerId,
+    sink: Arc<Mutex<CsvSink>>,
+    finished: bool,
+}
+
+impl<'a> CsvRecorder<'a> {
+    /// Stops recording, then flushes and closes the file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every sample was written
+    /// * `Err(Error::FileAccess)` - If writing a row or flushing failed
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.monitor.sampler.remove_listener(self.listener);
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        let flushed = sink.writer.flush();
+        match sink.error.take() {
+            Some(_) => Err(Error::FileAccess),
+            None => flushed.map_err(|_| Error::FileAccess),
+        }
+    }
+}
+
+impl<'a> Drop for CsvRecorder<'a> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.close();
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Records every sample to a CSV file
+    ///
+    /// The file is created (or truncated) and starts with the header
+    /// `timestamp,sensor,voltage,current,power`. While the returned recorder
+    /// is alive, each sample taken by the sampling thread appends one row per
+    /// sensor, in the order of [`PowerMonitor::get_latest_data`]. Processing
+    /// configured through [`crate::PowerMonitorBuilder`] is applied to the
+    /// rows. Recording does not start sampling by itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the CSV file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CsvRecorder)` - The recorder, which stops recording when dropped
+    /// * `Err(Error::FileAccess)` - If the file could not be created
+    pub fn record_csv<P: AsRef<Path>>(&self, path: P) -> Result<CsvRecorder<'_>, Error> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| file_access_error(path, e))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", CSV_HEADER).map_err(|_| Error::FileAccess)?;
+
+        let sink = Arc::new(Mutex::new(CsvSink { writer, error: None }));
+        let listener_sink = Arc::clone(&sink);
+        let processing = self.processing.clone();
+        let listener = self.sampler.add_listener(move |data| {
+            let mut sink = listener_sink.lock().unwrap_or_else(|e| e.into_inner());
+            if sink.error.is_some() {
+                return;
+            }
+            let data = processing.data(data.to_owned());
+            for sensor in &data.sensors {
+                let row = writeln!(
+                    sink.writer,
+                    "{:.6},{},{},{},{}",
+                    sensor.timestamp_ns as f64 / 1e9,
+                    sensor.name(),
+                    sensor.voltage,
+                    sensor.current,
+                    sensor.power
+                );
+                if let Err(e) = row {
+                    sink.error = Some(e);
+                    return;
+                }
+            }
+        });
+
+        Ok(CsvRecorder {
+            monitor: self,
+            listener,
+            sink,
+            finished: false,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+//! Selection of the monitored device on multi-board systems
+//!
+//! [`PowerMonitor::new`] monitors every INA226 sensor it can find. When
+//! several boards or add-on cards are attached, their sensors sit on
+//! different I2C buses; [`enumerate_devices`] lists those buses and
+//! [`PowerMonitor::with_device`] restricts a monitor to one of them.
+//! [`PowerMonitor::with_sysfs_root`] reads the sensors from another
+//! directory tree instead, e.g. a test fixture. Whichever was used is
+//! remembered, so [`PowerMonitor::reinitialize`] rediscovers the same sensors.
+
+use std::ffi::{c_void, CString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr::NonNull;
+
+use crate::context;
+use crate::{check_result, decode_c_str, pm_enumerate_devices, pm_init, pm_init_device, pm_init_sysfs, Error, PowerMonitor};
+
+/// Maximum number of devices reported, one per physical sensor at most
+const MAX_DEVICES: usize = 32;
+
+/// Device record as laid out by the C library
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct RawDeviceInfo {
+    id: [u8; 64],
+    path: [u8; 256],
+    sensor_count: i32,
+}
+
+/// How the library handle of a monitor was initialized
+pub(crate) enum InitSource {
+    /// All sensors below the default sysfs root, see [`PowerMonitor::new`]
+    Default,
+    /// The sensors of one device, see [`PowerMonitor::with_device`]
+    Device(CString),
+    /// All sensors below another root, see [`PowerMonitor::with_sysfs_root`]
+    SysfsRoot(CString),
+}
+
+impl InitSource {
+    /// Initializes a new library handle, discovering the sensors again
+    pub(crate) fn init(&self) -> Result<NonNull<c_void>, Error> {
+        let mut handle = std::ptr::null_mut();
+        let result = unsafe {
+            match self {
+                InitSource::Default => pm_init(&mut handle),
+                InitSource::Device(id) => pm_init_device(&mut handle, id.as_ptr()),
+                InitSource::SysfsRoot(root) => pm_init_sysfs(&mut handle, root.as_ptr()),
+            }
+        };
+        if let Err(error) = check_result("pm_init", result) {
+            // Discovery records the file it could not access, if any
+            context::record_c_context(result);
+            return Err(error);
+        }
+        NonNull::new(handle).ok_or(Error::InitFailed)
+    }
+}
+
+/// A device carrying power sensors, usually an I2C bus
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    /// Id to pass to [`PowerMonitor::with_device`], e.g. `"i2c-3"`
+    pub id: String,
+    /// Resolved sysfs path of the device
+    pub path: String,
+    /// Number of physical sensors on the device
+    pub sensor_count: i32,
+}
+
+/// Lists the devices that carry power sensors
+///
+/// # Returns
+///
+/// * `Ok(Vec<DeviceInfo>)` - The devices, empty if no sensors were found
+/// * `Err(Error)` - An error code if enumeration fails
+pub fn enumerate_devices() -> Result<Vec<DeviceInfo>, Error> {
+    let mut raw = vec![
+        RawDeviceInfo {
+            id: [0; 64],
+            path: [0; 256],
+            sensor_count: 0,
+        };
+        MAX_DEVICES
+    ];
+    let mut count = MAX_DEVICES as i32;
+    let result = unsafe { pm_enumerate_devices(raw.as_mut_ptr(), &mut count) };
+    check_result("pm_enumerate_devices", result)?;
+    raw.truncate(count.max(0) as usize);
+    Ok(raw
+        .iter()
+        .map(|device| DeviceInfo {
+            id: decode_c_str(&device.id).into_owned(),
+            path: decode_c_str(&device.path).into_owned(),
+            sensor_count: device.sensor_count,
+        })
+        .collect())
+}
+
+impl PowerMonitor {
+    /// Creates a monitor for the sensors of a single device
+    ///
+    /// The virtual `PS_TOTAL_POWER`, `PL_TOTAL_POWER` and `TOTAL_POWER`
+    /// sensors then only add up the rails of that device.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Device id as reported by [`enumerate_devices`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerMonitor)` - A new monitor for the device
+    /// * `Err(Error::NoSensors)` - If no sensors belong to `id`
+    /// * `Err(Error::InvalidArgument)` - If `id` contains a NUL byte
+    pub fn with_device(id: &str) -> Result<Self, Error> {
+        let id = CString::new(id).map_err(|_| Error::InvalidArgument)?;
+        Self::from_source(InitSource::Device(id))
+    }
+
+    /// Creates a monitor for the sensors below a different sysfs root
+    ///
+    /// Sensors are read from `<root>/class/hwmon` instead of
+    /// `/sys/class/hwmon`, with the same file layout. Setting the
+    /// `XLNPWMON_SYSFS_ROOT` environment variable has the same effect on
+    /// [`PowerMonitor::new`]. The `mock` feature ignores the root.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Directory standing in for `/sys`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerMonitor)` - A new monitor for the sensors found there
+    /// * `Err(Error::NoSensors)` - If no sensors were found below `root`
+    /// * `Err(Error::InvalidArgument)` - If `root` contains a NUL byte
+    pub fn with_sysfs_root<P: AsRef<Path>>(root: P) -> Result<Self, Error> {
+        let root = CString::new(root.as_ref().as_os_str().as_bytes()).map_err(|_| Error::InvalidArgument)?;
+        Self::from_source(InitSource::SysfsRoot(root))
+    }
+}
@@ -0,0 +1,84 @@
+//! Per-sensor differences between two snapshots
+//!
+//! Comparing an idle snapshot with one taken under load shows which rails
+//! the load moved. [`OwnedPowerData::diff`] pairs the sensors of two
+//! snapshots by name and reports how far each reading changed.
+
+use crate::{OwnedPowerData, SensorData};
+
+/// Change of one sensor's reading between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SensorDelta {
+    /// Sensor name
+    pub name: String,
+    /// Voltage change in volts
+    pub d_voltage: f64,
+    /// Current change in amperes
+    pub d_current: f64,
+    /// Power change in watts
+    pub d_power: f64,
+}
+
+impl SensorDelta {
+    /// Computes `current - baseline`, named after `current`
+    fn between(current: &SensorData, baseline: &SensorData) -> Self {
+        Self {
+            name: current.name().into_owned(),
+            d_voltage: current.voltage - baseline.voltage,
+            d_current: current.current - baseline.current,
+            d_power: current.power - baseline.power,
+        }
+    }
+}
+
+/// Differences between a snapshot and a baseline snapshot
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapshotDiff {
+    /// Change of the total
+    pub total: SensorDelta,
+    /// Changes of the sensors present in both snapshots, in the order of the
+    /// newer one
+    pub sensors: Vec<SensorDelta>,
+    /// Sensors only present in the newer snapshot
+    pub added: Vec<String>,
+    /// Sensors only present in the baseline
+    pub removed: Vec<String>,
+}
+
+impl OwnedPowerData {
+    /// Compares the snapshot with an earlier one
+    ///
+    /// Sensors are matched by name; every delta is `self - baseline`.
+    ///
+    /// # Arguments
+    ///
+    /// * `baseline` - Snapshot to compare against, e.g. taken while idle
+    ///
+    /// # Returns
+    ///
+    /// The total and per-sensor changes, and the sensors that appear in only
+    /// one of the snapshots
+    pub fn diff(&self, baseline: &OwnedPowerData) -> SnapshotDiff {
+        let mut diff = SnapshotDiff {
+            total: SensorDelta::between(&self.total, &baseline.total),
+            sensors: Vec::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+        };
+        for sensor in &self.sensors {
+            match baseline.sensors.iter().find(|b| b.name() == sensor.name()) {
+                Some(before) => diff.sensors.push(SensorDelta::between(sensor, before)),
+                None => diff.added.push(sensor.name().into_owned()),
+            }
+        }
+        diff.removed = baseline
+            .sensors
+            .iter()
+            .filter(|b| !self.sensors.iter().any(|sensor| sensor.name() == b.name()))
+            .map(|b| b.name().into_owned())
+            .collect();
+        diff
+    }
+}
@@ -0,0 +1,67 @@
+//! Logical sensor groups
+//!
+//! On custom boards several physical rails often feed one logical component
+//! (e.g. three rails powering an accelerator). Groups let users name such a
+//! set of sensors and query their combined power without touching the
+//! hardware description. Groups are kept entirely in the Rust layer.
+
+use crate::{Error, PowerMonitor};
+
+impl PowerMonitor {
+    /// Defines (or redefines) a named group of sensors
+    ///
+    /// # Arguments
+    ///
+    /// * `group_name` - Name of the group
+    /// * `members` - Names of the sensors belonging to the group, matched
+    ///   exactly against each sensor name with surrounding whitespace trimmed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If a member does not name an existing sensor
+    pub fn define_group(&self, group_name: &str, members: &[&str]) -> Result<(), Error> {
+        let data = self.latest_copy()?;
+        let sensors = &data.sensors;
+        for member in members {
+            if !sensors.iter().any(|s| s.name().trim() == *member) {
+                return Err(Error::InvalidArgument);
+            }
+        }
+
+        let members = members.iter().map(|m| m.to_string()).collect();
+        self.groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(group_name.to_string(), members);
+        Ok(())
+    }
+
+    /// Gets the summed power of a named group from the latest data
+    ///
+    /// # Arguments
+    ///
+    /// * `group_name` - Name of a group created with [`PowerMonitor::define_group`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - Total power of the group members in watts
+    /// * `Err(Error::NoSensors)` - If no group with this name is defined
+    pub fn group_power(&self, group_name: &str) -> Result<f64, Error> {
+        let members = self
+            .groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(group_name)
+            .cloned()
+            .ok_or(Error::NoSensors)?;
+
+        let data = self.latest_copy()?;
+        Ok(data
+            .sensors
+            .iter()
+            .filter(|s| members.iter().any(|m| s.name().trim() == m.as_str()))
+            .map(|s| s.power)
+            .sum())
+    }
+}
@@ -0,0 +1,80 @@
+//! Scoped sampling
+//!
+//! Pairing `start_sampling` and `stop_sampling` by hand leaks a running
+//! sampling thread whenever an early return or a panic skips the stop. A
+//! [`SamplingGuard`] ties the sampling run to a scope instead.
+
+use std::ops::Deref;
+
+use crate::{Error, PowerMonitor};
+
+/// Stops sampling when dropped
+///
+/// Created by [`PowerMonitor::start_sampling_guarded`]. The guard derefs to
+/// the monitor, so it can be used to read data while sampling runs.
+///
+/// ```no_run
+/// use xlnpwmon::PowerMonitor;
+///
+/// let monitor = PowerMonitor::new().unwrap();
+/// {
+///     let sampling = monitor.start_sampling_guarded().unwrap();
+///     std::thread::sleep(std::time::Duration::from_millis(100));
+///     println!("{:.2} W", sampling.get_latest_data().unwrap().total.power);
+/// }
+/// assert!(!monitor.is_sampling().unwrap());
+/// ```
+#[must_use = "sampling stops as soon as the guard is dropped"]
+pub struct SamplingGuard<'a> {
+    monitor: &'a PowerMonitor,
+    armed: bool,
+}
+
+impl<'a> SamplingGuard<'a> {
+    /// Stops sampling now, reporting any error
+    ///
+    /// Dropping the guard also stops sampling but ignores errors.
+    pub fn stop(mut self) -> Result<(), Error> {
+        self.armed = false;
+        self.monitor.stop_sampling()
+    }
+
+    /// Gets the guarded monitor
+    pub fn monitor(&self) -> &'a PowerMonitor {
+        self.monitor
+    }
+}
+
+impl Deref for SamplingGuard<'_> {
+    type Target = PowerMonitor;
+
+    fn deref(&self) -> &PowerMonitor {
+        self.monitor
+    }
+}
+
+impl Drop for SamplingGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            // Errors cannot be reported from drop; sampling may already have
+            // been stopped through the monitor.
+            let _ = self.monitor.stop_sampling();
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Starts sampling and returns a guard that stops it when dropped
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SamplingGuard)` - Sampling is running until the guard is dropped
+    /// * `Err(Error)` - An error code if starting sampling fails
+    pub fn start_sampling_guarded(&self) -> Result<SamplingGuard<'_>, Error> {
+        self.start_sampling()?;
+        Ok(SamplingGuard {
+            monitor: self,
+            armed: true,
+        })
+    }
+}
@@ -0,0 +1,66 @@
+//! Headroom to critical power thresholds
+//!
+//! Control software that throttles proactively needs to know how close each
+//! rail is to its critical threshold, not just whether it has crossed it.
+
+use crate::{Error, OwnedPowerData, PowerMonitor};
+
+impl OwnedPowerData {
+    /// Gets the headroom of every sensor with a critical threshold
+    ///
+    /// Headroom is `critical_threshold - power` in watts and is negative for
+    /// a sensor above its threshold. Sensors whose critical threshold is zero
+    /// (unset) or negative are excluded.
+    ///
+    /// # Returns
+    ///
+    /// `(sensor name, headroom in watts)` pairs in sensor order
+    pub fn headroom(&self) -> Vec<(String, f64)> {
+        self.sensors
+            .iter()
+            .filter(|sensor| sensor.critical_threshold > 0.0)
+            .map(|sensor| {
+                (
+                    sensor.name().into_owned(),
+                    sensor.critical_threshold - sensor.power,
+                )
+            })
+            .collect()
+    }
+
+    /// Gets the sensor with the least headroom
+    ///
+    /// # Returns
+    ///
+    /// * `Some((name, headroom))` - The tightest rail, see [`OwnedPowerData::headroom`]
+    /// * `None` - If no sensor has a critical threshold
+    pub fn min_headroom(&self) -> Option<(String, f64)> {
+        self.headroom()
+            .into_iter()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+impl PowerMonitor {
+    /// Gets the headroom of every sensor to its critical threshold
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(String, f64)>)` - Sensor names and headroom in watts, see
+    ///   [`OwnedPowerData::headroom`]
+    /// * `Err(Error)` - An error code if getting the latest data fails
+    pub fn thermal_headroom(&self) -> Result<Vec<(String, f64)>, Error> {
+        Ok(self.latest_copy()?.headroom())
+    }
+
+    /// Gets the sensor with the least headroom to its critical threshold
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((name, headroom)))` - The tightest rail
+    /// * `Ok(None)` - If no sensor has a critical threshold
+    /// * `Err(Error)` - An error code if getting the latest data fails
+    pub fn min_headroom(&self) -> Result<Option<(String, f64)>, Error> {
+        Ok(self.latest_copy()?.min_headroom())
+    }
+}
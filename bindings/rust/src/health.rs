@@ -0,0 +1,174 @@
+//! Liveness reporting
+//!
+//! A [`Heartbeat`] bundles the handful of diagnostics a service needs to
+//! answer "is the monitor healthy" into one snapshot, e.g. for a `/healthz`
+//! endpoint. [`SamplingHealth`] is the narrower check, backed by the C
+//! library, that the sampling thread is alive and still reading sensors.
+
+use std::sync::MutexGuard;
+use std::time::{Duration, Instant};
+
+use crate::{check_result, pm_get_sampling_health, Error, PowerMonitor};
+
+/// A single liveness snapshot of a power monitor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heartbeat {
+    /// Time since a new sample was last observed
+    pub last_sample_age: Duration,
+    /// Achieved sampling rate in Hz over the current statistics window
+    pub effective_rate: f64,
+    /// Whether the sampling thread is running
+    pub sampling: bool,
+    /// Number of sensors currently reporting as online
+    pub sensors_online: usize,
+    /// Total number of sensors
+    pub sensors_total: usize,
+}
+
+/// Whether the sampling thread is alive and how fresh its readings are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplingHealth {
+    /// Whether the sampling thread is running
+    pub thread_alive: bool,
+    /// Milliseconds since a sample last read at least one sensor
+    pub last_sample_age_ms: u64,
+}
+
+impl SamplingHealth {
+    /// Checks if the thread is dead or its last successful sample is older
+    /// than `max_age`
+    ///
+    /// A sensible `max_age` is a few sampling periods.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        !self.thread_alive || Duration::from_millis(self.last_sample_age_ms) > max_age
+    }
+}
+
+/// Rust-side bookkeeping of the sampling lifecycle
+#[derive(Debug)]
+pub(crate) struct SampleTracker {
+    created: Instant,
+    window_start: Option<Instant>,
+    window_end: Option<Instant>,
+    last_count: u64,
+    last_sample_at: Option<Instant>,
+}
+
+impl SampleTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            created: Instant::now(),
+            window_start: None,
+            window_end: None,
+            last_count: 0,
+            last_sample_at: None,
+        }
+    }
+
+    pub(crate) fn on_start(&mut self) {
+        let now = Instant::now();
+        if self.window_start.is_none() || self.window_end.is_some() {
+            self.window_start = Some(now);
+            self.last_count = 0;
+        }
+        self.window_end = None;
+    }
+
+    pub(crate) fn on_stop(&mut self) {
+        self.window_end = Some(Instant::now());
+    }
+
+    pub(crate) fn on_reset(&mut self) {
+        let now = Instant::now();
+        self.window_start = Some(now);
+        if self.window_end.is_some() {
+            self.window_end = Some(now);
+        }
+        self.last_count = 0;
+    }
+
+    /// Records the current sample count, returning the age of the newest sample
+    fn observe(&mut self, count: u64) -> Duration {
+        let now = Instant::now();
+        if count != self.last_count {
+            self.last_count = count;
+            self.last_sample_at = Some(now);
+        }
+        now - self.last_sample_at.unwrap_or(self.created)
+    }
+
+    /// Samples per second over the current statistics window
+    fn rate(&self, count: u64) -> f64 {
+        let start = match self.window_start {
+            Some(start) => start,
+            None => return 0.0,
+        };
+        let elapsed = self.window_end.unwrap_or_else(Instant::now) - start;
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        count as f64 / elapsed.as_secs_f64()
+    }
+}
+
+impl PowerMonitor {
+    pub(crate) fn tracker(&self) -> MutexGuard<'_, SampleTracker> {
+        self.tracker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Gets a liveness snapshot of the monitor
+    ///
+    /// The sample age is tracked on the Rust side: it measures the time since
+    /// a heartbeat (or the monitor's creation) first observed the current
+    /// sample count, so poll at least as often as the staleness you want to
+    /// detect. Failed queries are reported as zero values rather than errors.
+    ///
+    /// # Returns
+    ///
+    /// * `Heartbeat` - The current liveness snapshot
+    pub fn heartbeat(&self) -> Heartbeat {
+        let sampling = self.is_sampling().unwrap_or(false);
+        let count = self
+            .statistics_copy()
+            .map(|stats| stats.total.power.count)
+            .unwrap_or(0);
+        let (sensors_online, sensors_total) = self
+            .latest_copy()
+            .map(|data| {
+                let sensors = &data.sensors;
+                (sensors.iter().filter(|s| s.online).count(), sensors.len())
+            })
+            .unwrap_or((0, 0));
+
+        let mut tracker = self.tracker();
+        Heartbeat {
+            last_sample_age: tracker.observe(count),
+            effective_rate: tracker.rate(count),
+            sampling,
+            sensors_online,
+            sensors_total,
+        }
+    }
+
+    /// Checks that the sampling thread is alive and still reading sensors
+    ///
+    /// Unlike [`PowerMonitor::heartbeat`], the sample age is tracked by the
+    /// sampling thread itself and only advances while no sensor can be read,
+    /// e.g. after the hwmon files went away. Until the first successful
+    /// sample, the age counts from the start of sampling.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SamplingHealth)` - The current health
+    /// * `Err(Error)` - An error code if the query fails
+    pub fn sampling_health(&self) -> Result<SamplingHealth, Error> {
+        let mut alive = false;
+        let mut age_ns = 0u64;
+        let result = unsafe { pm_get_sampling_health(self.handle.as_ptr(), &mut alive, &mut age_ns) };
+        check_result("pm_get_sampling_health", result)?;
+        Ok(SamplingHealth {
+            thread_alive: alive,
+            last_sample_age_ms: age_ns / 1_000_000,
+        })
+    }
+}
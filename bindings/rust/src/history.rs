@@ -0,0 +1,135 @@
+//! Recent sample history
+//!
+//! Statistics condense a capture into min/max/avg, which is not enough for
+//! plotting or spectral analysis. The C library can additionally keep the
+//! most recent samples in a bounded ring buffer; it is disabled by default so
+//! captures that do not need it pay nothing for it. The retained samples
+//! also back the percentile statistics of [`PowerMonitor::get_percentiles`].
+
+use std::sync::atomic::Ordering;
+
+use crate::{check_result, pm_get_history, pm_set_history_capacity, Error, OwnedPowerData, PowerMonitor, SensorData};
+
+impl PowerMonitor {
+    /// Sets the number of recent samples retained by the sampling thread
+    ///
+    /// Once the history is full, each new sample drops the oldest one. A
+    /// capacity of 0, the default, disables the history. Changing the
+    /// capacity discards the samples retained so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of samples to retain
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If `capacity` exceeds `i32::MAX`
+    /// * `Err(Error::Memory)` - If the buffer could not be allocated
+    pub fn set_history_capacity(&self, capacity: usize) -> Result<(), Error> {
+        if capacity > i32::MAX as usize {
+            return Err(Error::InvalidArgument);
+        }
+        let result = unsafe { pm_set_history_capacity(self.handle.as_ptr(), capacity as i32) };
+        check_result("pm_set_history_capacity", result)?;
+        self.history_capacity.store(capacity, Ordering::Release);
+        Ok(())
+    }
+
+    /// Gets the number of samples the history retains
+    pub fn history_capacity(&self) -> usize {
+        self.history_capacity.load(Ordering::Acquire)
+    }
+
+    /// Copies the samples retained in the history, oldest first
+    ///
+    /// The history is left unchanged. Sensors are in the same order as in
+    /// [`PowerMonitor::get_latest_data`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<OwnedPowerData>)` - The retained samples, empty if the history is disabled
+    /// * `Err(Error)` - An error code if the history could not be read
+    pub fn get_history(&self) -> Result<Vec<OwnedPowerData>, Error> {
+        let capacity = self.history_capacity();
+        if capacity == 0 {
+            return Ok(Vec::new());
+        }
+        let sensor_count = self.get_sensor_count()?.max(0) as usize;
+        let mut totals = vec![SensorData::default(); capacity];
+        let mut sensors = vec![SensorData::default(); capacity * sensor_count];
+        let mut count = capacity as i32;
+        let result = unsafe {
+            pm_get_history(
+                self.handle.as_ptr(),
+                totals.as_mut_ptr(),
+                sensors.as_mut_ptr(),
+                &mut count,
+            )
+        };
+        check_result("pm_get_history", result)?;
+
+        let count = count.max(0) as usize;
+        Ok(totals
+            .into_iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, total)| {
+                self.process_data(OwnedPowerData {
+                    total,
+                    sensors: sensors[i * sensor_count..(i + 1) * sensor_count].to_vec(),
+                })
+            })
+            .collect())
+    }
+
+    /// Computes quantiles of a sensor's power over the retained history
+    ///
+    /// Quantiles are interpolated linearly between the two nearest samples.
+    /// Only samples in which the sensor was online are used. Enable the
+    /// history with [`PowerMonitor::set_history_capacity`] first; the
+    /// quantiles cover the samples it currently retains.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - Sensor name
+    /// * `qs` - Quantiles to compute, e.g. `0.95` for p95; clamped to [0, 1]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<f64>)` - Power in watts at each quantile, in the order of `qs`
+    /// * `Err(Error::NotRunning)` - If the history holds no samples of the sensor
+    /// * `Err(Error::NoSensors)` - If `sensor` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If a quantile is NaN
+    pub fn get_percentiles(&self, sensor: &str, qs: &[f64]) -> Result<Vec<f64>, Error> {
+        if qs.iter().any(|q| q.is_nan()) {
+            return Err(Error::InvalidArgument);
+        }
+        if !self.sensor_names()?.iter().any(|name| name == sensor) {
+            return Err(Error::NoSensors);
+        }
+
+        let mut powers: Vec<f64> = self
+            .get_history()?
+            .iter()
+            .filter_map(|sample| sample.sensors.iter().find(|data| data.name() == sensor))
+            .filter(|data| data.online)
+            .map(|data| data.power)
+            .collect();
+        if powers.is_empty() {
+            return Err(Error::NotRunning);
+        }
+        powers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let last = (powers.len() - 1) as f64;
+        Ok(qs
+            .iter()
+            .map(|q| {
+                let rank = q.clamp(0.0, 1.0) * last;
+                let lower = rank.floor() as usize;
+                let upper = rank.ceil() as usize;
+                powers[lower] + (powers[upper] - powers[lower]) * (rank - lower as f64)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,90 @@
+//! Minimal HTTP endpoint for Prometheus scrapes
+//!
+//! [`serve_metrics`] answers `GET /metrics` with
+//! [`PowerMonitor::prometheus_metrics`] and nothing else. It is built on
+//! `std::net` only: a scraper sends one small request every few seconds, so
+//! connections are handled one at a time on the calling thread. The server
+//! only reads the latest data; sampling must be started separately.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::PowerMonitor;
+
+/// Largest request head that is read before answering
+const MAX_REQUEST_LEN: usize = 8192;
+/// How long a client may take to send its request or read the response
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serves the Prometheus metrics of `monitor` over HTTP
+///
+/// Blocks the calling thread, so it is usually run on a thread of its own.
+/// Requests for `/metrics` get the latest readings, other paths `404` and
+/// methods other than `GET` `405`; if the readings cannot be taken the
+/// response is `503`. Errors on individual connections are ignored.
+///
+/// The monitor must be sampling, otherwise every scrape returns the same
+/// readings.
+///
+/// # Arguments
+///
+/// * `monitor` - Monitor to read, shared with the sampling code
+/// * `addr` - Address to listen on, e.g. `0.0.0.0:9100`
+///
+/// # Returns
+///
+/// * `Err(io::Error)` - If `addr` cannot be bound or accepting fails; the
+///   function does not return otherwise
+pub fn serve_metrics(monitor: Arc<PowerMonitor>, addr: SocketAddr) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let _ = handle_connection(&monitor, stream?);
+    }
+    Ok(())
+}
+
+/// Reads one request from `stream` and writes the response
+fn handle_connection(monitor: &PowerMonitor, mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_LEN {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+
+    let (status, body) = if method != "GET" {
+        ("405 Method Not Allowed", String::from("method not allowed\n"))
+    } else if path != "/metrics" {
+        ("404 Not Found", String::from("not found\n"))
+    } else {
+        match monitor.prometheus_metrics() {
+            Ok(text) => ("200 OK", text),
+            Err(e) => ("503 Service Unavailable", format!("{}\n", e)),
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
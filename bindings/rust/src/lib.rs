@@ -1,5 +1,106 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::ffi::{c_void, CString};
+use std::fmt;
+use std::io;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+mod baseline;
+mod builder;
+mod clipping;
+mod config;
+mod context;
+mod csv;
+mod device;
+mod diff;
+mod groups;
+mod guard;
+mod headroom;
+mod health;
+mod history;
+#[cfg(feature = "http")]
+mod http;
+mod measure;
+mod metadata;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod owned;
+mod phases;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+mod rails;
+mod ranking;
+mod registry;
+mod report;
+mod sampler;
+mod scheduling;
+mod sensor;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod snapshot;
+mod spikes;
+mod status;
+#[cfg(feature = "tokio")]
+mod stream;
+mod subscribe;
+#[cfg(feature = "bindgen")]
+pub mod sys;
+#[cfg(feature = "serde")]
+mod threshold_presets;
+mod thresholds;
+mod trace;
+mod units;
+mod watchdog;
+
+pub use baseline::Baseline;
+pub use builder::{PowerMonitorBuilder, PowerMonitorConfig};
+pub use clipping::{ClippingDetector, FullScale, DEFAULT_CLIPPING_FRACTION, DEFAULT_CLIPPING_WINDOW};
+pub use config::MonitorConfig;
+pub use csv::CsvRecorder;
+pub use device::{enumerate_devices, DeviceInfo};
+pub use diff::{SensorDelta, SnapshotDiff};
+pub use guard::SamplingGuard;
+pub use health::{Heartbeat, SamplingHealth};
+#[cfg(feature = "http")]
+pub use http::serve_metrics;
+pub use metadata::CaptureMetadata;
+#[cfg(feature = "metrics")]
+pub use crate::metrics::{METRIC_CURRENT, METRIC_POWER, METRIC_VOLTAGE};
+pub use owned::{OwnedPowerData, OwnedPowerStats};
+pub use phases::{PhaseRecorder, PhaseReport};
+pub use rails::{PowerDomain, Rail};
+pub use registry::{PowerMonitorRegistry, PowerSource};
+pub use sampler::Ewma;
+pub use sensor::Sensor;
+pub use snapshot::PowerSnapshot;
+pub use spikes::{SpikeEvent, MIN_SPIKE_SAMPLES};
+#[cfg(feature = "tokio")]
+pub use stream::PowerStream;
+pub use subscribe::SampleReceiver;
+pub use thresholds::{SensorThresholds, ThresholdLevel};
+pub use trace::{ReplayMonitor, ReplayPace, TraceRecorder};
+pub use units::{Amps, Joules, Volts, Watts, MIN_RESISTANCE_CURRENT};
+use device::InitSource;
+use health::SampleTracker;
+use sampler::{EwmaListener, Sampler};
+
+/// Sampling frequency in Hz that new monitors start with
+///
+/// Fast enough to follow workload phases, slow enough that the hwmon reads
+/// stay negligible next to the workload. Mirrors
+/// `PM_DEFAULT_SAMPLING_FREQUENCY_HZ`, the default of the C library.
+pub const DEFAULT_FREQUENCY_HZ: i32 = 10;
+
+/// Lowest sampling frequency in Hz the library accepts
+pub const MIN_FREQUENCY_HZ: i32 = 1;
+
+/// Highest sampling frequency in Hz the library accepts
+///
+/// Well above the rate at which the hwmon reads of a board can keep up.
+pub const MAX_FREQUENCY_HZ: i32 = 10_000;
 
 /// A handle to the power monitor instance
 #[repr(C)]
@@ -8,7 +109,8 @@ pub struct PowerHandle(*mut c_void);
 
 /// Types of power sensors supported by the library
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SensorType {
     /// Unknown sensor type
     Unknown = 0,
@@ -18,11 +120,63 @@ pub enum SensorType {
     System = 2,
 }
 
+impl Default for SensorType {
+    fn default() -> Self {
+        SensorType::Unknown
+    }
+}
+
+impl SensorType {
+    /// Converts a raw sensor type, mapping unknown values to `SensorType::Unknown`
+    ///
+    /// This is the lenient counterpart of the `TryFrom<i32>` conversion.
+    /// It is not a `From<i32>` impl because that would conflict with the
+    /// standard library's blanket `TryFrom` implementation.
+    pub fn from_raw(value: i32) -> Self {
+        Self::try_from(value).unwrap_or(SensorType::Unknown)
+    }
+}
+
+impl TryFrom<i32> for SensorType {
+    type Error = Error;
+
+    /// Converts a raw sensor type, failing with `Error::InvalidArgument`
+    /// for values that name no sensor type
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SensorType::Unknown),
+            1 => Ok(SensorType::I2C),
+            2 => Ok(SensorType::System),
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+}
+
+impl From<SensorType> for i32 {
+    fn from(sensor_type: SensorType) -> Self {
+        sensor_type as i32
+    }
+}
+
+/// Whether a monitor is taking samples, see [`PowerMonitor::sampling_state`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SamplingState {
+    /// Samples are being taken
+    Running,
+    /// Sampling was started, then paused with [`PowerMonitor::pause`]
+    Paused,
+    /// Sampling is not started, or was stopped
+    Stopped,
+}
+
 /// Power data for a single sensor
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorData {
     /// Sensor name
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl::c_str"))]
     pub name: [u8; 64],
     /// Sensor type
     pub type_: SensorType,
@@ -35,16 +189,89 @@ pub struct SensorData {
     /// Whether the sensor is online
     pub online: bool,
     /// Status string (if available)
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl::c_str"))]
     pub status: [u8; 32],
     /// Warning threshold in watts
     pub warning_threshold: f64,
     /// Critical threshold in watts
     pub critical_threshold: f64,
+    /// Monotonic time of the reading in nanoseconds since the monitor was created
+    pub timestamp_ns: u64,
+}
+
+impl Default for SensorData {
+    /// An offline reading of zero without a name, as when zero-initialized
+    fn default() -> Self {
+        Self {
+            name: [0; 64],
+            type_: SensorType::Unknown,
+            voltage: 0.0,
+            current: 0.0,
+            power: 0.0,
+            online: false,
+            status: [0; 32],
+            warning_threshold: 0.0,
+            critical_threshold: 0.0,
+            timestamp_ns: 0,
+        }
+    }
+}
+
+impl SensorData {
+    /// Creates an offline reading of zero for the sensor `name`
+    ///
+    /// A name that does not fit the buffer is truncated at a character
+    /// boundary, keeping its NUL terminator. Set the remaining fields with
+    /// struct update syntax, e.g.
+    /// `SensorData { power: 1.5, online: true, ..SensorData::new("VCCINT") }`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: encode_c_str(name),
+            ..Self::default()
+        }
+    }
+
+    /// Decodes the sensor name up to the first NUL byte
+    ///
+    /// Invalid UTF-8 is replaced lossily; a buffer without a NUL terminator
+    /// is decoded in full.
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_c_str(&self.name)
+    }
+
+    /// Decodes the status string up to the first NUL byte
+    pub fn status(&self) -> Cow<'_, str> {
+        decode_c_str(&self.status)
+    }
+}
+
+impl fmt::Display for SensorData {
+    /// Formats the name, readings and online state, e.g.
+    /// `VCCINT: 0.85 V, 1.20 A, 1.02 W [online]`
+    ///
+    /// The readings have two decimals unless a precision is given, as in
+    /// `format!("{:.3}", sensor)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(
+            f,
+            "{}: {:.*} V, {:.*} A, {:.*} W [{}]",
+            self.name(),
+            precision,
+            self.voltage,
+            precision,
+            self.current,
+            precision,
+            self.power,
+            if self.online { "online" } else { "offline" }
+        )
+    }
 }
 
 /// Statistical data for a metric
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stats {
     /// Minimum value
     pub min: f64,
@@ -56,13 +283,34 @@ pub struct Stats {
     pub total: f64,
     /// Number of samples
     pub count: u64,
+    /// Sum of squared deviations from the average, from which `stddev` is derived
+    pub sum_sq_dev: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+}
+
+impl fmt::Display for Stats {
+    /// Formats the range and average, e.g. `min/avg/max = 0.98 / 1.02 / 1.10`
+    ///
+    /// The values have two decimals unless a precision is given, as in
+    /// `format!("{:.3}", stats)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(
+            f,
+            "min/avg/max = {:.*} / {:.*} / {:.*}",
+            precision, self.min, precision, self.avg, precision, self.max
+        )
+    }
 }
 
 /// Power statistics for a sensor
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorStats {
     /// Sensor name
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl::c_str"))]
     pub name: [u8; 64],
     /// Voltage statistics
     pub voltage: Stats,
@@ -72,7 +320,39 @@ pub struct SensorStats {
     pub power: Stats,
 }
 
+impl Default for SensorStats {
+    /// Empty statistics without a name, as when zero-initialized
+    fn default() -> Self {
+        Self {
+            name: [0; 64],
+            voltage: Stats::default(),
+            current: Stats::default(),
+            power: Stats::default(),
+        }
+    }
+}
+
+impl SensorStats {
+    /// Creates empty statistics for the sensor `name`, truncated as in
+    /// [`SensorData::new`]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: encode_c_str(name),
+            ..Self::default()
+        }
+    }
+
+    /// Decodes the sensor name up to the first NUL byte, see [`SensorData::name`]
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_c_str(&self.name)
+    }
+}
+
 /// Overall power data
+///
+/// Not `Clone`: `sensors` points into a buffer the library keeps
+/// overwriting. Use [`PowerData::to_owned`] to keep a copy as an
+/// [`OwnedPowerData`].
 #[repr(C)]
 #[derive(Debug)]
 pub struct PowerData {
@@ -84,7 +364,24 @@ pub struct PowerData {
     pub sensor_count: i32,
 }
 
+impl PowerData {
+    /// Views the sensor array as a slice
+    ///
+    /// The slice is empty when the sensor pointer is null or the count is
+    /// not positive, and borrows from `self` like the data it points to.
+    pub fn sensors(&self) -> &[SensorData] {
+        if self.sensors.is_null() || self.sensor_count <= 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.sensors, self.sensor_count as usize) }
+    }
+}
+
 /// Overall power statistics
+///
+/// Not `Clone`: `sensors` points into a buffer the library keeps
+/// overwriting. Use [`PowerStats::to_owned`] to keep a copy as an
+/// [`OwnedPowerStats`].
 #[repr(C)]
 #[derive(Debug)]
 pub struct PowerStats {
@@ -96,9 +393,37 @@ pub struct PowerStats {
     pub sensor_count: i32,
 }
 
+impl PowerStats {
+    /// Views the sensor statistics array as a slice
+    ///
+    /// The slice is empty when the sensor pointer is null or the count is
+    /// not positive, and borrows from `self` like the data it points to.
+    pub fn sensors(&self) -> &[SensorStats] {
+        if self.sensors.is_null() || self.sensor_count <= 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.sensors, self.sensor_count as usize) }
+    }
+
+    /// Iterates over the sensor statistics, see [`PowerStats::sensors`]
+    pub fn iter(&self) -> std::slice::Iter<'_, SensorStats> {
+        self.sensors().iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PowerStats {
+    type Item = &'a SensorStats;
+    type IntoIter = std::slice::Iter<'a, SensorStats>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Summary power data for PS, PL and Total
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerSummary {
     /// PS total power in watts
     pub ps_total_power: f64,
@@ -110,7 +435,8 @@ pub struct PowerSummary {
 
 /// Summary power statistics for PS, PL and Total
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerSummaryStats {
     /// PS total power statistics
     pub ps_total_power: Stats,
@@ -122,6 +448,7 @@ pub struct PowerSummaryStats {
 
 /// Error codes returned by library functions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum Error {
     /// Initialization failed
@@ -142,11 +469,16 @@ pub enum Error {
     Memory = -8,
     /// Thread creation/management error
     Thread = -9,
+    /// Invalid argument
+    InvalidArgument = -10,
+    /// Timed out waiting
+    Timeout = -11,
     /// Unknown error code
-    Unknown(i32) = -10,
+    Unknown(i32) = -12,
 }
 
 impl From<i32> for Error {
+    /// Maps a C error code
     fn from(code: i32) -> Self {
         match code {
             -1 => Error::InitFailed,
@@ -158,14 +490,17 @@ impl From<i32> for Error {
             -7 => Error::FileAccess,
             -8 => Error::Memory,
             -9 => Error::Thread,
+            -10 => Error::InvalidArgument,
+            -11 => Error::Timeout,
             _ => Error::Unknown(code),
         }
     }
 }
 
-impl From<Error> for i32 {
-    fn from(error: Error) -> Self {
-        match error {
+impl Error {
+    /// Gets the C error code of the error
+    fn code(&self) -> i32 {
+        match *self {
             Error::InitFailed => -1,
             Error::NotInitialized => -2,
             Error::AlreadyRunning => -3,
@@ -175,57 +510,313 @@ impl From<Error> for i32 {
             Error::FileAccess => -7,
             Error::Memory => -8,
             Error::Thread => -9,
+            Error::InvalidArgument => -10,
+            Error::Timeout => -11,
             Error::Unknown(code) => code,
         }
     }
 }
 
+impl From<Error> for i32 {
+    fn from(error: Error) -> Self {
+        error.code()
+    }
+}
+
+impl fmt::Display for Error {
+    /// Formats the message of the C library for the error code
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The C library returns a pointer to a static string literal
+        let message = unsafe { std::ffi::CStr::from_ptr(pm_error_string(self.code())) };
+        f.write_str(&message.to_string_lossy())?;
+        if let Error::Unknown(code) = self {
+            write!(f, " (code {})", code)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    /// Wraps the error in an [`io::Error`] of the closest kind
+    ///
+    /// If the OS error behind the error was recorded (see
+    /// [`Error::context`]), its kind is used, e.g. `NotFound` or
+    /// `PermissionDenied`. Otherwise `FileAccess` becomes `PermissionDenied`,
+    /// as the sensor files exist once discovered and mostly fail to open for
+    /// lack of permission, and `NoSensors` becomes `NotFound`. Invalid
+    /// arguments and frequencies become `InvalidInput`, timeouts `TimedOut`
+    /// and allocation failures `OutOfMemory`; everything else is `Other`.
+    /// The original error is kept as the inner error, with its message. The
+    /// recorded cause goes with the error, so it is no longer returned by
+    /// [`Error::context`] afterwards.
+    fn from(error: Error) -> Self {
+        if let Some(errno) = context::take_os_errno(&error) {
+            return io::Error::new(io::Error::from_raw_os_error(errno).kind(), error);
+        }
+        let kind = match error {
+            Error::FileAccess => io::ErrorKind::PermissionDenied,
+            Error::NoSensors => io::ErrorKind::NotFound,
+            Error::InvalidArgument | Error::InvalidFrequency => io::ErrorKind::InvalidInput,
+            Error::Timeout => io::ErrorKind::TimedOut,
+            Error::Memory => io::ErrorKind::OutOfMemory,
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, error)
+    }
+}
+
 /// A power monitor instance that provides functionality to monitor power consumption
 /// from various sources (I2C sensors, system power supplies), collect statistics,
 /// and control the sampling process.
+///
+/// A monitor can be shared between threads, e.g. in an `Arc`; see the
+/// `Send`/`Sync` implementations below for the guarantees this relies on.
 pub struct PowerMonitor {
     handle: NonNull<c_void>,
+    /// Named sensor groups, see [`PowerMonitor::define_group`]
+    groups: Mutex<HashMap<String, Vec<String>>>,
+    /// Rust-side sampling bookkeeping used by [`PowerMonitor::heartbeat`]
+    tracker: Mutex<SampleTracker>,
+    /// Processing applied to owned outputs, see [`PowerMonitorBuilder`]
+    processing: owned::Processing,
+    /// Whether `start_sampling` resets the statistics first
+    reset_on_start: bool,
+    /// Receives every sample from the C sampling thread
+    sampler: Box<Sampler>,
+    /// Filter behind [`PowerMonitor::ewma_total_power`], if smoothing is enabled
+    ewma: Mutex<Option<EwmaListener>>,
+    /// Detector behind [`PowerMonitor::clipping_report`]
+    clipping: Arc<Mutex<ClippingDetector>>,
+    /// Cached sampling state behind [`PowerMonitor::is_sampling`]
+    sampling: AtomicBool,
+    /// Deadline of a capture started with [`PowerMonitor::start_sampling_for`]
+    stop_deadline: Mutex<Option<Instant>>,
+    /// Provenance of the current capture, see [`PowerMonitor::capture_metadata`]
+    metadata: Mutex<Option<CaptureMetadata>>,
+    /// Samples retained by the C library, see [`PowerMonitor::set_history_capacity`]
+    history_capacity: AtomicUsize,
+    /// Number of sensors of the handle, fixed until [`PowerMonitor::reinitialize`]
+    sensor_count: i32,
+    /// Open streams, see [`PowerMonitor::sample_stream`]
+    #[cfg(feature = "tokio")]
+    streams: stream::StreamSenders,
+    /// Registers the listener feeding the streams once
+    #[cfg(feature = "tokio")]
+    stream_listener: std::sync::Once,
+    /// Open receivers, see [`PowerMonitor::subscribe`]
+    subscribers: subscribe::Subscribers,
+    /// Registers the listener feeding the receivers once
+    subscribe_listener: std::sync::Once,
+    /// Where the sensors were discovered, reused by [`PowerMonitor::reinitialize`]
+    source: InitSource,
+    /// Set by [`PowerMonitor::close`] so `Drop` does not clean up twice
+    closed: bool,
+}
+
+/// Routes the samples of `handle` to `sampler`
+///
+/// `sampler` must stay at the same address until `handle` is cleaned up.
+fn attach_sampler(handle: NonNull<c_void>, sampler: &Sampler) -> Result<(), Error> {
+    let result = unsafe {
+        pm_set_sample_callback(
+            handle.as_ptr(),
+            Some(sampler::sample_callback),
+            sampler as *const Sampler as *mut c_void,
+        )
+    };
+    check_result("pm_set_sample_callback", result)?;
+    Ok(())
+}
+
+/// Asks the library for the number of sensors of `handle`
+fn query_sensor_count(handle: NonNull<c_void>) -> Result<i32, Error> {
+    let mut count = 0;
+    let result = unsafe { pm_get_sensor_count(handle.as_ptr(), &mut count) };
+    check_result("pm_get_sensor_count", result)?;
+    Ok(count)
+}
+
+/// Converts the return code of the FFI call `call` into a `Result`
+///
+/// A failure discards the cause recorded for an earlier error on this
+/// thread, so [`Error::context`] never attaches it to this one. With the
+/// `tracing` feature, every failure is reported as a debug event
+/// carrying the call, the code and the error. Debug, because the error is
+/// returned as well and some failures are expected, such as `NotRunning`
+/// when a stopped monitor is dropped.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn check_result(call: &'static str, code: i32) -> Result<(), Error> {
+    if code == 0 {
+        return Ok(());
+    }
+    let error = Error::from(code);
+    context::clear_context();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(call, code, error = %error, "FFI call failed");
+    Err(error)
+}
+
+/// Rejects frequencies outside [`MIN_FREQUENCY_HZ`]..=[`MAX_FREQUENCY_HZ`]
+/// before they reach the library
+fn check_frequency(frequency_hz: i32) -> Result<(), Error> {
+    if !(MIN_FREQUENCY_HZ..=MAX_FREQUENCY_HZ).contains(&frequency_hz) {
+        return Err(Error::InvalidFrequency);
+    }
+    Ok(())
+}
+
+/// Decodes a fixed-size, NUL-padded C string buffer
+fn decode_c_str(buf: &[u8]) -> Cow<'_, str> {
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..len])
+}
+
+/// Encodes `text` into a fixed-size, NUL-padded C string buffer
+///
+/// Text that does not fit is truncated at a character boundary so the
+/// buffer always keeps its NUL terminator.
+fn encode_c_str<const N: usize>(text: &str) -> [u8; N] {
+    let mut len = text.len().min(N.saturating_sub(1));
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    let mut buf = [0u8; N];
+    buf[..len].copy_from_slice(&text.as_bytes()[..len]);
+    buf
+}
+
+/// Gets the version of the linked C library, e.g. `"0.0.3"`
+///
+/// With the `system-lib` feature this reports the installed library, which
+/// should match the crate version.
+pub fn version() -> &'static str {
+    // The C library returns a pointer to a static string literal
+    unsafe { std::ffi::CStr::from_ptr(pm_version()) }
+        .to_str()
+        .unwrap_or("unknown")
 }
 
 impl PowerMonitor {
     /// Creates a new power monitor instance
     /// 
     /// This function discovers power sensors on the system and initializes
-    /// the power monitor library.
+    /// the power monitor library. The monitor samples at
+    /// [`DEFAULT_FREQUENCY_HZ`] until another frequency is set. This is
+    /// equivalent to `PowerMonitorConfig::default().build()`.
     /// 
     /// # Returns
     /// 
     /// * `Ok(PowerMonitor)` - A new power monitor instance
+    /// * `Err(Error::InitFailed)` - If the library reports success but
+    ///   returns no handle
     /// * `Err(Error)` - An error code if initialization fails
     pub fn new() -> Result<Self, Error> {
-        let mut handle = std::ptr::null_mut();
-        let result = unsafe { pm_init(&mut handle) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        PowerMonitorConfig::default().build()
+    }
+
+    /// Initializes a library handle from `source` and wraps it
+    ///
+    /// The handle is cleaned up on error and when the returned monitor is
+    /// dropped.
+    fn from_source(source: InitSource) -> Result<Self, Error> {
+        let handle = source.init()?;
+        let sampler = Box::<Sampler>::default();
+        let configured = attach_sampler(handle, &sampler).and_then(|()| query_sensor_count(handle));
+        let sensor_count = match configured {
+            Ok(count) => count,
+            Err(e) => {
+                unsafe { pm_cleanup(handle.as_ptr()) };
+                return Err(e);
+            }
+        };
+        #[cfg(feature = "tracing")]
+        tracing::info!(sensor_count, frequency_hz = DEFAULT_FREQUENCY_HZ, "power monitor initialized");
+        let clipping = Arc::new(Mutex::new(ClippingDetector::new()));
+        let detector = Arc::clone(&clipping);
+        sampler.add_listener(move |data| {
+            detector
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .observe(data.sensors());
+        });
         Ok(Self {
-            handle: NonNull::new(handle).unwrap(),
+            handle,
+            groups: Mutex::new(HashMap::new()),
+            tracker: Mutex::new(SampleTracker::new()),
+            processing: owned::Processing::default(),
+            reset_on_start: true,
+            sampler,
+            ewma: Mutex::new(None),
+            clipping,
+            sampling: AtomicBool::new(false),
+            stop_deadline: Mutex::new(None),
+            metadata: Mutex::new(None),
+            history_capacity: AtomicUsize::new(0),
+            sensor_count,
+            #[cfg(feature = "tokio")]
+            streams: Default::default(),
+            #[cfg(feature = "tokio")]
+            stream_listener: std::sync::Once::new(),
+            subscribers: Default::default(),
+            subscribe_listener: std::sync::Once::new(),
+            source,
+            closed: false,
         })
     }
 
     /// Sets the sampling frequency
     /// 
+    /// May be called while sampling; the new frequency takes effect
+    /// immediately, including for the period already in progress.
+    /// 
     /// # Arguments
     /// 
-    /// * `frequency_hz` - Sampling frequency in Hz (must be > 0)
+    /// * `frequency_hz` - Sampling frequency in Hz, from [`MIN_FREQUENCY_HZ`]
+    ///   to [`MAX_FREQUENCY_HZ`]
     /// 
     /// # Returns
     /// 
     /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidFrequency)` - If `frequency_hz` is out of range
     /// * `Err(Error)` - An error code if setting frequency fails
     pub fn set_sampling_frequency(&self, frequency_hz: i32) -> Result<(), Error> {
+        check_frequency(frequency_hz)?;
         let result = unsafe { pm_set_sampling_frequency(self.handle.as_ptr(), frequency_hz) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_set_sampling_frequency", result)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(frequency_hz, "sampling frequency set");
         Ok(())
     }
 
+    /// Sets the sampling frequency and returns the previous one
+    ///
+    /// The previous frequency is read in the same step as the new one is
+    /// set, so code that boosts the rate temporarily restores exactly what
+    /// it replaced, even if another thread changes the frequency meanwhile.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency_hz` - Sampling frequency in Hz, from [`MIN_FREQUENCY_HZ`]
+    ///   to [`MAX_FREQUENCY_HZ`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(i32)` - The sampling frequency in Hz before the change
+    /// * `Err(Error::InvalidFrequency)` - If `frequency_hz` is out of range;
+    ///   the frequency is left unchanged
+    /// * `Err(Error)` - An error code if setting frequency fails
+    pub fn set_sampling_frequency_swap(&self, frequency_hz: i32) -> Result<i32, Error> {
+        check_frequency(frequency_hz)?;
+        let mut previous_hz = 0;
+        let result = unsafe { pm_swap_sampling_frequency(self.handle.as_ptr(), frequency_hz, &mut previous_hz) };
+        check_result("pm_swap_sampling_frequency", result)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(frequency_hz, previous_hz, "sampling frequency set");
+        Ok(previous_hz)
+    }
+
     /// Gets the current sampling frequency
     /// 
     /// # Returns
@@ -235,26 +826,138 @@ impl PowerMonitor {
     pub fn get_sampling_frequency(&self) -> Result<i32, Error> {
         let mut frequency = 0;
         let result = unsafe { pm_get_sampling_frequency(self.handle.as_ptr(), &mut frequency) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_get_sampling_frequency", result)?;
         Ok(frequency)
     }
 
+    /// Gets the range of sampling frequencies the library accepts
+    ///
+    /// This is [`MIN_FREQUENCY_HZ`] to [`MAX_FREQUENCY_HZ`], as reported by
+    /// the linked library.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((min, max))` - Lowest and highest accepted frequency in Hz
+    /// * `Err(Error)` - An error code if the query fails
+    pub fn frequency_limits(&self) -> Result<(i32, i32), Error> {
+        let (mut min, mut max) = (0, 0);
+        let result = unsafe { pm_get_frequency_limits(self.handle.as_ptr(), &mut min, &mut max) };
+        check_result("pm_get_frequency_limits", result)?;
+        Ok((min, max))
+    }
+
+    /// Sets the time between samples
+    ///
+    /// An alternative to [`PowerMonitor::set_sampling_frequency`] for periods
+    /// that are not a whole number of Hz, such as 1.5 ms. Afterwards
+    /// `get_sampling_frequency` reports the nearest whole rate. May be
+    /// called while sampling, like `set_sampling_frequency`.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Sampling period, corresponding to a rate within
+    ///   [`PowerMonitor::frequency_limits`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidFrequency)` - If `interval` is out of range
+    /// * `Err(Error)` - An error code if setting the interval fails
+    pub fn set_sampling_interval(&self, interval: Duration) -> Result<(), Error> {
+        let interval_ns = u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX);
+        let result = unsafe { pm_set_sampling_interval(self.handle.as_ptr(), interval_ns) };
+        check_result("pm_set_sampling_interval", result)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(interval_ns, "sampling interval set");
+        Ok(())
+    }
+
+    /// Gets the time between samples
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Duration)` - Current sampling period
+    /// * `Err(Error)` - An error code if getting the interval fails
+    pub fn get_sampling_interval(&self) -> Result<Duration, Error> {
+        let mut interval_ns = 0u64;
+        let result = unsafe { pm_get_sampling_interval(self.handle.as_ptr(), &mut interval_ns) };
+        check_result("pm_get_sampling_interval", result)?;
+        Ok(Duration::from_nanos(interval_ns))
+    }
+
     /// Starts sampling
     /// 
     /// This function starts the sampling thread that periodically reads
     /// power data from all discovered sensors.
+    ///
+    /// If the monitor was built with
+    /// [`PowerMonitorBuilder::reset_on_start`] enabled (the default),
+    /// statistics from previous runs are reset before sampling starts.
+    ///
+    /// Starting a capture also records its [`CaptureMetadata`].
     /// 
     /// # Returns
     /// 
     /// * `Ok(())` - Success
     /// * `Err(Error)` - An error code if starting sampling fails
     pub fn start_sampling(&self) -> Result<(), Error> {
-        let result = unsafe { pm_start_sampling(self.handle.as_ptr()) };
-        if result != 0 {
-            return Err(result.into());
+        self.start_with("pm_start_sampling", None, || unsafe { pm_start_sampling(self.handle.as_ptr()) })
+    }
+
+    /// Starts sampling for a fixed duration
+    ///
+    /// Behaves like [`PowerMonitor::start_sampling`] but returns immediately
+    /// and lets the sampling thread stop by itself once `duration` has
+    /// elapsed; [`PowerMonitor::is_sampling`] reports `false` from then on.
+    /// Calling [`PowerMonitor::stop_sampling`] before the deadline stops
+    /// sampling early and cancels the deadline. Open streams end once the
+    /// automatic stop has been observed by `is_sampling`, `stop_sampling`
+    /// or the next start.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How long to sample, with millisecond resolution
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If `duration` is zero
+    /// * `Err(Error)` - An error code if starting sampling fails
+    pub fn start_sampling_for(&self, duration: Duration) -> Result<(), Error> {
+        if duration == Duration::ZERO {
+            return Err(Error::InvalidArgument);
+        }
+        // Round up, so a sub-millisecond duration still takes a sample
+        let duration_ms = ((duration.as_nanos() + 999_999) / 1_000_000).min(i32::MAX as u128) as i32;
+        // Taken before the C library's own deadline, so it never passes later
+        let deadline = Instant::now() + duration;
+        self.start_with("pm_start_sampling_for", Some(deadline), || unsafe {
+            pm_start_sampling_for(self.handle.as_ptr(), duration_ms)
+        })
+    }
+
+    /// Starts sampling through `start`, the FFI call `call`, and updates the
+    /// Rust-side state
+    fn start_with(&self, call: &'static str, deadline: Option<Instant>, start: impl FnOnce() -> i32) -> Result<(), Error> {
+        if self.reset_on_start && !self.is_sampling()? {
+            self.reset_statistics()?;
+        }
+        let result = start();
+        if let Err(e) = check_result(call, result) {
+            self.reconcile_sampling();
+            return Err(e);
         }
+        *self.stop_deadline.lock().unwrap_or_else(|e| e.into_inner()) = deadline;
+        self.sampling.store(true, Ordering::Release);
+        self.tracker().on_start();
+        self.record_metadata();
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            sensor_count = self.sensor_count,
+            frequency_hz = self.get_sampling_frequency().unwrap_or(0),
+            timed = deadline.is_some(),
+            "sampling started"
+        );
         Ok(())
     }
 
@@ -268,68 +971,392 @@ impl PowerMonitor {
     /// * `Err(Error)` - An error code if stopping sampling fails
     pub fn stop_sampling(&self) -> Result<(), Error> {
         let result = unsafe { pm_stop_sampling(self.handle.as_ptr()) };
-        if result != 0 {
-            return Err(result.into());
+        if let Err(e) = check_result("pm_stop_sampling", result) {
+            self.reconcile_sampling();
+            return Err(e);
         }
+        self.sampling.store(false, Ordering::Release);
+        self.on_stopped();
+        #[cfg(feature = "tracing")]
+        tracing::info!(sensor_count = self.sensor_count, "sampling stopped");
         Ok(())
     }
 
+    /// Stops sampling and releases the library handle
+    ///
+    /// Dropping the monitor does the same but has to discard any error;
+    /// prefer `close` when the result of the cleanup matters.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error)` - An error code if stopping sampling or cleaning up fails.
+    ///   The handle is released either way.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.teardown()
+    }
+
+    /// Replaces the library handle with a freshly initialized one
+    ///
+    /// Recovers from a device that disconnected and came back, e.g. after
+    /// a board reset: sampling is stopped and the sensors are discovered
+    /// again the same way the monitor was created, see
+    /// [`PowerMonitor::with_device`] and [`PowerMonitor::with_sysfs_root`].
+    /// The sampling interval and history capacity are carried over, as are
+    /// all Rust-side listeners. Thresholds, statistics and the history are
+    /// not; sampling has to be restarted.
+    ///
+    /// If the sensors cannot be discovered the current handle is kept, so
+    /// the call can simply be retried.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If the sensors are still missing
+    /// * `Err(Error)` - An error code if initialization fails
+    pub fn reinitialize(&mut self) -> Result<(), Error> {
+        let interval = self.get_sampling_interval().ok();
+        match self.stop_sampling() {
+            Ok(()) | Err(Error::NotRunning) => {}
+            Err(e) => return Err(e),
+        }
+
+        let handle = self.source.init()?;
+        let configured = attach_sampler(handle, &self.sampler).and_then(|()| {
+            if let Some(interval) = interval {
+                let result = unsafe { pm_set_sampling_interval(handle.as_ptr(), interval.as_nanos() as u64) };
+                check_result("pm_set_sampling_interval", result)?;
+            }
+            let capacity = self.history_capacity();
+            if capacity > 0 {
+                let result = unsafe { pm_set_history_capacity(handle.as_ptr(), capacity as i32) };
+                check_result("pm_set_history_capacity", result)?;
+            }
+            query_sensor_count(handle)
+        });
+        let sensor_count = match configured {
+            Ok(count) => count,
+            Err(e) => {
+                unsafe { pm_cleanup(handle.as_ptr()) };
+                return Err(e);
+            }
+        };
+
+        self.sensor_count = sensor_count;
+        let old = std::mem::replace(&mut self.handle, handle);
+        unsafe { pm_cleanup(old.as_ptr()) };
+        #[cfg(feature = "tracing")]
+        tracing::info!(sensor_count, "power monitor reinitialized");
+        Ok(())
+    }
+
+    /// Stops sampling, ignoring `NotRunning`, then cleans up the handle
+    fn teardown(&mut self) -> Result<(), Error> {
+        let stopped = match self.stop_sampling() {
+            Ok(()) | Err(Error::NotRunning) => Ok(()),
+            Err(e) => Err(e),
+        };
+        let result = unsafe { pm_cleanup(self.handle.as_ptr()) };
+        self.closed = true;
+        stopped?;
+        check_result("pm_cleanup", result)?;
+        Ok(())
+    }
+
+    /// Updates the Rust-side state after sampling has stopped
+    fn on_stopped(&self) {
+        *self.stop_deadline.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.tracker().on_stop();
+        #[cfg(feature = "tokio")]
+        self.close_streams();
+    }
+
     /// Checks if sampling is active
+    ///
+    /// This reads a Rust-side copy of the sampling state maintained by
+    /// `start_sampling` and `stop_sampling`, so it is cheap enough for busy
+    /// loops and only crosses the FFI boundary once the deadline of
+    /// [`PowerMonitor::start_sampling_for`] has passed. Use
+    /// [`PowerMonitor::is_sampling_uncached`] to query the library directly.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - true if sampling is active, false otherwise
+    /// * `Err(Error)` - An error code if checking status fails
+    pub fn is_sampling(&self) -> Result<bool, Error> {
+        if !self.sampling.load(Ordering::Acquire) {
+            return Ok(false);
+        }
+        let expired = matches!(
+            *self.stop_deadline.lock().unwrap_or_else(|e| e.into_inner()),
+            Some(deadline) if Instant::now() >= deadline
+        );
+        if expired {
+            self.reconcile_sampling();
+        }
+        Ok(self.sampling.load(Ordering::Acquire))
+    }
+
+    /// Checks if sampling is active by querying the C library
     /// 
     /// # Returns
     /// 
     /// * `Ok(bool)` - true if sampling is active, false otherwise
     /// * `Err(Error)` - An error code if checking status fails
-    pub fn is_sampling(&self) -> Result<bool, Error> {
+    pub fn is_sampling_uncached(&self) -> Result<bool, Error> {
         let mut is_sampling = false;
         let result = unsafe { pm_is_sampling(self.handle.as_ptr(), &mut is_sampling) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_is_sampling", result)?;
         Ok(is_sampling)
     }
 
+    /// Pauses sampling without ending the run
+    ///
+    /// Unlike [`PowerMonitor::stop_sampling`], the sampling thread keeps
+    /// running, the statistics and history are kept and open streams and
+    /// receivers stay open, so [`PowerMonitor::resume`] simply continues
+    /// the run, also when [`PowerMonitorBuilder::reset_on_start`] is enabled. No
+    /// sample is taken after this returns. [`PowerMonitor::is_sampling`]
+    /// keeps returning `true`; use [`PowerMonitor::sampling_state`] to tell
+    /// a paused monitor from a running one. The deadline of
+    /// [`PowerMonitor::start_sampling_for`] is not extended by a pause.
+    ///
+    /// Pausing a paused monitor has no effect.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NotRunning)` - If sampling is stopped
+    pub fn pause(&self) -> Result<(), Error> {
+        let result = unsafe { pm_pause_sampling(self.handle.as_ptr()) };
+        if let Err(e) = check_result("pm_pause_sampling", result) {
+            self.reconcile_sampling();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Resumes sampling paused with [`PowerMonitor::pause`]
+    ///
+    /// The pause is not counted as a sampling interval. Resuming a monitor
+    /// that is not paused has no effect.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NotRunning)` - If sampling is stopped
+    pub fn resume(&self) -> Result<(), Error> {
+        let result = unsafe { pm_resume_sampling(self.handle.as_ptr()) };
+        if let Err(e) = check_result("pm_resume_sampling", result) {
+            self.reconcile_sampling();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Gets whether samples are being taken
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SamplingState)` - `Running`, `Paused` or `Stopped`
+    /// * `Err(Error)` - An error code if checking status fails
+    pub fn sampling_state(&self) -> Result<SamplingState, Error> {
+        if !self.is_sampling()? {
+            return Ok(SamplingState::Stopped);
+        }
+        let mut paused = false;
+        let result = unsafe { pm_is_paused(self.handle.as_ptr(), &mut paused) };
+        check_result("pm_is_paused", result)?;
+        Ok(if paused {
+            SamplingState::Paused
+        } else {
+            SamplingState::Running
+        })
+    }
+
+    /// Refreshes the cached sampling state from the C library
+    ///
+    /// Called after an error and once a fixed-duration capture may have
+    /// stopped by itself.
+    fn reconcile_sampling(&self) {
+        if let Ok(sampling) = self.is_sampling_uncached() {
+            if self.sampling.swap(sampling, Ordering::AcqRel) && !sampling {
+                self.on_stopped();
+            }
+        }
+    }
+
+    /// Reads every sensor once, synchronously
+    ///
+    /// This takes a single fresh reading on the calling thread, without the
+    /// background sampling thread, so it suits one-shot measurements such as
+    /// health checks. It works whether or not sampling is active and leaves
+    /// the latest data and the accumulated statistics untouched. The call
+    /// blocks briefly on sensor file I/O. Processing configured through
+    /// [`PowerMonitorBuilder`] is applied as for [`PowerMonitor::get_latest_owned`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - The reading, in the order of `get_latest_data()`
+    /// * `Err(Error)` - An error code if reading fails
+    pub fn read_once(&self) -> Result<OwnedPowerData, Error> {
+        self.read_into("pm_read_once", |data| unsafe { pm_read_once(self.handle.as_ptr(), data) })
+    }
+
+    /// Waits for the sampling thread to take the next sample
+    ///
+    /// Blocks until a sample taken after the call has been published and
+    /// returns it, so successive calls never return the same sample twice.
+    /// Samples published between calls are skipped. Processing is applied as
+    /// for [`PowerMonitor::get_latest_owned`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum time to wait, saturated at `i32::MAX` milliseconds
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - The new sample
+    /// * `Err(Error::Timeout)` - If no sample was taken within `timeout`
+    /// * `Err(Error::NotRunning)` - If sampling is not active or stops while waiting
+    pub fn wait_for_next_sample(&self, timeout: Duration) -> Result<OwnedPowerData, Error> {
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+        self.read_into("pm_wait_for_next_sample", |data| unsafe {
+            pm_wait_for_next_sample(self.handle.as_ptr(), data, timeout_ms)
+        })
+    }
+
+    /// Runs `fill`, the FFI call `call`, on a caller-provided buffer sized
+    /// for every sensor
+    fn read_into(&self, call: &'static str, fill: impl FnOnce(&mut PowerData) -> i32) -> Result<OwnedPowerData, Error> {
+        let count = self.get_sensor_count()?.max(0) as usize;
+        let mut sensors = vec![SensorData::default(); count];
+        let mut data = PowerData {
+            total: SensorData::default(),
+            sensors: sensors.as_mut_ptr(),
+            sensor_count: count as i32,
+        };
+        let result = fill(&mut data);
+        check_result(call, result)?;
+        sensors.truncate(data.sensor_count.max(0) as usize);
+        Ok(self.process_data(OwnedPowerData {
+            total: data.total,
+            sensors,
+        }))
+    }
+
     /// Gets the latest power data
-    /// 
+    ///
+    /// Sensors are ordered by discovery (ascending hwmon index), followed by
+    /// the virtual `PS_TOTAL_POWER`, `PL_TOTAL_POWER` and `TOTAL_POWER`
+    /// sensors. This order is fixed for the lifetime of the monitor and is the
+    /// same as in [`PowerMonitor::get_statistics`], so index `i` of both
+    /// arrays always refers to the same sensor.
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Ok(PowerData)` - Latest power data. Note that the sensors pointer in the returned
-    ///                     structure points to internal library memory and should not be freed.
-    ///                     The pointer is only valid until the next call to this function
-    ///                     or until the PowerMonitor is dropped.
+    ///   structure points to internal library memory and should not be freed.
+    ///   The pointer is only valid until the PowerMonitor is dropped, which
+    ///   the compiler cannot check; prefer [`PowerMonitor::latest`], which
+    ///   returns an owned copy.
     /// * `Err(Error)` - An error code if getting data fails
     pub fn get_latest_data(&self) -> Result<PowerData, Error> {
         let mut data = PowerData {
-            total: unsafe { std::mem::zeroed() },
+            total: SensorData::default(),
             sensors: std::ptr::null(),
             sensor_count: 0,
         };
         let result = unsafe { pm_get_latest_data(self.handle.as_ptr(), &mut data) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_get_latest_data", result)?;
         Ok(data)
     }
 
+    /// Gets the latest power data once a sample has been taken
+    ///
+    /// Right after [`PowerMonitor::start_sampling`] the latest data still
+    /// holds zeros or the readings of a previous run until the sampling
+    /// thread publishes its first sample. This tells the two apart without
+    /// blocking. After sampling stops, the last sample of the run stays
+    /// available.
+    ///
+    /// No processing is applied: like [`PowerMonitor::get_latest_data`], the
+    /// copy holds the unmodified values of every sensor, even if the monitor
+    /// was built with [`PowerMonitorBuilder::quantize`] or
+    /// [`PowerMonitorBuilder::sensors`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(OwnedPowerData))` - Latest power data
+    /// * `Ok(None)` - If no sample has been published since sampling was
+    ///   last started, or sampling was never started
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn try_get_latest_data(&self) -> Result<Option<OwnedPowerData>, Error> {
+        let count = self.sensor_count.max(0) as usize;
+        let mut sensors = vec![SensorData::default(); count];
+        let mut data = PowerData {
+            total: SensorData::default(),
+            sensors: sensors.as_mut_ptr(),
+            sensor_count: count as i32,
+        };
+        let mut available = false;
+        // The library checks for a sample and copies it under the same lock
+        let result = unsafe { pm_try_get_latest_data(self.handle.as_ptr(), &mut data, &mut available) };
+        check_result("pm_try_get_latest_data", result)?;
+        if !available {
+            return Ok(None);
+        }
+        sensors.truncate(data.sensor_count.max(0) as usize);
+        Ok(Some(OwnedPowerData {
+            total: data.total,
+            sensors,
+        }))
+    }
+
+    /// Gets the latest reading of a single sensor
+    ///
+    /// The name is matched exactly against each sensor name with surrounding
+    /// whitespace trimmed. Processing configured through
+    /// [`PowerMonitorBuilder`] is applied first, so sensors outside the
+    /// allowlist are not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name, e.g. `VCCINT`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SensorData)` - A copy of the sensor's latest reading
+    /// * `Err(Error::NoSensors)` - If no sensor has this name
+    /// * `Err(Error)` - An error code if getting the latest data fails
+    pub fn get_sensor_by_name(&self, name: &str) -> Result<SensorData, Error> {
+        let data = self.process_data(self.latest_copy()?);
+        data.sensors
+            .into_iter()
+            .find(|sensor| sensor.name().trim() == name)
+            .ok_or(Error::NoSensors)
+    }
+
     /// Gets the power statistics
+    ///
+    /// Sensor statistics use the same order as the sensor data returned by
+    /// [`PowerMonitor::get_latest_data`].
     /// 
     /// # Returns
     /// 
     /// * `Ok(PowerStats)` - Power statistics. Note that the sensors pointer in the returned
-    ///                      structure points to internal library memory and should not be freed.
-    ///                      The pointer is only valid until the next call to this function
-    ///                      or until the PowerMonitor is dropped.
+    ///   structure points to internal library memory and should not be freed.
+    ///   The pointer is only valid until the next call to this function
+    ///   or until the PowerMonitor is dropped.
     /// * `Err(Error)` - An error code if getting statistics fails
     pub fn get_statistics(&self) -> Result<PowerStats, Error> {
         let mut stats = PowerStats {
-            total: unsafe { std::mem::zeroed() },
+            total: SensorStats::default(),
             sensors: std::ptr::null(),
             sensor_count: 0,
         };
         let result = unsafe { pm_get_statistics(self.handle.as_ptr(), &mut stats) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_get_statistics", result)?;
         Ok(stats)
     }
 
@@ -343,9 +1370,101 @@ impl PowerMonitor {
     /// * `Err(Error)` - An error code if resetting statistics fails
     pub fn reset_statistics(&self) -> Result<(), Error> {
         let result = unsafe { pm_reset_statistics(self.handle.as_ptr()) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_reset_statistics", result)?;
+        self.tracker().on_reset();
+        self.clipping.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        Ok(())
+    }
+
+    /// Gets statistics of the interval actually achieved between samples
+    ///
+    /// At high frequencies the sampling thread may miss its deadlines, which
+    /// biases energy estimates. Comparing the average interval with
+    /// `1 / frequency` shows whether the requested frequency was met. The
+    /// intervals are measured from the sample timestamps in microseconds;
+    /// the gap between two sampling runs is not counted. Reset by
+    /// [`PowerMonitor::reset_statistics`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Stats)` - Min, max and average interval in microseconds; `count`
+    ///   is the number of intervals, one less than the samples of a run
+    /// * `Err(Error)` - An error code if getting the statistics fails
+    pub fn get_jitter_stats(&self) -> Result<Stats, Error> {
+        let mut stats = Stats::default();
+        let result = unsafe { pm_get_interval_statistics(self.handle.as_ptr(), &mut stats) };
+        check_result("pm_get_interval_statistics", result)?;
+        Ok(stats)
+    }
+
+    /// Gets the statistics of a single sensor
+    ///
+    /// Processing configured through [`PowerMonitorBuilder`] is applied as
+    /// for [`PowerMonitor::get_statistics_owned`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SensorStats)` - A copy of the sensor's statistics
+    /// * `Err(Error::NoSensors)` - If `name` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If `name` contains a NUL byte
+    pub fn get_statistics_for_sensor(&self, name: &str) -> Result<SensorStats, Error> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+        let mut stats = SensorStats::default();
+        let result = unsafe { pm_get_sensor_statistics(self.handle.as_ptr(), name.as_ptr(), &mut stats) };
+        check_result("pm_get_sensor_statistics", result)?;
+        Ok(self.process_sensor_stats(stats))
+    }
+
+    /// Resets the statistics of a single sensor
+    ///
+    /// The statistics of every other sensor, including the virtual totals,
+    /// keep accumulating. The virtual totals are derived from the physical
+    /// sensors and can only be reset together with them, through
+    /// [`PowerMonitor::reset_statistics`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If `name` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If `name` is `TOTAL_POWER`,
+    ///   `PS_TOTAL_POWER` or `PL_TOTAL_POWER`, or contains a NUL byte
+    pub fn reset_statistics_for_sensor(&self, name: &str) -> Result<(), Error> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+        let result = unsafe { pm_reset_sensor_statistics(self.handle.as_ptr(), name.as_ptr()) };
+        check_result("pm_reset_sensor_statistics", result)?;
+        Ok(())
+    }
+
+    /// Adds power readings straight to the statistics of a sensor
+    ///
+    /// Only exists in mock builds, for testing the accumulation of long
+    /// captures without sampling for as long.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name
+    /// * `power` - Power reading in watts
+    /// * `count` - Number of readings to add
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If `name` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If `name` contains a NUL byte
+    #[cfg(feature = "mock")]
+    #[doc(hidden)]
+    pub fn mock_add_power_samples(&self, name: &str, power: f64, count: u64) -> Result<(), Error> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+        let result = unsafe { pm_mock_add_power_samples(self.handle.as_ptr(), name.as_ptr(), power, count) };
+        check_result("pm_mock_add_power_samples", result)?;
         Ok(())
     }
 
@@ -356,54 +1475,83 @@ impl PowerMonitor {
     /// # Returns
     ///
     /// * `Ok(PowerSummary)` - Power summary data
+    /// * `Err(Error::NotRunning)` - If sampling has never been started
+    /// * `Err(Error::NoSensors)` - If sampling has started but no sample has
+    ///   been taken yet
     /// * `Err(Error)` - An error code if getting summary fails
     pub fn get_power_summary(&self) -> Result<PowerSummary, Error> {
+        if self.sampler.sample_count() == 0 {
+            return Err(if self.is_sampling()? {
+                Error::NoSensors
+            } else {
+                Error::NotRunning
+            });
+        }
         let mut summary = PowerSummary {
             ps_total_power: 0.0,
             pl_total_power: 0.0,
             total_power: 0.0,
         };
         let result = unsafe { pm_get_power_summary(self.handle.as_ptr(), &mut summary) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_get_power_summary", result)?;
         Ok(summary)
     }
 
     /// Gets the power summary statistics (PS, PL, Total)
     ///
     /// This function returns the power statistics for PS, PL and Total.
+    /// The three statistics are read as one consistent snapshot, so their
+    /// `count` fields are always equal.
     ///
     /// # Returns
     ///
     /// * `Ok(PowerSummaryStats)` - Power summary statistics
     /// * `Err(Error)` - An error code if getting summary statistics fails
     pub fn get_power_summary_stats(&self) -> Result<PowerSummaryStats, Error> {
-        let mut summary_stats = PowerSummaryStats {
-            ps_total_power: unsafe { std::mem::zeroed() },
-            pl_total_power: unsafe { std::mem::zeroed() },
-            total_power: unsafe { std::mem::zeroed() },
-        };
+        let mut summary_stats = PowerSummaryStats::default();
         let result = unsafe { pm_get_power_summary_stats(self.handle.as_ptr(), &mut summary_stats) };
-        if result != 0 {
-            return Err(result.into());
-        }
+        check_result("pm_get_power_summary_stats", result)?;
         Ok(summary_stats)
     }
 
     /// Gets the number of sensors
     ///
+    /// The count is fixed once the sensors are discovered, so it is read
+    /// from the library when the monitor is created and again by
+    /// [`PowerMonitor::reinitialize`], and served from that cache otherwise.
+    /// Calling this in a sampling loop costs no FFI call.
+    ///
     /// # Returns
     ///
-    /// * `Ok(i32)` - Number of sensors
-    /// * `Err(Error)` - An error code if getting sensor count fails
+    /// * `Ok(i32)` - Number of sensors, including the virtual totals
+    /// * `Err(Error)` - Never returned; kept for compatibility
     pub fn get_sensor_count(&self) -> Result<i32, Error> {
-        let mut count = 0;
-        let result = unsafe { pm_get_sensor_count(self.handle.as_ptr(), &mut count) };
-        if result != 0 {
-            return Err(result.into());
-        }
-        Ok(count)
+        Ok(self.sensor_count)
+    }
+
+    /// Gets the sensor names
+    ///
+    /// This function returns the names of all sensors, in the same order as
+    /// the sensor arrays of `get_latest_data()` and `get_statistics()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<String>)` - Vector of sensor names, one per sensor
+    /// * `Err(Error)` - An error code if getting sensor names fails
+    pub fn sensor_names(&self) -> Result<Vec<String>, Error> {
+        // One caller-owned buffer of fixed-size slots, filled by the library
+        let mut count = self.get_sensor_count()?.max(0);
+        let mut names = vec![[0u8; 64]; count as usize];
+        let result = unsafe {
+            pm_copy_sensor_names(
+                self.handle.as_ptr(),
+                names.as_mut_ptr() as *mut std::os::raw::c_char,
+                &mut count,
+            )
+        };
+        check_result("pm_copy_sensor_names", result)?;
+        names.truncate(count.max(0) as usize);
+        Ok(names.iter().map(|name| decode_c_str(name).into_owned()).collect())
     }
 
     /// Gets the sensor names
@@ -413,7 +1561,7 @@ impl PowerMonitor {
     /// # Deprecated
     /// 
     /// This function is deprecated and will be removed in a future version.
-    /// Please use `get_latest_data()` or `get_statistics()` instead to access sensor names.
+    /// It now forwards to [`PowerMonitor::sensor_names`], which should be used instead.
     /// 
     /// # Returns
     /// 
@@ -421,81 +1569,108 @@ impl PowerMonitor {
     /// * `Err(Error)` - An error code if getting sensor names fails
     #[deprecated(
         since = "1.1.0",
-        note = "This function is unsafe and will be removed in a future version. Please use get_latest_data() or get_statistics() instead."
+        note = "This function will be removed in a future version. Please use sensor_names() instead."
     )]
     pub fn get_sensor_names(&self) -> Result<Vec<String>, Error> {
-        let count = self.get_sensor_count()?;
-        let mut names = vec![std::ptr::null_mut(); count as usize];
-        
-        // 为每个字符串分配固定大小的缓冲区 (64字节，与 pm_sensor_data_t 中的 name 字段大小一致)
-        for i in 0..count as usize {
-            names[i] = unsafe { std::alloc::alloc(std::alloc::Layout::array::<i8>(64).unwrap()) as *mut i8 };
-            if names[i].is_null() {
-                // 清理已分配的内存
-                for j in 0..i {
-                    unsafe { std::alloc::dealloc(names[j] as *mut u8, std::alloc::Layout::array::<i8>(64).unwrap()) };
-                }
-                return Err(Error::Memory);
-            }
-        }
-        
-        let mut count = count;
-        let result = unsafe {
-            pm_get_sensor_names(
-                self.handle.as_ptr(),
-                names.as_mut_ptr(),
-                &mut count,
-            )
-        };
-        
-        if result != 0 {
-            // 清理内存
-            for ptr in names.iter() {
-                if !ptr.is_null() {
-                    unsafe { std::alloc::dealloc(*ptr as *mut u8, std::alloc::Layout::array::<i8>(64).unwrap()) };
-                }
-            }
-            return Err(result.into());
-        }
-        
-        let mut result = Vec::with_capacity(count as usize);
-        for ptr in names.into_iter().take(count as usize) {
-            if !ptr.is_null() {
-                unsafe {
-                    let cstr = CString::from_raw(ptr as *mut _);
-                    result.push(cstr.into_string().unwrap_or_default());
-                }
-            }
-        }
-        Ok(result)
+        self.sensor_names()
     }
 }
 
+// SAFETY: the C library takes the handle's data mutex in its getters and
+// setters, and starting/stopping is serialized by a separate control mutex.
+// The latest sample and the statistics are rewritten by the sampling thread:
+// `get_latest_data` and `get_statistics` hand out pointers into those buffers,
+// as their docs state, while every other getter copies them through
+// `pm_copy_latest_data` and `pm_copy_statistics` under the data mutex. The
+// data passed to sample listeners points at a per-callback snapshot. The only
+// call that must not race with others is `pm_cleanup`, which runs in `Drop`
+// and therefore with exclusive access. All Rust-side state is behind mutexes
+// or atomics, and the sample listeners are `Send`.
+unsafe impl Send for PowerMonitor {}
+unsafe impl Sync for PowerMonitor {}
+
 impl Drop for PowerMonitor {
     /// Cleans up resources when the power monitor is dropped
     /// 
     /// This function stops any active sampling and frees all resources
-    /// allocated by the library.
+    /// allocated by the library. Errors are ignored; use
+    /// [`PowerMonitor::close`] to observe them.
     fn drop(&mut self) {
-        unsafe {
-            pm_cleanup(self.handle.as_ptr());
+        if !self.closed {
+            let _ = self.teardown();
         }
     }
 }
 
 extern "C" {
     fn pm_init(handle: *mut *mut c_void) -> i32;
+    fn pm_init_device(handle: *mut *mut c_void, device_id: *const std::os::raw::c_char) -> i32;
+    fn pm_init_sysfs(handle: *mut *mut c_void, sysfs_root: *const std::os::raw::c_char) -> i32;
+    fn pm_enumerate_devices(devices: *mut device::RawDeviceInfo, count: *mut i32) -> i32;
     fn pm_cleanup(handle: *mut c_void) -> i32;
     fn pm_set_sampling_frequency(handle: *mut c_void, frequency_hz: i32) -> i32;
+    fn pm_swap_sampling_frequency(handle: *mut c_void, frequency_hz: i32, previous_hz: *mut i32) -> i32;
     fn pm_get_sampling_frequency(handle: *mut c_void, frequency_hz: *mut i32) -> i32;
+    fn pm_get_frequency_limits(handle: *mut c_void, min_hz: *mut i32, max_hz: *mut i32) -> i32;
+    fn pm_set_sampling_interval(handle: *mut c_void, interval_ns: u64) -> i32;
+    fn pm_get_sampling_interval(handle: *mut c_void, interval_ns: *mut u64) -> i32;
+    fn pm_set_sampling_thread_affinity(handle: *mut c_void, cpus: *const i32, count: i32) -> i32;
+    fn pm_set_sampling_thread_priority(handle: *mut c_void, priority: i32) -> i32;
     fn pm_start_sampling(handle: *mut c_void) -> i32;
+    fn pm_start_sampling_for(handle: *mut c_void, duration_ms: i32) -> i32;
     fn pm_stop_sampling(handle: *mut c_void) -> i32;
     fn pm_is_sampling(handle: *mut c_void, is_sampling: *mut bool) -> i32;
+    fn pm_pause_sampling(handle: *mut c_void) -> i32;
+    fn pm_resume_sampling(handle: *mut c_void) -> i32;
+    fn pm_is_paused(handle: *mut c_void, paused: *mut bool) -> i32;
     fn pm_get_latest_data(handle: *mut c_void, data: *mut PowerData) -> i32;
+    fn pm_copy_latest_data(handle: *mut c_void, buf: *mut SensorData, len: i32, total: *mut SensorData) -> i32;
+    fn pm_try_get_latest_data(handle: *mut c_void, data: *mut PowerData, available: *mut bool) -> i32;
+    fn pm_read_once(handle: *mut c_void, data: *mut PowerData) -> i32;
+    fn pm_wait_for_next_sample(handle: *mut c_void, data: *mut PowerData, timeout_ms: i32) -> i32;
     fn pm_get_statistics(handle: *mut c_void, stats: *mut PowerStats) -> i32;
+    fn pm_copy_statistics(handle: *mut c_void, buf: *mut SensorStats, len: i32, total: *mut SensorStats) -> i32;
     fn pm_reset_statistics(handle: *mut c_void) -> i32;
+    fn pm_get_sensor_statistics(
+        handle: *mut c_void,
+        sensor_name: *const std::os::raw::c_char,
+        stats: *mut SensorStats,
+    ) -> i32;
+    fn pm_reset_sensor_statistics(handle: *mut c_void, sensor_name: *const std::os::raw::c_char) -> i32;
+    fn pm_get_interval_statistics(handle: *mut c_void, stats: *mut Stats) -> i32;
+    fn pm_get_sampling_health(handle: *mut c_void, alive: *mut bool, last_sample_age_ns: *mut u64) -> i32;
     fn pm_get_power_summary(handle: *mut c_void, summary: *mut PowerSummary) -> i32;
     fn pm_get_power_summary_stats(handle: *mut c_void, summary_stats: *mut PowerSummaryStats) -> i32;
     fn pm_get_sensor_count(handle: *mut c_void, count: *mut i32) -> i32;
-    fn pm_get_sensor_names(handle: *mut c_void, names: *mut *mut i8, count: *mut i32) -> i32;
+    fn pm_copy_sensor_names(handle: *mut c_void, names: *mut std::os::raw::c_char, count: *mut i32) -> i32;
+    fn pm_set_history_capacity(handle: *mut c_void, capacity: i32) -> i32;
+    fn pm_get_history(
+        handle: *mut c_void,
+        total: *mut SensorData,
+        sensors: *mut SensorData,
+        count: *mut i32,
+    ) -> i32;
+    fn pm_set_thresholds(
+        handle: *mut c_void,
+        sensor_name: *const std::os::raw::c_char,
+        warning: f64,
+        critical: f64,
+    ) -> i32;
+    fn pm_set_sample_callback(
+        handle: *mut c_void,
+        callback: Option<extern "C" fn(*const PowerData, *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> i32;
+    #[cfg(feature = "mock")]
+    fn pm_mock_set_sensor_online(handle: *mut c_void, sensor_name: *const std::os::raw::c_char, online: bool) -> i32;
+    #[cfg(feature = "mock")]
+    fn pm_mock_add_power_samples(
+        handle: *mut c_void,
+        sensor_name: *const std::os::raw::c_char,
+        power: f64,
+        count: u64,
+    ) -> i32;
+    fn pm_error_string(error: i32) -> *const std::os::raw::c_char;
+    fn pm_get_last_file_error(errnum: *mut i32, path: *mut std::os::raw::c_char, path_len: i32) -> i32;
+    fn pm_version() -> *const std::os::raw::c_char;
 }
\ No newline at end of file
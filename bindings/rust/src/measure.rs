@@ -0,0 +1,89 @@
+//! Measurement protocols built on top of the sampling primitives
+
+use std::time::Duration;
+
+use crate::{Error, OwnedPowerStats, PowerMonitor};
+
+impl PowerMonitor {
+    /// Runs `f` under a clean, warmed-up capture and returns its statistics
+    ///
+    /// This encodes the full measurement protocol: statistics are reset,
+    /// sampling starts, samples taken during `warmup` are discarded by a
+    /// second reset, `f` runs, and sampling stops before the statistics are
+    /// read. Sampling is stopped even if `f` panics.
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup` - Time to sample before the measurement window opens
+    /// * `f` - The workload to measure
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((T, OwnedPowerStats))` - The result of `f` and the statistics of its run
+    /// * `Err(Error)` - An error code if sampling could not be controlled,
+    ///   e.g. `AlreadyRunning` if sampling is active
+    pub fn reset_and_measure<T>(
+        &self,
+        warmup: Duration,
+        f: impl FnOnce() -> T,
+    ) -> Result<(T, OwnedPowerStats), Error> {
+        self.reset_statistics()?;
+        let guard = self.start_sampling_guarded()?;
+
+        std::thread::sleep(warmup);
+        self.reset_statistics()?;
+        let value = f();
+
+        guard.stop()?;
+        Ok((value, self.get_statistics_owned()?))
+    }
+
+    /// Samples for a fixed window and returns its statistics
+    ///
+    /// Statistics are reset, sampling runs for `duration`, and the
+    /// statistics are read after sampling has stopped. Sampling must not be
+    /// active: the running capture is left untouched and an error is
+    /// returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - Length of the sampling window
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerStats)` - Statistics of the window
+    /// * `Err(Error::AlreadyRunning)` - If sampling is already active
+    /// * `Err(Error)` - An error code if sampling could not be controlled
+    pub fn sample_for(&self, duration: Duration) -> Result<OwnedPowerStats, Error> {
+        self.measure(|| std::thread::sleep(duration)).map(|(_, stats)| stats)
+    }
+
+    /// Runs `f` while sampling and returns its result and statistics
+    ///
+    /// Statistics are reset, sampling starts, `f` runs, and the statistics
+    /// are read after sampling has stopped, so they cover the execution of
+    /// `f`. Sampling is stopped even if `f` panics. Sampling must not be
+    /// active: the running capture is left untouched and an error is
+    /// returned instead. Use [`PowerMonitor::reset_and_measure`] to discard
+    /// a warm-up period first.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The workload to measure
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((T, OwnedPowerStats))` - The result of `f` and the statistics of its run
+    /// * `Err(Error::AlreadyRunning)` - If sampling is already active
+    /// * `Err(Error)` - An error code if sampling could not be controlled
+    pub fn measure<T, F: FnOnce() -> T>(&self, f: F) -> Result<(T, OwnedPowerStats), Error> {
+        if self.is_sampling()? {
+            return Err(Error::AlreadyRunning);
+        }
+        self.reset_statistics()?;
+        let guard = self.start_sampling_guarded()?;
+        let value = f();
+        guard.stop()?;
+        Ok((value, self.get_statistics_owned()?))
+    }
+}
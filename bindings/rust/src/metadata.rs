@@ -0,0 +1,157 @@
+//! Provenance of a capture
+//!
+//! Archived captures are only useful if it is known where and how they were
+//! taken. [`CaptureMetadata`] records the host, kernel, board and sampling
+//! configuration at the moment sampling starts, so exports can carry it
+//! along. Everything is read from procfs and the device tree; whatever is not
+//! available on the running system is left as `None`.
+
+use std::fmt::Write;
+use std::fs;
+use std::time::SystemTime;
+#[cfg(not(feature = "serde"))]
+use std::time::UNIX_EPOCH;
+
+use crate::PowerMonitor;
+
+const HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+const KERNEL_VERSION_PATH: &str = "/proc/sys/kernel/osrelease";
+const BOARD_MODEL_PATHS: [&str; 2] = ["/proc/device-tree/model", "/sys/firmware/devicetree/base/model"];
+
+/// Describes the system and configuration a capture was taken with
+///
+/// With the `serde` feature it (de)serializes in the format of
+/// [`CaptureMetadata::to_json`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaptureMetadata {
+    /// Host name of the machine
+    pub hostname: Option<String>,
+    /// Kernel release, as reported by `uname -r`
+    pub kernel_version: Option<String>,
+    /// Board model from the device tree
+    pub board_model: Option<String>,
+    /// Wall-clock time the capture started
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "start_time_unix_ms", with = "crate::serde_impl::unix_ms")
+    )]
+    pub start_time: SystemTime,
+    /// Sampling frequency in Hz at the start of the capture
+    pub sampling_frequency_hz: Option<i32>,
+}
+
+/// Reads a single-line text file, dropping trailing whitespace and NULs
+fn read_trimmed(path: &str) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+/// Writes `value` as a JSON string literal
+pub(crate) fn write_json_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(not(feature = "serde"))]
+fn write_json_opt_str(out: &mut String, value: &Option<String>) {
+    match value {
+        Some(value) => write_json_str(out, value),
+        None => out.push_str("null"),
+    }
+}
+
+impl CaptureMetadata {
+    /// Gathers metadata about the running system, timestamped now
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_frequency_hz` - Sampling frequency of the capture, if known
+    pub fn collect(sampling_frequency_hz: Option<i32>) -> Self {
+        Self {
+            hostname: read_trimmed(HOSTNAME_PATH),
+            kernel_version: read_trimmed(KERNEL_VERSION_PATH),
+            board_model: BOARD_MODEL_PATHS.iter().find_map(|path| read_trimmed(path)),
+            start_time: SystemTime::now(),
+            sampling_frequency_hz,
+        }
+    }
+
+    /// Serializes the metadata as a JSON object
+    ///
+    /// `start_time` is written as milliseconds since the Unix epoch under
+    /// `start_time_unix_ms`; unknown fields are written as `null`. With the
+    /// `serde` feature this uses the derived `Serialize` implementation.
+    pub fn to_json(&self) -> String {
+        self.encode_json()
+    }
+
+    #[cfg(feature = "serde")]
+    fn encode_json(&self) -> String {
+        // Only strings, integers and options, so serialization cannot fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "serde"))]
+    fn encode_json(&self) -> String {
+        let start_ms = self
+            .start_time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+
+        let mut out = String::from("{\"hostname\":");
+        write_json_opt_str(&mut out, &self.hostname);
+        out.push_str(",\"kernel_version\":");
+        write_json_opt_str(&mut out, &self.kernel_version);
+        out.push_str(",\"board_model\":");
+        write_json_opt_str(&mut out, &self.board_model);
+        let _ = write!(out, ",\"start_time_unix_ms\":{}", start_ms);
+        out.push_str(",\"sampling_frequency_hz\":");
+        match self.sampling_frequency_hz {
+            Some(hz) => {
+                let _ = write!(out, "{}", hz);
+            }
+            None => out.push_str("null"),
+        }
+        out.push('}');
+        out
+    }
+}
+
+impl PowerMonitor {
+    /// Gets the metadata recorded when sampling was last started
+    ///
+    /// # Returns
+    ///
+    /// * `Some(CaptureMetadata)` - Metadata of the current or most recent capture
+    /// * `None` - If sampling has never been started
+    pub fn capture_metadata(&self) -> Option<CaptureMetadata> {
+        self.metadata.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Records the metadata of a capture that is starting
+    pub(crate) fn record_metadata(&self) {
+        let metadata = CaptureMetadata::collect(self.get_sampling_frequency().ok());
+        *self.metadata.lock().unwrap_or_else(|e| e.into_inner()) = Some(metadata);
+    }
+}
@@ -0,0 +1,45 @@
+//! Gauge reporting for the `metrics` facade
+//!
+//! Applications that already route telemetry through the `metrics` crate
+//! only need the readings delivered as named gauges. [`PowerMonitor::emit_metrics`]
+//! reports every sample that way, with names that are stable across
+//! releases, to the recorder the application installed.
+
+use crate::PowerMonitor;
+
+/// Gauge with the power of a sensor in watts
+pub const METRIC_POWER: &str = "xlnpwmon.power";
+/// Gauge with the bus voltage of a sensor in volts
+pub const METRIC_VOLTAGE: &str = "xlnpwmon.voltage";
+/// Gauge with the current of a sensor in amperes
+pub const METRIC_CURRENT: &str = "xlnpwmon.current";
+
+impl PowerMonitor {
+    /// Reports every sample as gauges of the `metrics` facade
+    ///
+    /// After every sample, the sampling thread sets the [`METRIC_POWER`],
+    /// [`METRIC_VOLTAGE`] and [`METRIC_CURRENT`] gauges of each sensor, with
+    /// the sensor name as the `sensor` label, on the globally installed
+    /// recorder. The virtual `PS_TOTAL_POWER`, `PL_TOTAL_POWER` and
+    /// `TOTAL_POWER` sensors carry the totals. Processing configured through
+    /// [`crate::PowerMonitorBuilder`] is applied first. Reporting stays
+    /// enabled for the lifetime of the monitor.
+    ///
+    /// ```ignore
+    /// metrics_exporter_prometheus::PrometheusBuilder::new().install()?;
+    /// monitor.emit_metrics();
+    /// monitor.start_sampling()?;
+    /// ```
+    pub fn emit_metrics(&self) {
+        let processing = self.processing.clone();
+        self.sampler.add_listener(move |data| {
+            let data = processing.data(data.to_owned());
+            for sensor in &data.sensors {
+                let name = sensor.name().into_owned();
+                ::metrics::gauge!(METRIC_POWER, "sensor" => name.clone()).set(sensor.power);
+                ::metrics::gauge!(METRIC_VOLTAGE, "sensor" => name.clone()).set(sensor.voltage);
+                ::metrics::gauge!(METRIC_CURRENT, "sensor" => name).set(sensor.current);
+            }
+        });
+    }
+}
@@ -0,0 +1,382 @@
+//! Owned, self-contained copies of power data and statistics
+//!
+//! Unlike [`PowerData`] and [`PowerStats`], which point into library memory,
+//! these types own their sensor arrays and can be kept around freely.
+
+use crate::{check_result, pm_copy_latest_data, pm_copy_statistics, PowerData, PowerMonitor, PowerStats, Error, SensorData, SensorStats, Stats};
+
+/// An owned snapshot of the latest power data
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedPowerData {
+    /// Total power consumption
+    pub total: SensorData,
+    /// Data of every sensor
+    pub sensors: Vec<SensorData>,
+}
+
+/// An owned snapshot of the power statistics
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedPowerStats {
+    /// Total power statistics
+    pub total: SensorStats,
+    /// Statistics of every sensor
+    pub sensors: Vec<SensorStats>,
+}
+
+/// Rounds `value` to `decimals` decimal places
+fn round_to(value: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (value * scale).round() / scale
+}
+
+fn quantize_sensor(sensor: &mut SensorData, decimals: u32) {
+    sensor.voltage = round_to(sensor.voltage, decimals);
+    sensor.current = round_to(sensor.current, decimals);
+    sensor.power = round_to(sensor.power, decimals);
+}
+
+fn quantize_data(data: &mut OwnedPowerData, decimals: u32) {
+    quantize_sensor(&mut data.total, decimals);
+    for sensor in &mut data.sensors {
+        quantize_sensor(sensor, decimals);
+    }
+}
+
+fn quantize_stats(stats: &mut Stats, decimals: u32) {
+    stats.min = round_to(stats.min, decimals);
+    stats.max = round_to(stats.max, decimals);
+    stats.avg = round_to(stats.avg, decimals);
+    stats.total = round_to(stats.total, decimals);
+    stats.stddev = round_to(stats.stddev, decimals);
+}
+
+/// Combines the statistics of two sets of samples
+///
+/// `sum_sq_dev` uses the pairwise update of Chan et al., so the spread of
+/// the merged set is exact rather than an average of the two spreads.
+fn merge_stats(a: &Stats, b: &Stats) -> Stats {
+    if a.count == 0 {
+        return *b;
+    }
+    if b.count == 0 {
+        return *a;
+    }
+    let count = a.count + b.count;
+    let total = a.total + b.total;
+    let delta = b.avg - a.avg;
+    let sum_sq_dev = a.sum_sq_dev + b.sum_sq_dev + delta * delta * (a.count as f64 * b.count as f64) / count as f64;
+    Stats {
+        min: a.min.min(b.min),
+        max: a.max.max(b.max),
+        avg: total / count as f64,
+        total,
+        count,
+        sum_sq_dev,
+        stddev: (sum_sq_dev / count as f64).sqrt(),
+    }
+}
+
+fn merge_sensor_stats(a: &SensorStats, b: &SensorStats) -> SensorStats {
+    SensorStats {
+        name: a.name,
+        voltage: merge_stats(&a.voltage, &b.voltage),
+        current: merge_stats(&a.current, &b.current),
+        power: merge_stats(&a.power, &b.power),
+    }
+}
+
+fn quantize_sensor_stats(stats: &mut SensorStats, decimals: u32) {
+    quantize_stats(&mut stats.voltage, decimals);
+    quantize_stats(&mut stats.current, decimals);
+    quantize_stats(&mut stats.power, decimals);
+}
+
+impl PowerData {
+    /// Copies the data out of library memory into an [`OwnedPowerData`]
+    pub fn to_owned(&self) -> OwnedPowerData {
+        OwnedPowerData {
+            total: self.total,
+            sensors: self.sensors().to_vec(),
+        }
+    }
+}
+
+impl PowerStats {
+    /// Copies the statistics out of library memory into an [`OwnedPowerStats`]
+    pub fn to_owned(&self) -> OwnedPowerStats {
+        OwnedPowerStats {
+            total: self.total,
+            sensors: self.sensors().to_vec(),
+        }
+    }
+}
+
+impl OwnedPowerData {
+    /// Returns the total power in watts
+    pub fn total_power(&self) -> f64 {
+        self.total.power
+    }
+
+    /// Returns a copy with voltage, current and power rounded to `decimals` places
+    pub fn quantized(&self, decimals: u32) -> Self {
+        let mut data = self.clone();
+        quantize_data(&mut data, decimals);
+        data
+    }
+}
+
+impl SensorStats {
+    /// Gets the energy consumed while the statistics were collected
+    ///
+    /// `power.total` is the plain sum of the power samples in watts, not an
+    /// energy. Each sample is taken to stand for one sampling period, so the
+    /// energy is `power.total / sampling_frequency_hz`, which equals the
+    /// average power times the sampled duration `count / sampling_frequency_hz`.
+    /// This assumes the frequency was constant over the whole capture.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_frequency_hz` - Frequency the samples were taken at
+    ///
+    /// # Returns
+    ///
+    /// Energy in joules, or 0 if the frequency is not positive
+    pub fn energy_joules(&self, sampling_frequency_hz: i32) -> f64 {
+        if sampling_frequency_hz <= 0 {
+            return 0.0;
+        }
+        self.power.total / f64::from(sampling_frequency_hz)
+    }
+}
+
+impl OwnedPowerStats {
+    /// Gets the total energy consumed while the statistics were collected
+    ///
+    /// See [`SensorStats::energy_joules`] for how the energy is derived from
+    /// the power samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_frequency_hz` - Frequency the samples were taken at, e.g.
+    ///   from [`PowerMonitor::get_sampling_frequency`]
+    ///
+    /// # Returns
+    ///
+    /// Energy in joules, or 0 if the frequency is not positive
+    pub fn energy_joules(&self, sampling_frequency_hz: i32) -> f64 {
+        self.total.energy_joules(sampling_frequency_hz)
+    }
+
+    /// Combines the statistics of two runs, as if they were one capture
+    ///
+    /// Sensors are matched by name: minima and maxima are combined, sums and
+    /// counts added and the average and standard deviation recomputed from
+    /// them. Sensors present in only one of the runs are carried through
+    /// unchanged, those of `other` after the ones of `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Statistics of the other run
+    ///
+    /// # Returns
+    ///
+    /// The combined statistics
+    pub fn merge(&self, other: &OwnedPowerStats) -> OwnedPowerStats {
+        let mut sensors: Vec<SensorStats> = self
+            .sensors
+            .iter()
+            .map(|sensor| match other.sensors.iter().find(|o| o.name() == sensor.name()) {
+                Some(matching) => merge_sensor_stats(sensor, matching),
+                None => *sensor,
+            })
+            .collect();
+        sensors.extend(
+            other
+                .sensors
+                .iter()
+                .filter(|o| !self.sensors.iter().any(|sensor| sensor.name() == o.name()))
+                .copied(),
+        );
+        OwnedPowerStats {
+            total: merge_sensor_stats(&self.total, &other.total),
+            sensors,
+        }
+    }
+
+    /// Returns a copy with min/max/avg/total/stddev rounded to `decimals` places
+    ///
+    /// Sample counts are left untouched.
+    pub fn quantized(&self, decimals: u32) -> Self {
+        let mut stats = self.clone();
+        quantize_sensor_stats(&mut stats.total, decimals);
+        for sensor in &mut stats.sensors {
+            quantize_sensor_stats(sensor, decimals);
+        }
+        stats
+    }
+}
+
+/// Processing applied to owned outputs, configured through the builder
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Processing {
+    /// Decimal places values are rounded to, if any
+    pub(crate) quantize: Option<u32>,
+    /// Names of the sensors that are kept, or `None` to keep all
+    pub(crate) sensors: Option<Vec<String>>,
+}
+
+impl Processing {
+    fn keeps(&self, name: &str) -> bool {
+        match &self.sensors {
+            Some(sensors) => sensors.iter().any(|sensor| sensor == name),
+            None => true,
+        }
+    }
+
+    /// Applies the processing to owned power data
+    pub(crate) fn data(&self, mut data: OwnedPowerData) -> OwnedPowerData {
+        self.data_in_place(&mut data);
+        data
+    }
+
+    /// Applies the processing to owned power data without reallocating it
+    pub(crate) fn data_in_place(&self, data: &mut OwnedPowerData) {
+        if self.sensors.is_some() {
+            data.sensors.retain(|sensor| self.keeps(&sensor.name()));
+        }
+        if let Some(decimals) = self.quantize {
+            quantize_data(data, decimals);
+        }
+    }
+
+    /// Applies the processing to the statistics of one sensor
+    pub(crate) fn sensor_stats(&self, mut stats: SensorStats) -> SensorStats {
+        if let Some(decimals) = self.quantize {
+            quantize_sensor_stats(&mut stats, decimals);
+        }
+        stats
+    }
+
+    /// Applies the processing to owned power statistics
+    pub(crate) fn stats(&self, mut stats: OwnedPowerStats) -> OwnedPowerStats {
+        if self.sensors.is_some() {
+            stats.sensors.retain(|sensor| self.keeps(&sensor.name()));
+        }
+        match self.quantize {
+            Some(decimals) => stats.quantized(decimals),
+            None => stats,
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Applies the configured processing to owned power data
+    pub(crate) fn process_data(&self, data: OwnedPowerData) -> OwnedPowerData {
+        self.processing.data(data)
+    }
+
+    /// Applies the configured processing to the statistics of one sensor
+    pub(crate) fn process_sensor_stats(&self, stats: SensorStats) -> SensorStats {
+        self.processing.sensor_stats(stats)
+    }
+
+    /// Applies the configured processing to owned power statistics
+    pub(crate) fn process_stats(&self, stats: OwnedPowerStats) -> OwnedPowerStats {
+        self.processing.stats(stats)
+    }
+
+    /// Gets an owned, processed copy of the latest power data
+    ///
+    /// Like [`PowerMonitor::get_latest_data`], but any processing configured
+    /// through [`PowerMonitorBuilder`](crate::PowerMonitorBuilder) (such as
+    /// quantization) has been applied.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - Latest power data
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn get_latest_owned(&self) -> Result<OwnedPowerData, Error> {
+        Ok(self.process_data(self.latest_copy()?))
+    }
+
+    /// Copies the latest data while the library holds its data lock, so it
+    /// is always one complete sample; no processing is applied
+    pub(crate) fn latest_copy(&self) -> Result<OwnedPowerData, Error> {
+        let mut data = OwnedPowerData {
+            total: SensorData::default(),
+            sensors: Vec::new(),
+        };
+        self.copy_latest_data(&mut data)?;
+        Ok(data)
+    }
+
+    /// Copies the statistics while the library holds its data lock, so every
+    /// entry covers the same samples; no processing is applied
+    pub(crate) fn statistics_copy(&self) -> Result<OwnedPowerStats, Error> {
+        let count = self.sensor_count.max(0) as usize;
+        let mut sensors: Vec<SensorStats> = Vec::with_capacity(count);
+        let mut total = SensorStats::default();
+        let result = unsafe {
+            pm_copy_statistics(self.handle.as_ptr(), sensors.as_mut_ptr(), count as i32, &mut total)
+        };
+        check_result("pm_copy_statistics", result)?;
+        // SAFETY: the library initialized the first `count` entries
+        unsafe { sensors.set_len(count) };
+        Ok(OwnedPowerStats { total, sensors })
+    }
+
+    /// Copies the latest data into `buf` while the library holds its data
+    /// lock, replacing its sensors
+    ///
+    /// On error `buf` holds no sensors.
+    pub(crate) fn copy_latest_data(&self, buf: &mut OwnedPowerData) -> Result<(), Error> {
+        let count = self.sensor_count.max(0) as usize;
+        buf.sensors.clear();
+        buf.sensors.reserve(count);
+        let result = unsafe {
+            pm_copy_latest_data(self.handle.as_ptr(), buf.sensors.as_mut_ptr(), count as i32, &mut buf.total)
+        };
+        check_result("pm_copy_latest_data", result)?;
+        // SAFETY: the library initialized the first `count` entries
+        unsafe { buf.sensors.set_len(count) };
+        Ok(())
+    }
+
+    /// Copies the latest power data into a reusable buffer
+    ///
+    /// Fills `buf` like [`PowerMonitor::get_latest_owned`] but reuses its
+    /// sensor vector, which only grows when the sensor count exceeds its
+    /// capacity. A polling loop that keeps passing the same buffer therefore
+    /// does not allocate after the first call. The copy is made while the
+    /// sampling thread is locked out. On error `buf` holds no sensors.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - Buffer overwritten with the latest data
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn get_latest_data_into(&self, buf: &mut OwnedPowerData) -> Result<(), Error> {
+        self.copy_latest_data(buf)?;
+        self.processing.data_in_place(buf);
+        Ok(())
+    }
+
+    /// Gets an owned, processed copy of the power statistics
+    ///
+    /// Like [`PowerMonitor::get_statistics`], but any processing configured
+    /// through [`PowerMonitorBuilder`](crate::PowerMonitorBuilder) (such as
+    /// quantization) has been applied.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerStats)` - Power statistics
+    /// * `Err(Error)` - An error code if getting statistics fails
+    pub fn get_statistics_owned(&self) -> Result<OwnedPowerStats, Error> {
+        Ok(self.process_stats(self.statistics_copy()?))
+    }
+}
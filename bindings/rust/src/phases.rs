@@ -0,0 +1,124 @@
+//! Labeled measurement phases
+//!
+//! A [`PhaseRecorder`] splits a running capture into named intervals. Each
+//! call to [`PhaseRecorder::mark`] ends the current phase and starts the next
+//! one; [`PhaseRecorder::finish`] ends the last phase and reports the samples
+//! captured in each interval.
+
+use std::time::{Duration, Instant};
+
+use crate::{Error, PowerMonitor};
+
+/// Power attributed to one labeled phase
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseReport {
+    /// Phase label passed to [`PhaseRecorder::mark`]
+    pub name: String,
+    /// Wall-clock duration of the phase
+    pub duration: Duration,
+    /// Number of samples captured during the phase
+    pub samples: u64,
+    /// Average total power during the phase in watts
+    pub avg_power: f64,
+    /// Energy consumed during the phase in joules
+    pub energy: f64,
+}
+
+/// Running totals of the total power statistics at a mark
+#[derive(Debug, Clone, Copy)]
+struct Checkpoint {
+    at: Instant,
+    sum: f64,
+    count: u64,
+}
+
+/// Records labeled phases of a workload, see [`PowerMonitor::phase_recorder`]
+pub struct PhaseRecorder<'a> {
+    monitor: &'a PowerMonitor,
+    current: Option<(String, Checkpoint)>,
+    reports: Vec<PhaseReport>,
+}
+
+impl<'a> PhaseRecorder<'a> {
+    fn checkpoint(&self) -> Result<Checkpoint, Error> {
+        let stats = self.monitor.statistics_copy()?;
+        Ok(Checkpoint {
+            at: Instant::now(),
+            sum: stats.total.power.total,
+            count: stats.total.power.count,
+        })
+    }
+
+    fn close_current(&mut self, end: Checkpoint) {
+        if let Some((name, start)) = self.current.take() {
+            // A statistics reset during the phase restarts the running totals
+            let (sum, samples) = if end.count >= start.count {
+                (end.sum - start.sum, end.count - start.count)
+            } else {
+                (end.sum, end.count)
+            };
+            let duration = end.at - start.at;
+            let avg_power = if samples > 0 { sum / samples as f64 } else { 0.0 };
+            self.reports.push(PhaseReport {
+                name,
+                duration,
+                samples,
+                avg_power,
+                energy: avg_power * duration.as_secs_f64(),
+            });
+        }
+    }
+
+    /// Ends the current phase (if any) and starts a new one named `name`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error)` - An error code if reading the statistics fails
+    pub fn mark(&mut self, name: &str) -> Result<(), Error> {
+        let checkpoint = self.checkpoint()?;
+        self.close_current(checkpoint);
+        self.current = Some((name.to_string(), checkpoint));
+        Ok(())
+    }
+
+    /// Ends the last phase and returns the reports of all phases in order
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PhaseReport>)` - One report per marked phase
+    /// * `Err(Error)` - An error code if reading the statistics fails
+    pub fn finish(mut self) -> Result<Vec<PhaseReport>, Error> {
+        let checkpoint = self.checkpoint()?;
+        self.close_current(checkpoint);
+        Ok(self.reports)
+    }
+}
+
+impl PowerMonitor {
+    /// Creates a recorder that attributes captured samples to labeled phases
+    ///
+    /// Sampling must be running while phases are recorded. Samples are
+    /// attributed from the running total power statistics, so statistics
+    /// should not be reset while a phase is open.
+    ///
+    /// ```no_run
+    /// # let monitor = xlnpwmon::PowerMonitor::new().unwrap();
+    /// monitor.start_sampling().unwrap();
+    /// let mut recorder = monitor.phase_recorder();
+    /// recorder.mark("load").unwrap();
+    /// // ... load data ...
+    /// recorder.mark("compute").unwrap();
+    /// // ... compute ...
+    /// for phase in recorder.finish().unwrap() {
+    ///     println!("{}: {:.2} W, {:.2} J", phase.name, phase.avg_power, phase.energy);
+    /// }
+    /// ```
+    pub fn phase_recorder(&self) -> PhaseRecorder<'_> {
+        PhaseRecorder {
+            monitor: self,
+            current: None,
+            reports: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Prometheus text exposition of the latest readings
+//!
+//! Boards running in a cluster are typically scraped by Prometheus. The
+//! exporter renders the latest data in the text exposition format, so it can
+//! be served from any HTTP endpoint without a client library.
+
+use std::fmt::Write;
+
+use crate::{Error, OwnedPowerData, PowerMonitor, SensorData};
+
+/// Escapes a sensor name for use as a label value
+///
+/// Backslashes, double quotes and newlines are escaped as the format
+/// requires; other control characters are replaced by `_`.
+fn label_value(name: &str) -> String {
+    let mut value = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => value.push_str("\\\\"),
+            '"' => value.push_str("\\\""),
+            '\n' => value.push_str("\\n"),
+            c if c.is_control() => value.push('_'),
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+/// Formats a sample value, spelling non-finite values as Prometheus does
+fn sample_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes one per-sensor gauge family
+fn write_family(out: &mut String, name: &str, help: &str, sensors: &[SensorData], value: fn(&SensorData) -> f64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for sensor in sensors {
+        let _ = writeln!(
+            out,
+            "{}{{sensor=\"{}\"}} {}",
+            name,
+            label_value(&sensor.name()),
+            sample_value(value(sensor))
+        );
+    }
+}
+
+impl OwnedPowerData {
+    /// Renders the data in the Prometheus text exposition format
+    ///
+    /// Emits the gauges `xlnpwmon_power_watts`, `xlnpwmon_voltage_volts` and
+    /// `xlnpwmon_current_amperes` with one sample per sensor, labeled by
+    /// `sensor`, followed by `xlnpwmon_power_total_watts` for the total power.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        write_family(
+            &mut out,
+            "xlnpwmon_power_watts",
+            "Latest power of each sensor in watts.",
+            &self.sensors,
+            |sensor| sensor.power,
+        );
+        write_family(
+            &mut out,
+            "xlnpwmon_voltage_volts",
+            "Latest bus voltage of each sensor in volts.",
+            &self.sensors,
+            |sensor| sensor.voltage,
+        );
+        write_family(
+            &mut out,
+            "xlnpwmon_current_amperes",
+            "Latest current of each sensor in amperes.",
+            &self.sensors,
+            |sensor| sensor.current,
+        );
+        out.push_str("# HELP xlnpwmon_power_total_watts Latest total power in watts.\n");
+        out.push_str("# TYPE xlnpwmon_power_total_watts gauge\n");
+        let _ = writeln!(out, "xlnpwmon_power_total_watts {}", sample_value(self.total.power));
+        out
+    }
+}
+
+impl PowerMonitor {
+    /// Renders the latest data as Prometheus exposition text
+    ///
+    /// Processing configured through [`crate::PowerMonitorBuilder`] is
+    /// applied first. See [`OwnedPowerData::to_prometheus`] for the metrics.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The metrics, ready to be served to a scraper
+    /// * `Err(Error)` - An error code if getting the latest data fails
+    pub fn prometheus_metrics(&self) -> Result<String, Error> {
+        let data = self.process_data(self.latest_copy()?);
+        Ok(data.to_prometheus())
+    }
+}
@@ -0,0 +1,154 @@
+//! Classification of sensors by the Xilinx rail they measure
+//!
+//! Sensor names follow the rail names of the board schematics, e.g.
+//! `VCCINT` for the PL core or `VCC_PSINTLP` for the PS low-power domain.
+//! [`Rail`] decodes those names once, so callers can group readings by
+//! [`PowerDomain`] instead of matching strings themselves.
+
+use crate::SensorData;
+
+/// Part of the device a rail supplies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerDomain {
+    /// Processing system, including the Versal PMC
+    Ps,
+    /// Programmable logic
+    Pl,
+    /// Multi-gigabit transceivers (GTR, GTH, GTY)
+    Transceiver,
+    /// Board rails and anything not attributable to one domain
+    Other,
+}
+
+/// A known Xilinx supply rail
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rail {
+    /// PS full-power domain core, `VCCPSINTFP` (Versal: `VCC_PSFP`)
+    VccPsIntFp,
+    /// PS low-power domain core, `VCCPSINTLP` (Versal: `VCC_PSLP`)
+    VccPsIntLp,
+    /// PS auxiliary, `VCCPSAUX`
+    VccPsAux,
+    /// PS PLLs, `VCCPSPLL`
+    VccPsPll,
+    /// PS DDR PLL, `VCCPSDDRPLL`
+    VccPsDdrPll,
+    /// PS DDR I/O, `VCCO_PSDDR`
+    VccoPsDdr,
+    /// PS MIO banks, `VCCOPS`
+    VccoPs,
+    /// Versal platform management controller, `VCC_PMC`
+    VccPmc,
+    /// PL core, `VCCINT`
+    VccInt,
+    /// PL block RAM, `VCCBRAM`
+    VccBram,
+    /// PL auxiliary, `VCCAUX`
+    VccAux,
+    /// Versal system-on-chip fabric (NoC, DDRMC), `VCC_SOC`
+    VccSoc,
+    /// Versal block and UltraRAM, `VCC_RAM`
+    VccRam,
+    /// PS-GTR transceiver analog supply, `MGTRAVCC`
+    MgtrAvcc,
+    /// PS-GTR transceiver termination, `MGTRAVTT`
+    MgtrAvtt,
+    /// PL transceiver analog supply, `MGTAVCC` (Versal: `MGTYAVCC`)
+    MgtAvcc,
+    /// PL transceiver termination, `MGTAVTT` (Versal: `MGTYAVTT`)
+    MgtAvtt,
+    /// PL transceiver auxiliary, `MGTVCCAUX` (Versal: `MGTYVCCAUX`)
+    MgtVccAux,
+    /// FMC adjustable I/O voltage, `VADJ_FMC`
+    VadjFmc,
+    /// Board 1.2 V rail, `VCC1V2`
+    Vcc1V2,
+    /// Board 3.3 V rail, `VCC3V3`
+    Vcc3V3,
+    /// A rail not known to this crate, with the name it was reported under
+    Unknown(String),
+}
+
+impl Rail {
+    /// Classifies a sensor name
+    ///
+    /// Matching ignores case, underscores and a trailing bank or voltage
+    /// suffix, so `VCC_PSINTLP`, `vccpsintlp` and `VCCO_PSDDR_504` are all
+    /// recognized.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name, e.g. `VCCINT`
+    ///
+    /// # Returns
+    ///
+    /// The rail, or `Rail::Unknown` holding `name` unchanged
+    pub fn from_name(name: &str) -> Rail {
+        let full: String = name
+            .chars()
+            .filter(|c| *c != '_')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        // Bank numbers (`VCCO_PSDDR_504`) and voltages (`VCCOPS3`) follow
+        // the rail name, except for the board rails named by their voltage
+        let key = match full.as_str() {
+            "VCC1V2" | "VCC3V3" => full.as_str(),
+            _ => full.trim_end_matches(|c: char| c.is_ascii_digit()),
+        };
+        match key {
+            "VCCPSINTFP" | "VCCPSFP" => Rail::VccPsIntFp,
+            "VCCPSINTLP" | "VCCPSLP" => Rail::VccPsIntLp,
+            "VCCPSAUX" => Rail::VccPsAux,
+            "VCCPSPLL" => Rail::VccPsPll,
+            "VCCPSDDRPLL" | "VCCPSDDDRPLL" => Rail::VccPsDdrPll,
+            "VCCOPSDDR" => Rail::VccoPsDdr,
+            "VCCOPS" => Rail::VccoPs,
+            "VCCPMC" => Rail::VccPmc,
+            "VCCINT" => Rail::VccInt,
+            "VCCBRAM" => Rail::VccBram,
+            "VCCAUX" => Rail::VccAux,
+            "VCCSOC" => Rail::VccSoc,
+            "VCCRAM" => Rail::VccRam,
+            "MGTRAVCC" => Rail::MgtrAvcc,
+            "MGTRAVTT" => Rail::MgtrAvtt,
+            "MGTAVCC" | "MGTYAVCC" => Rail::MgtAvcc,
+            "MGTAVTT" | "MGTYAVTT" => Rail::MgtAvtt,
+            "MGTVCCAUX" | "MGTYVCCAUX" => Rail::MgtVccAux,
+            "VADJFMC" => Rail::VadjFmc,
+            "VCC1V2" => Rail::Vcc1V2,
+            "VCC3V3" => Rail::Vcc3V3,
+            _ => Rail::Unknown(name.to_string()),
+        }
+    }
+
+    /// Gets the domain the rail supplies
+    ///
+    /// The PS-GTR rails count as [`PowerDomain::Transceiver`] even though
+    /// the C library sums them into `PS_TOTAL_POWER`.
+    pub fn domain(&self) -> PowerDomain {
+        match self {
+            Rail::VccPsIntFp
+            | Rail::VccPsIntLp
+            | Rail::VccPsAux
+            | Rail::VccPsPll
+            | Rail::VccPsDdrPll
+            | Rail::VccoPsDdr
+            | Rail::VccoPs
+            | Rail::VccPmc => PowerDomain::Ps,
+            Rail::VccInt | Rail::VccBram | Rail::VccAux | Rail::VccSoc | Rail::VccRam => PowerDomain::Pl,
+            Rail::MgtrAvcc | Rail::MgtrAvtt | Rail::MgtAvcc | Rail::MgtAvtt | Rail::MgtVccAux => {
+                PowerDomain::Transceiver
+            }
+            Rail::VadjFmc | Rail::Vcc1V2 | Rail::Vcc3V3 | Rail::Unknown(_) => PowerDomain::Other,
+        }
+    }
+}
+
+impl SensorData {
+    /// Classifies the sensor by its name, see [`Rail::from_name`]
+    pub fn rail(&self) -> Rail {
+        Rail::from_name(&self.name())
+    }
+}
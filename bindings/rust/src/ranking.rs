@@ -0,0 +1,81 @@
+//! Ranking of sensors by power
+//!
+//! Boards can expose dozens of rails. Ranking them by their latest power
+//! reading, or breaking the total down into per-rail shares, points at the
+//! biggest contributors without iterating manually.
+
+use crate::{Error, OwnedPowerData, PowerMonitor};
+
+/// Names of the virtual sensors the C library appends after the physical ones
+pub(crate) const VIRTUAL_SENSORS: [&str; 3] = ["PS_TOTAL_POWER", "PL_TOTAL_POWER", "TOTAL_POWER"];
+
+impl OwnedPowerData {
+    /// Gets the physical sensors with the highest power
+    ///
+    /// The virtual `PS_TOTAL_POWER`, `PL_TOTAL_POWER` and `TOTAL_POWER`
+    /// sensors are sums of the others and are not ranked. Sensors with equal
+    /// power keep their sensor order.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Maximum number of sensors to return; all are returned if there
+    ///   are fewer
+    ///
+    /// # Returns
+    ///
+    /// `(sensor name, power in watts)` pairs, highest power first
+    pub fn top_consumers(&self, n: usize) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = self
+            .sensors
+            .iter()
+            .map(|sensor| (sensor.name().into_owned(), sensor.power))
+            .filter(|(name, _)| !VIRTUAL_SENSORS.contains(&name.as_str()))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Gets each physical sensor's share of the total power
+    ///
+    /// Shares are relative to `total.power`. The virtual total sensors are
+    /// excluded, as for [`OwnedPowerData::top_consumers`]. If the total power
+    /// is zero every share is 0%.
+    ///
+    /// # Returns
+    ///
+    /// `(sensor name, percentage of total power)` pairs in sensor order
+    pub fn contributions(&self) -> Vec<(String, f64)> {
+        let total = self.total_power();
+        self.sensors
+            .iter()
+            .map(|sensor| (sensor.name().into_owned(), sensor.power))
+            .filter(|(name, _)| !VIRTUAL_SENSORS.contains(&name.as_str()))
+            .map(|(name, power)| {
+                let share = if total == 0.0 { 0.0 } else { power / total * 100.0 };
+                (name, share)
+            })
+            .collect()
+    }
+}
+
+impl PowerMonitor {
+    /// Gets the sensors with the highest power in the latest data
+    ///
+    /// Processing configured through [`crate::PowerMonitorBuilder`] is applied
+    /// first, so only allowed sensors are ranked.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Maximum number of sensors to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(String, f64)>)` - Sensor names and power in watts, highest
+    ///   first, see [`OwnedPowerData::top_consumers`]
+    /// * `Err(Error)` - An error code if getting the latest data fails
+    pub fn top_consumers(&self, n: usize) -> Result<Vec<(String, f64)>, Error> {
+        let data = self.latest_copy()?;
+        Ok(self.process_data(data).top_consumers(n))
+    }
+}
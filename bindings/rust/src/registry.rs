@@ -0,0 +1,103 @@
+//! Multi-board registry
+//!
+//! In multi-FPGA chassis every board is monitored by its own library
+//! instance. A [`PowerMonitorRegistry`] keeps those instances together under
+//! board identifiers and provides a chassis-level view of them.
+
+use std::collections::BTreeMap;
+
+use crate::{Error, PowerMonitor, PowerSummary};
+
+/// A source of PS/PL/total power summaries, such as a [`PowerMonitor`]
+pub trait PowerSource {
+    /// Gets the latest PS, PL and total power of this source
+    fn get_power_summary(&self) -> Result<PowerSummary, Error>;
+}
+
+impl PowerSource for PowerMonitor {
+    fn get_power_summary(&self) -> Result<PowerSummary, Error> {
+        PowerMonitor::get_power_summary(self)
+    }
+}
+
+/// A collection of monitors keyed by board identifier
+///
+/// Boards are identified by caller-chosen strings. The library has no notion
+/// of a board serial number, so a stable choice is the board's index in the
+/// chassis (`"0"`, `"1"`, ...) or a serial read from the board's own
+/// inventory. Boards are iterated in identifier order.
+#[derive(Debug)]
+pub struct PowerMonitorRegistry<M = PowerMonitor> {
+    boards: BTreeMap<String, M>,
+}
+
+impl<M> Default for PowerMonitorRegistry<M> {
+    fn default() -> Self {
+        Self {
+            boards: BTreeMap::new(),
+        }
+    }
+}
+
+impl<M: PowerSource> PowerMonitorRegistry<M> {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a board, returning the monitor previously registered under `id`
+    pub fn insert(&mut self, id: &str, monitor: M) -> Option<M> {
+        self.boards.insert(id.to_string(), monitor)
+    }
+
+    /// Removes a board, returning its monitor
+    pub fn remove(&mut self, id: &str) -> Option<M> {
+        self.boards.remove(id)
+    }
+
+    /// Gets the monitor of a board
+    pub fn get(&self, id: &str) -> Option<&M> {
+        self.boards.get(id)
+    }
+
+    /// Gets the identifiers of all registered boards
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.boards.keys().map(|id| id.as_str())
+    }
+
+    /// Iterates over all boards and their monitors
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &M)> {
+        self.boards.iter().map(|(id, monitor)| (id.as_str(), monitor))
+    }
+
+    /// Gets the number of registered boards
+    pub fn len(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// Checks if no boards are registered
+    pub fn is_empty(&self) -> bool {
+        self.boards.is_empty()
+    }
+
+    /// Sums the PS, PL and total power across all boards
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerSummary)` - Chassis-level power summary
+    /// * `Err(Error)` - The first error reported by any board
+    pub fn aggregate_summary(&self) -> Result<PowerSummary, Error> {
+        let mut total = PowerSummary {
+            ps_total_power: 0.0,
+            pl_total_power: 0.0,
+            total_power: 0.0,
+        };
+        for monitor in self.boards.values() {
+            let summary = monitor.get_power_summary()?;
+            total.ps_total_power += summary.ps_total_power;
+            total.pl_total_power += summary.pl_total_power;
+            total.total_power += summary.total_power;
+        }
+        Ok(total)
+    }
+}
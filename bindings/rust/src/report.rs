@@ -0,0 +1,171 @@
+//! Human-readable and machine-readable reports of owned power data and
+//! statistics
+
+use std::fmt::Write;
+
+use crate::metadata::write_json_str;
+use crate::ranking::VIRTUAL_SENSORS;
+use crate::{CaptureMetadata, OwnedPowerData, OwnedPowerStats, SensorData, SensorStats, Stats};
+
+/// Escapes characters that would break a Markdown table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn markdown_row(label: Option<&str>, sensor: &SensorData) -> String {
+    let name = sensor.name();
+    let status = sensor.status();
+    format!(
+        "| {} | {:?} | {:.3} | {:.3} | {:.3} | {} | {} |\n",
+        markdown_cell(label.unwrap_or(&name)),
+        sensor.type_,
+        sensor.voltage,
+        sensor.current,
+        sensor.power,
+        if sensor.online { "yes" } else { "no" },
+        markdown_cell(&status),
+    )
+}
+
+impl OwnedPowerData {
+    /// Formats the sensor table as a GitHub-flavored Markdown table
+    ///
+    /// The table has one row per sensor followed by a bold total row, with
+    /// the columns name, type, voltage (V), current (A), power (W), online
+    /// and status. Numeric columns are right-aligned.
+    pub fn to_markdown_table(&self) -> String {
+        let mut table = String::from(
+            "| Name | Type | Voltage (V) | Current (A) | Power (W) | Online | Status |\n\
+             |:-----|:-----|------------:|------------:|----------:|:------:|:-------|\n",
+        );
+        for sensor in &self.sensors {
+            table.push_str(&markdown_row(None, sensor));
+        }
+        table.push_str(&markdown_row(Some("**Total**"), &self.total));
+        table
+    }
+}
+
+/// Writes a number as JSON, with non-finite values as `null`
+fn write_json_f64(out: &mut String, value: f64) {
+    if value.is_finite() {
+        let _ = write!(out, "{}", value);
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn write_json_stats(out: &mut String, stats: &Stats) {
+    out.push_str("{\"min\":");
+    write_json_f64(out, stats.min);
+    out.push_str(",\"max\":");
+    write_json_f64(out, stats.max);
+    out.push_str(",\"avg\":");
+    write_json_f64(out, stats.avg);
+    out.push_str(",\"total\":");
+    write_json_f64(out, stats.total);
+    out.push_str(",\"stddev\":");
+    write_json_f64(out, stats.stddev);
+    let _ = write!(out, ",\"count\":{}}}", stats.count);
+}
+
+fn write_json_sensor_stats(out: &mut String, stats: &SensorStats) {
+    out.push_str("{\"voltage\":");
+    write_json_stats(out, &stats.voltage);
+    out.push_str(",\"current\":");
+    write_json_stats(out, &stats.current);
+    out.push_str(",\"power\":");
+    write_json_stats(out, &stats.power);
+    out.push('}');
+}
+
+fn write_report_section(out: &mut String, title: &str, stats: &SensorStats, frequency: i32, share: Option<f64>) {
+    let _ = writeln!(out, "{}:", title);
+    let _ = writeln!(out, "  Power (W): {}", stats.power);
+    let _ = writeln!(out, "  Energy: {:.2} J", stats.energy_joules(frequency));
+    let _ = writeln!(out, "  Samples: {}", stats.power.count);
+    if let Some(share) = share {
+        let _ = writeln!(out, "  Share: {:.1}% of total energy", share);
+    }
+}
+
+impl OwnedPowerStats {
+    /// Formats a summary of the whole run
+    ///
+    /// The report starts with a `Total:` section followed by one
+    /// `Sensor <name>:` section per sensor, each with the power range and
+    /// average, the energy and the sample count. Physical sensors also get
+    /// their share of the total energy; the virtual totals, which are sums
+    /// of the others, do not. Energies are derived as in
+    /// [`SensorStats::energy_joules`].
+    ///
+    /// # Arguments
+    ///
+    /// * `sampling_frequency_hz` - Frequency the samples were taken at, e.g.
+    ///   from [`crate::PowerMonitor::get_sampling_frequency`]
+    ///
+    /// # Returns
+    ///
+    /// The multi-line report, ending with a newline
+    pub fn report(&self, sampling_frequency_hz: i32) -> String {
+        let mut out = String::new();
+        write_report_section(&mut out, "Total", &self.total, sampling_frequency_hz, None);
+        let total = self.total.power.total;
+        for sensor in &self.sensors {
+            let name = sensor.name();
+            let share = if VIRTUAL_SENSORS.contains(&name.as_ref()) {
+                None
+            } else if total == 0.0 {
+                Some(0.0)
+            } else {
+                Some(sensor.power.total / total * 100.0)
+            };
+            out.push('\n');
+            write_report_section(
+                &mut out,
+                &format!("Sensor {}", name),
+                sensor,
+                sampling_frequency_hz,
+                share,
+            );
+        }
+        out
+    }
+
+    /// Serializes the statistics as a JSON object
+    ///
+    /// The object has a `total` entry and a `sensors` object keyed by sensor
+    /// name, in sensor order. Each entry holds `voltage`, `current` and
+    /// `power` objects with `min`, `max`, `avg`, `total` and `count`.
+    /// Non-finite values are written as `null`. No `serde` feature is needed.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"total\":");
+        write_json_sensor_stats(&mut out, &self.total);
+        out.push_str(",\"sensors\":{");
+        for (i, sensor) in self.sensors.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_str(&mut out, &sensor.name());
+            out.push(':');
+            write_json_sensor_stats(&mut out, sensor);
+        }
+        out.push_str("}}");
+        out
+    }
+
+    /// Serializes the statistics as a JSON object, with capture metadata
+    ///
+    /// Like [`OwnedPowerStats::to_json`], with the object of
+    /// [`CaptureMetadata::to_json`] added under a leading `metadata` key, so
+    /// the export records where and how the capture was taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Metadata of the capture, e.g. from
+    ///   [`crate::PowerMonitor::capture_metadata`]
+    pub fn to_json_with_metadata(&self, metadata: &CaptureMetadata) -> String {
+        let stats = self.to_json();
+        format!("{{\"metadata\":{},{}", metadata.to_json(), &stats[1..])
+    }
+}
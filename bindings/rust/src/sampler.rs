@@ -0,0 +1,208 @@
+//! Per-sample processing on the Rust side
+//!
+//! The C library invokes a callback after every sample it takes. The
+//! [`Sampler`] receives that callback and fans the sample out to the
+//! listeners registered by the Rust API, so derived signals such as the
+//! [`Ewma`] are updated once per sample instead of once per poll.
+
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::{Error, PowerData, PowerMonitor};
+
+type Listener = Box<dyn FnMut(&PowerData) + Send>;
+
+/// Identifies a registered listener, see [`Sampler::remove_listener`]
+pub(crate) type ListenerId = u64;
+
+/// Dispatches every sample taken by the C sampling thread to Rust listeners
+#[derive(Default)]
+pub(crate) struct Sampler {
+    listeners: Mutex<Vec<(ListenerId, Listener)>>,
+    next_id: AtomicU64,
+    samples: AtomicU64,
+}
+
+impl Sampler {
+    /// Registers a listener that is called with every new sample
+    pub(crate) fn add_listener(&self, listener: impl FnMut(&PowerData) + Send + 'static) -> ListenerId {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((id, Box::new(listener)));
+        id
+    }
+
+    /// Unregisters a listener; once this returns it is no longer called
+    ///
+    /// Must not be called from within a listener.
+    pub(crate) fn remove_listener(&self, id: ListenerId) {
+        self.listeners
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    /// Returns the number of samples taken since the monitor was created
+    pub(crate) fn sample_count(&self) -> u64 {
+        self.samples.load(Ordering::Acquire)
+    }
+
+    fn dispatch(&self, data: &PowerData) {
+        self.samples.fetch_add(1, Ordering::AcqRel);
+        let mut listeners = self.listeners.lock().unwrap_or_else(|e| e.into_inner());
+        for (_, listener) in listeners.iter_mut() {
+            listener(data);
+        }
+    }
+}
+
+/// Entry point for the C sample callback
+///
+/// `user_data` points at the monitor's [`Sampler`], which outlives the
+/// sampling thread because `pm_cleanup` joins the thread before the
+/// sampler is dropped. Panics must not unwind into C, so they are caught
+/// and the sample is dropped.
+pub(crate) extern "C" fn sample_callback(data: *const PowerData, user_data: *mut c_void) {
+    if data.is_null() || user_data.is_null() {
+        return;
+    }
+    let sampler = unsafe { &*(user_data as *const Sampler) };
+    let data = unsafe { &*data };
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| sampler.dispatch(data)));
+}
+
+/// Exponentially weighted moving average
+///
+/// Each update computes `value = alpha * sample + (1 - alpha) * value`, so
+/// only the current value is stored. A smaller `alpha` smooths more: the
+/// filter behaves roughly like a simple moving average over
+/// `2 / alpha - 1` samples, which at a sampling rate of `f` Hz is a window
+/// of about `(2 / alpha - 1) / f` seconds. For example, `alpha = 0.1` at
+/// 100 Hz averages over roughly the last 190 ms. `alpha = 1.0` disables
+/// smoothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ewma {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ewma {
+    /// Creates a new, empty EWMA
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Smoothing factor, must be in (0, 1]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Ewma)` - The new filter
+    /// * `Err(Error::InvalidArgument)` - If `alpha` is outside (0, 1]
+    pub fn new(alpha: f64) -> Result<Self, Error> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(Error::InvalidArgument);
+        }
+        Ok(Self { alpha, value: None })
+    }
+
+    /// Returns the smoothing factor
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Feeds a sample into the filter and returns the updated value
+    ///
+    /// The first sample initializes the filter directly.
+    pub fn update(&mut self, sample: f64) -> f64 {
+        let value = match self.value {
+            Some(previous) => self.alpha * sample + (1.0 - self.alpha) * previous,
+            None => sample,
+        };
+        self.value = Some(value);
+        value
+    }
+
+    /// Returns the current value, or `None` before the first sample
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// Clears the filter so the next sample initializes it again
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// The filter of [`PowerMonitor::ewma_total_power`] and the listener
+/// updating it
+pub(crate) struct EwmaListener {
+    filter: Arc<Mutex<Ewma>>,
+    listener: ListenerId,
+}
+
+impl PowerMonitor {
+    /// Gets the exponentially weighted moving average of the total power
+    ///
+    /// The first call creates a filter, seeded with the latest total power,
+    /// that is then updated by the sampling thread on every sample. Later
+    /// calls with the same `alpha` return the smoothed value without any
+    /// extra work; a call with another `alpha` replaces the filter with a
+    /// new one. See [`Ewma`] for how `alpha` relates to the effective
+    /// averaging window, and [`PowerMonitor::disable_ewma`] to stop
+    /// smoothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Smoothing factor, must be in (0, 1]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - Smoothed total power in watts
+    /// * `Err(Error::NotRunning)` - If a new filter is needed but no sample
+    ///   has been taken since sampling was last started, so there is nothing
+    ///   to seed it with; see [`PowerMonitor::try_get_latest_data`]
+    /// * `Err(Error)` - `Error::InvalidArgument` for an invalid `alpha`, or
+    ///   an error code if reading the latest data fails
+    pub fn ewma_total_power(&self, alpha: f64) -> Result<f64, Error> {
+        let mut ewma = Ewma::new(alpha)?;
+        let mut current = self.ewma.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = current.as_ref() {
+            let filter = current.filter.lock().unwrap_or_else(|e| e.into_inner());
+            if filter.alpha().to_bits() == alpha.to_bits() {
+                if let Some(value) = filter.value() {
+                    return Ok(value);
+                }
+            }
+        }
+
+        let latest = self.try_get_latest_data()?.ok_or(Error::NotRunning)?;
+        let value = ewma.update(latest.total.power);
+        if let Some(previous) = current.take() {
+            self.sampler.remove_listener(previous.listener);
+        }
+        let filter = Arc::new(Mutex::new(ewma));
+        let listener_filter = Arc::clone(&filter);
+        let listener = self.sampler.add_listener(move |data| {
+            listener_filter
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .update(data.total.power);
+        });
+        *current = Some(EwmaListener { filter, listener });
+        Ok(value)
+    }
+
+    /// Stops the smoothing started by [`PowerMonitor::ewma_total_power`]
+    ///
+    /// The filter is no longer updated by the sampling thread, and the next
+    /// call to `ewma_total_power` starts a new one. Does nothing if
+    /// smoothing is not enabled.
+    pub fn disable_ewma(&self) {
+        if let Some(previous) = self.ewma.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            self.sampler.remove_listener(previous.listener);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Placement and priority of the sampling thread
+//!
+//! At high rates the sampling thread competes for CPU time with the workload
+//! being measured. Pinning it to a core the workload does not use, or
+//! raising its priority so it samples on time, keeps the two apart. Both
+//! settings are Linux-specific and persist across sampling runs.
+
+use crate::{check_result, pm_set_sampling_thread_affinity, pm_set_sampling_thread_priority, Error, PowerMonitor};
+
+impl PowerMonitor {
+    /// Restricts the sampling thread to the given CPUs
+    ///
+    /// Applies immediately if sampling is active, and to every later run.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpus` - CPU numbers the thread may run on; empty allows every CPU
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If a CPU number is out of range
+    /// * `Err(Error::Thread)` - If the kernel rejects the set, e.g. because
+    ///   none of the CPUs is online
+    pub fn set_sampling_thread_affinity(&self, cpus: &[usize]) -> Result<(), Error> {
+        let cpus = cpus
+            .iter()
+            .map(|&cpu| i32::try_from(cpu).map_err(|_| Error::InvalidArgument))
+            .collect::<Result<Vec<i32>, Error>>()?;
+        let count = i32::try_from(cpus.len()).map_err(|_| Error::InvalidArgument)?;
+        let result = unsafe { pm_set_sampling_thread_affinity(self.handle.as_ptr(), cpus.as_ptr(), count) };
+        check_result("pm_set_sampling_thread_affinity", result)?;
+        Ok(())
+    }
+
+    /// Sets the real-time priority of the sampling thread
+    ///
+    /// A positive priority runs the thread under `SCHED_FIFO`, which usually
+    /// requires `CAP_SYS_NICE` or root; 0 restores the default `SCHED_OTHER`
+    /// policy. Applies immediately if sampling is active, and to every later
+    /// run. A priority the process may not use is only detected once a
+    /// thread exists, so [`PowerMonitor::start_sampling`] then fails with
+    /// `Error::Thread` as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - `SCHED_FIFO` priority, usually 1 to 99, or 0
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If `priority` is out of range
+    /// * `Err(Error::Thread)` - If the policy cannot be applied to the running thread
+    pub fn set_sampling_thread_priority(&self, priority: i32) -> Result<(), Error> {
+        let result = unsafe { pm_set_sampling_thread_priority(self.handle.as_ptr(), priority) };
+        check_result("pm_set_sampling_thread_priority", result)?;
+        Ok(())
+    }
+}
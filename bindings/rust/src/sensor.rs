@@ -0,0 +1,94 @@
+//! Decoded sensor readings for application code
+//!
+//! [`SensorData`] mirrors the C layout, with fixed-size, NUL-padded name and
+//! status buffers. [`Sensor`] holds the same reading with those buffers
+//! decoded into `String`s and is the recommended type to pass around.
+
+use std::collections::HashMap;
+
+use crate::{Error, OwnedPowerData, PowerMonitor, SensorData, SensorType};
+
+/// A sensor reading with decoded name and status
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sensor {
+    /// Sensor name
+    pub name: String,
+    /// Sensor type
+    pub kind: SensorType,
+    /// Voltage in volts
+    pub voltage: f64,
+    /// Current in amperes
+    pub current: f64,
+    /// Power in watts
+    pub power: f64,
+    /// Whether the sensor is online
+    pub online: bool,
+    /// Status string, empty if not available
+    pub status: String,
+    /// Warning threshold in watts
+    pub warning_threshold: f64,
+    /// Critical threshold in watts
+    pub critical_threshold: f64,
+}
+
+impl From<&SensorData> for Sensor {
+    fn from(data: &SensorData) -> Self {
+        Self {
+            name: data.name().into_owned(),
+            kind: data.type_,
+            voltage: data.voltage,
+            current: data.current,
+            power: data.power,
+            online: data.online,
+            status: data.status().into_owned(),
+            warning_threshold: data.warning_threshold,
+            critical_threshold: data.critical_threshold,
+        }
+    }
+}
+
+impl OwnedPowerData {
+    /// Groups the sensors by their type
+    ///
+    /// The virtual `PS_TOTAL_POWER`, `PL_TOTAL_POWER` and `TOTAL_POWER`
+    /// sensors have type [`SensorType::System`]; the INA226 rails are
+    /// [`SensorType::I2C`]. Within a group, sensors keep their order.
+    pub fn group_by_type(&self) -> HashMap<SensorType, Vec<Sensor>> {
+        let mut groups: HashMap<SensorType, Vec<Sensor>> = HashMap::new();
+        for sensor in &self.sensors {
+            groups.entry(sensor.type_).or_default().push(Sensor::from(sensor));
+        }
+        groups
+    }
+
+    /// Sums the power of the sensors of each type, in watts
+    ///
+    /// Offline sensors are included with their last reading. Note that the
+    /// [`SensorType::System`] sum includes the virtual totals, which already
+    /// add up the physical rails.
+    pub fn sum_by_type(&self) -> HashMap<SensorType, f64> {
+        let mut sums: HashMap<SensorType, f64> = HashMap::new();
+        for sensor in &self.sensors {
+            *sums.entry(sensor.type_).or_default() += sensor.power;
+        }
+        sums
+    }
+}
+
+impl PowerMonitor {
+    /// Gets the latest reading of every sensor as [`Sensor`]s
+    ///
+    /// This is the recommended way to read sensors: nothing points into
+    /// library memory and names need no decoding. Processing is applied as
+    /// for [`PowerMonitor::get_latest_owned`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Sensor>)` - The sensors, in the order of `get_latest_data()`
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn latest_sensors(&self) -> Result<Vec<Sensor>, Error> {
+        let data = self.get_latest_owned()?;
+        Ok(data.sensors.iter().map(Sensor::from).collect())
+    }
+}
@@ -0,0 +1,70 @@
+//! Serde support, enabled by the `serde` feature
+//!
+//! Fixed-size C string buffers are serialized as trimmed strings, and the
+//! raw-pointer types [`PowerData`] and [`PowerStats`] serialize their sensor
+//! arrays as sequences. The raw-pointer types cannot be deserialized, since
+//! their sensor arrays belong to the library; deserialize into
+//! [`crate::OwnedPowerData`] or [`crate::OwnedPowerStats`] instead, which
+//! share the same format.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::{PowerData, PowerStats};
+
+/// (De)serializes a NUL-padded `[u8; N]` buffer as a string
+pub(crate) mod c_str {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::{decode_c_str, encode_c_str};
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        buf: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&decode_c_str(buf))
+    }
+
+    /// Strings that do not fit are truncated at a character boundary so the
+    /// buffer always keeps its NUL terminator
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        Ok(encode_c_str(&String::deserialize(deserializer)?))
+    }
+}
+
+/// (De)serializes a [`std::time::SystemTime`] as milliseconds since the Unix epoch
+pub(crate) mod unix_ms {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Times before the epoch are written as 0
+    pub(crate) fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let ms = time.duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        serializer.serialize_u64(ms.min(u64::MAX as u128) as u64)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        Ok(UNIX_EPOCH + Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+impl Serialize for PowerData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PowerData", 2)?;
+        state.serialize_field("total", &self.total)?;
+        state.serialize_field("sensors", self.sensors())?;
+        state.end()
+    }
+}
+
+impl Serialize for PowerStats {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PowerStats", 2)?;
+        state.serialize_field("total", &self.total)?;
+        state.serialize_field("sensors", self.sensors())?;
+        state.end()
+    }
+}
@@ -0,0 +1,61 @@
+//! Snapshots of the latest power data
+//!
+//! A [`PowerSnapshot`] is an owned copy of the latest sample, taken while
+//! the sampling thread is locked out. It is not a `PowerSnapshot<'a>`
+//! borrowing the library's buffer: it owns its sensor array, so it never
+//! changes while it is held and stays valid after the [`PowerMonitor`] it
+//! came from has been dropped.
+
+use crate::{Error, OwnedPowerData, PowerMonitor, SensorData};
+
+/// An owned copy of the latest power data of a [`PowerMonitor`]
+///
+/// The snapshot carries no lifetime and does not borrow the monitor.
+///
+/// ```no_run
+/// use xlnpwmon::PowerMonitor;
+///
+/// let monitor = PowerMonitor::new().unwrap();
+/// let snapshot = monitor.latest().unwrap();
+/// drop(monitor);
+/// println!("{}", snapshot.sensors().len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PowerSnapshot {
+    data: OwnedPowerData,
+}
+
+impl PowerSnapshot {
+    /// Gets the total power consumption
+    pub fn total(&self) -> &SensorData {
+        &self.data.total
+    }
+
+    /// Gets the data of every sensor
+    pub fn sensors(&self) -> &[SensorData] {
+        &self.data.sensors
+    }
+
+    /// Copies the snapshot into an [`OwnedPowerData`]
+    pub fn to_owned_data(&self) -> OwnedPowerData {
+        self.data.clone()
+    }
+}
+
+impl PowerMonitor {
+    /// Gets a snapshot of the latest power data
+    ///
+    /// The data is copied while the sampling thread is locked out, so every
+    /// sensor in the snapshot comes from the same sample. The snapshot is an
+    /// owned copy rather than a view into the library's buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerSnapshot)` - Latest power data
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn latest(&self) -> Result<PowerSnapshot, Error> {
+        Ok(PowerSnapshot {
+            data: self.latest_copy()?,
+        })
+    }
+}
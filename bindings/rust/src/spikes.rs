@@ -0,0 +1,98 @@
+//! Detection of transient power spikes
+//!
+//! Short load bursts are averaged away in the statistics. [`SpikeEvent`]s
+//! flag the samples of the retained history whose total power stands out
+//! from the rest, measured in standard deviations above the mean of that
+//! same history.
+
+use std::time::Duration;
+
+use crate::{Error, OwnedPowerData, PowerMonitor};
+
+/// Fewest samples needed before the spread of the history is meaningful
+pub const MIN_SPIKE_SAMPLES: usize = 8;
+
+/// A sample whose total power exceeded the spike threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpikeEvent {
+    /// Position of the sample in the history, oldest first
+    pub index: usize,
+    /// Time of the sample after the oldest one, from the sample timestamps
+    pub offset: Duration,
+    /// Total power of the sample in watts
+    pub power: f64,
+    /// Distance above the mean in standard deviations
+    pub sigma: f64,
+}
+
+impl SpikeEvent {
+    /// Finds the samples whose total power exceeds `mean + sigma * stddev`
+    ///
+    /// The mean and (population) standard deviation are taken over
+    /// `samples` themselves, so a spike is always relative to its
+    /// surroundings. Constant power has no spikes.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Samples, oldest first
+    /// * `sigma` - Threshold in standard deviations, must be finite and
+    ///   non-negative
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SpikeEvent>)` - The spikes in order, empty for fewer than
+    ///   [`MIN_SPIKE_SAMPLES`] samples
+    /// * `Err(Error::InvalidArgument)` - If `sigma` is negative or not finite
+    pub fn detect(samples: &[OwnedPowerData], sigma: f64) -> Result<Vec<SpikeEvent>, Error> {
+        if !(sigma.is_finite() && sigma >= 0.0) {
+            return Err(Error::InvalidArgument);
+        }
+        if samples.len() < MIN_SPIKE_SAMPLES {
+            return Ok(Vec::new());
+        }
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|s| s.total.power).sum::<f64>() / n;
+        let variance = samples.iter().map(|s| (s.total.power - mean).powi(2)).sum::<f64>() / n;
+        let std_dev = variance.sqrt();
+        if std_dev == 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let threshold = mean + sigma * std_dev;
+        let start_ns = samples[0].total.timestamp_ns;
+        Ok(samples
+            .iter()
+            .enumerate()
+            .filter(|(_, sample)| sample.total.power > threshold)
+            .map(|(index, sample)| SpikeEvent {
+                index,
+                offset: Duration::from_nanos(sample.total.timestamp_ns.saturating_sub(start_ns)),
+                power: sample.total.power,
+                sigma: (sample.total.power - mean) / std_dev,
+            })
+            .collect())
+    }
+}
+
+impl PowerMonitor {
+    /// Flags the retained samples whose total power spikes above the rest
+    ///
+    /// Enable the history with [`PowerMonitor::set_history_capacity`]
+    /// first; see [`SpikeEvent::detect`] for the criterion.
+    ///
+    /// # Arguments
+    ///
+    /// * `sigma` - Threshold in standard deviations above the mean, e.g. `3.0`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SpikeEvent>)` - The spikes, oldest first; empty if the
+    ///   history holds fewer than [`MIN_SPIKE_SAMPLES`] samples
+    /// * `Err(Error::InvalidArgument)` - If `sigma` is negative or not finite
+    /// * `Err(Error)` - An error code if the history could not be read
+    pub fn detect_spikes(&self, sigma: f64) -> Result<Vec<SpikeEvent>, Error> {
+        SpikeEvent::detect(&self.get_history()?, sigma)
+    }
+}
@@ -0,0 +1,70 @@
+//! Notifications for sensors going offline and coming back
+//!
+//! A sensor that cannot be read keeps its last values but is reported with
+//! `online` cleared until a read succeeds again. [`PowerMonitor::on_status_change`]
+//! reports those transitions as they happen, instead of leaving callers to
+//! compare consecutive samples themselves.
+
+#[cfg(feature = "mock")]
+use std::ffi::CString;
+
+#[cfg(feature = "mock")]
+use crate::{check_result, pm_mock_set_sensor_online, Error};
+use crate::{PowerMonitor, SensorData};
+
+impl PowerMonitor {
+    /// Registers a callback for sensors changing between online and offline
+    ///
+    /// The callback is invoked with the sensor reading whenever a sensor's
+    /// `online` flag differs from the previous sample; `online` tells which
+    /// way it flipped. The first sample only records the initial state, and
+    /// a sensor that stays offline or online is not reported again.
+    ///
+    /// The callback runs on the sampling thread under the same rules as the
+    /// one of [`PowerMonitor::on_threshold`], and is dropped together with
+    /// the monitor.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Called with the sensor whose state changed
+    pub fn on_status_change<F>(&self, mut callback: F)
+    where
+        F: FnMut(&SensorData) + Send + 'static,
+    {
+        let mut states: Vec<Option<bool>> = Vec::new();
+        self.sampler.add_listener(move |data| {
+            let sensors = data.sensors();
+            states.resize(sensors.len(), None);
+            for (sensor, previous) in sensors.iter().zip(states.iter_mut()) {
+                if previous.map_or(false, |previous| previous != sensor.online) {
+                    callback(sensor);
+                }
+                *previous = Some(sensor.online);
+            }
+        });
+    }
+
+    /// Takes a synthetic sensor offline or brings it back
+    ///
+    /// Only exists in mock builds, for testing how sensors that stop
+    /// responding are handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of a physical sensor
+    /// * `online` - Whether the sensor can be read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If `name` does not name a physical sensor
+    /// * `Err(Error::InvalidArgument)` - If `name` contains a NUL byte
+    #[cfg(feature = "mock")]
+    #[doc(hidden)]
+    pub fn mock_set_sensor_online(&self, name: &str, online: bool) -> Result<(), Error> {
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+        let result = unsafe { pm_mock_set_sensor_online(self.handle.as_ptr(), name.as_ptr(), online) };
+        check_result("pm_mock_set_sensor_online", result)?;
+        Ok(())
+    }
+}
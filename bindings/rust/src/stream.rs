@@ -0,0 +1,104 @@
+//! Asynchronous stream of samples
+//!
+//! Dashboards and other async consumers want to await samples instead of
+//! polling [`PowerMonitor::get_latest_data`]. A [`PowerStream`] receives every
+//! sample from the sampling thread through a bounded channel.
+
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+
+use crate::{Error, OwnedPowerData, PowerMonitor};
+
+/// Number of samples a stream buffers before new samples are dropped
+const STREAM_BUFFER: usize = 64;
+
+/// Senders of the open streams of a monitor
+pub(crate) type StreamSenders = Arc<Mutex<Vec<mpsc::Sender<OwnedPowerData>>>>;
+
+/// Stream of owned samples, one per sampling period
+///
+/// Created by [`PowerMonitor::sample_stream`]. The stream ends when sampling
+/// stops or the monitor is dropped. If the consumer falls more than
+/// 64 samples behind, newer samples are dropped until it catches up.
+#[must_use = "streams do nothing unless polled"]
+pub struct PowerStream {
+    receiver: mpsc::Receiver<OwnedPowerData>,
+    error: Option<Error>,
+}
+
+impl Stream for PowerStream {
+    type Item = Result<OwnedPowerData, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(error) = self.error.take() {
+            return Poll::Ready(Some(Err(error)));
+        }
+        self.receiver.poll_recv(cx).map(|data| data.map(Ok))
+    }
+}
+
+impl PowerMonitor {
+    /// Streams every sample taken while sampling runs
+    ///
+    /// Each item is an owned copy of one sample, processed like
+    /// [`PowerMonitor::get_latest_owned`]. If sampling is not active, the
+    /// stream yields a single `Err(Error::NotRunning)` and ends.
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use futures_util::StreamExt;
+    /// use xlnpwmon::PowerMonitor;
+    ///
+    /// let monitor = PowerMonitor::new().unwrap();
+    /// monitor.start_sampling().unwrap();
+    /// let mut stream = monitor.sample_stream();
+    /// while let Some(Ok(data)) = stream.next().await {
+    ///     println!("{:.2} W", data.total.power);
+    /// }
+    /// # }
+    /// ```
+    pub fn sample_stream(&self) -> PowerStream {
+        self.stream_listener.call_once(|| {
+            let senders = Arc::clone(&self.streams);
+            let processing = self.processing.clone();
+            self.sampler.add_listener(move |data| {
+                let mut senders = senders.lock().unwrap_or_else(|e| e.into_inner());
+                if senders.is_empty() {
+                    return;
+                }
+                let data = processing.data(data.to_owned());
+                // A full buffer drops the sample; a closed one drops the stream
+                senders.retain(|sender| {
+                    !matches!(
+                        sender.try_send(data.clone()),
+                        Err(mpsc::error::TrySendError::Closed(_))
+                    )
+                });
+            });
+        });
+
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let mut senders = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        // Checked under the lock so a concurrent stop either sees this stream
+        // or is seen here
+        if self.sampling.load(Ordering::Acquire) {
+            senders.push(sender);
+            PowerStream { receiver, error: None }
+        } else {
+            PowerStream {
+                receiver,
+                error: Some(Error::NotRunning),
+            }
+        }
+    }
+
+    /// Ends all open streams
+    pub(crate) fn close_streams(&self) {
+        self.streams.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
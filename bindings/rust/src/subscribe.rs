@@ -0,0 +1,162 @@
+//! Broadcast of every sample to any number of consumers
+//!
+//! A logger, a dashboard and a watchdog may all want every sample. Rather
+//! than sharing one callback, each calls [`PowerMonitor::subscribe`] and
+//! gets a [`SampleReceiver`] of its own, fed by a single listener on the
+//! sampling thread. Unlike the `PowerStream` of the `tokio` feature, this
+//! needs no async runtime.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvError, RecvTimeoutError, SyncSender, TryRecvError, TrySendError};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use crate::{OwnedPowerData, PowerMonitor};
+
+/// Number of samples a receiver buffers before new samples are dropped
+const SUBSCRIBER_BUFFER: usize = 64;
+
+/// Sending side of one receiver
+pub(crate) struct Subscriber {
+    sender: SyncSender<OwnedPowerData>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Receivers of a monitor that are still open
+pub(crate) type Subscribers = Arc<Mutex<Vec<Subscriber>>>;
+
+/// Receiver of every sample of a monitor
+///
+/// Created by [`PowerMonitor::subscribe`]. Each receiver buffers up to 64
+/// samples. If its consumer falls further behind, newer samples are dropped
+/// for that receiver alone until it catches up, so a slow consumer never
+/// stalls the sampling thread or the other receivers;
+/// [`SampleReceiver::dropped`] counts the samples it missed.
+///
+/// Cloning subscribes again: the clone receives the samples taken from then
+/// on, with a buffer of its own. Receiving fails with a disconnected error
+/// once the monitor is dropped and the buffered samples are drained.
+pub struct SampleReceiver {
+    receiver: mpsc::Receiver<OwnedPowerData>,
+    dropped: Arc<AtomicU64>,
+    subscribers: Weak<Mutex<Vec<Subscriber>>>,
+}
+
+impl SampleReceiver {
+    /// Opens a receiver on `subscribers`, already disconnected if the
+    /// monitor is gone
+    fn open(subscribers: Weak<Mutex<Vec<Subscriber>>>) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(SUBSCRIBER_BUFFER);
+        let dropped = Arc::new(AtomicU64::new(0));
+        if let Some(list) = subscribers.upgrade() {
+            list.lock().unwrap_or_else(|e| e.into_inner()).push(Subscriber {
+                sender,
+                dropped: Arc::clone(&dropped),
+            });
+        }
+        Self {
+            receiver,
+            dropped,
+            subscribers,
+        }
+    }
+
+    /// Waits for the next sample
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - The oldest buffered sample
+    /// * `Err(RecvError)` - If the monitor was dropped and the buffer is empty
+    pub fn recv(&self) -> Result<OwnedPowerData, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// Waits at most `timeout` for the next sample
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - The oldest buffered sample
+    /// * `Err(RecvTimeoutError)` - If no sample arrived in time, e.g. because
+    ///   sampling is stopped, or the monitor was dropped
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<OwnedPowerData, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// Takes the next sample if one is buffered
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OwnedPowerData)` - The oldest buffered sample
+    /// * `Err(TryRecvError)` - If the buffer is empty or the monitor was
+    ///   dropped
+    pub fn try_recv(&self) -> Result<OwnedPowerData, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Returns the number of samples dropped because the buffer was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Clone for SampleReceiver {
+    fn clone(&self) -> Self {
+        Self::open(Weak::clone(&self.subscribers))
+    }
+}
+
+impl std::fmt::Debug for SampleReceiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SampleReceiver")
+            .field("dropped", &self.dropped())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PowerMonitor {
+    /// Subscribes to every sample taken from now on
+    ///
+    /// Each item is an owned copy of one sample, processed like
+    /// [`PowerMonitor::get_latest_owned`]. Every receiver, including clones,
+    /// sees every sample, subject to the overflow policy described on
+    /// [`SampleReceiver`]. The subscription stays open across
+    /// `stop_sampling` and `start_sampling`; while sampling is stopped no
+    /// samples arrive.
+    ///
+    /// ```no_run
+    /// use std::thread;
+    /// use xlnpwmon::PowerMonitor;
+    ///
+    /// let monitor = PowerMonitor::new().unwrap();
+    /// let logger = monitor.subscribe();
+    /// thread::spawn(move || {
+    ///     while let Ok(data) = logger.recv() {
+    ///         println!("{:.2} W", data.total.power);
+    ///     }
+    /// });
+    /// monitor.start_sampling().unwrap();
+    /// ```
+    pub fn subscribe(&self) -> SampleReceiver {
+        self.subscribe_listener.call_once(|| {
+            let subscribers = Arc::clone(&self.subscribers);
+            let processing = self.processing.clone();
+            self.sampler.add_listener(move |data| {
+                let mut subscribers = subscribers.lock().unwrap_or_else(|e| e.into_inner());
+                if subscribers.is_empty() {
+                    return;
+                }
+                let data = processing.data(data.to_owned());
+                // A full buffer drops the sample; a closed one drops the receiver
+                subscribers.retain(|subscriber| match subscriber.sender.try_send(data.clone()) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        subscriber.dropped.fetch_add(1, Ordering::Relaxed);
+                        true
+                    }
+                    Err(TrySendError::Disconnected(_)) => false,
+                });
+            });
+        });
+        SampleReceiver::open(Arc::downgrade(&self.subscribers))
+    }
+}
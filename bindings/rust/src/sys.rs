@@ -0,0 +1,10 @@
+//! Raw declarations generated from `xlnpwmon.h`
+//!
+//! Generated by bindgen at build time, so they always match the bundled
+//! header. The safe API does not use them: it is built on the hand-written
+//! `#[repr(C)]` structs and `extern "C"` block, whose layouts the crate's
+//! tests compare against these. They are exposed for callers that need a
+//! function the safe API does not wrap yet.
+#![allow(non_camel_case_types, non_snake_case, non_upper_case_globals, dead_code, missing_docs, clippy::all)]
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
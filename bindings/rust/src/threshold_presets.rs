@@ -0,0 +1,76 @@
+//! Threshold presets loaded from a file
+//!
+//! Operators keep the warning and critical limits of each rail in a
+//! version-controlled JSON file instead of code, as an object keyed by
+//! sensor name:
+//!
+//! ```json
+//! {
+//!     "VCCINT": { "warning": 3.5, "critical": 4.5 },
+//!     "VCCPSINTFP": { "warning": 1.8, "critical": 2.2 }
+//! }
+//! ```
+//!
+//! Only JSON is read, as `serde_json` is already a dependency of the
+//! `serde` feature. TOML or YAML presets are out of scope: convert them to
+//! JSON, or parse them into a map of [`SensorThresholds`] and apply it with
+//! [`PowerMonitor::set_sensor_thresholds`].
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::context::{file_access_error, with_context};
+use crate::{Error, PowerMonitor, SensorThresholds};
+
+impl PowerMonitor {
+    /// Applies the per-sensor thresholds of a JSON preset file
+    ///
+    /// Each entry is applied with [`PowerMonitor::set_sensor_thresholds`].
+    /// The whole file is checked first, so on error no threshold has been
+    /// changed. [`Error::context`] tells what was wrong with the file, e.g.
+    /// which sensor names it contains that this monitor does not have.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Preset file in the format described in the module docs
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of sensors whose thresholds were set
+    /// * `Err(Error::FileAccess)` - If the file cannot be read
+    /// * `Err(Error::InvalidArgument)` - If the file is not a valid preset,
+    ///   names sensors this monitor does not have, or a warning threshold is
+    ///   above its critical threshold
+    pub fn apply_thresholds_from_file<P: AsRef<Path>>(&self, path: P) -> Result<usize, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| file_access_error(path, e))?;
+        let presets: BTreeMap<String, SensorThresholds> = serde_json::from_str(&text)
+            .map_err(|e| with_context(Error::InvalidArgument, format!("{}: {}", path.display(), e)))?;
+
+        let names = self.sensor_names()?;
+        let unknown: Vec<&str> = presets
+            .keys()
+            .filter(|name| !names.contains(name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(with_context(
+                Error::InvalidArgument,
+                format!("{}: unknown sensors: {}", path.display(), unknown.join(", ")),
+            ));
+        }
+        // JSON has no NaN, so only the order needs checking
+        if let Some((name, _)) = presets.iter().find(|(_, preset)| preset.warning > preset.critical) {
+            return Err(with_context(
+                Error::InvalidArgument,
+                format!("{}: warning threshold of {} is above its critical threshold", path.display(), name),
+            ));
+        }
+
+        for (name, preset) in &presets {
+            self.set_sensor_thresholds(name, preset.warning, preset.critical)?;
+        }
+        Ok(presets.len())
+    }
+}
@@ -0,0 +1,167 @@
+//! Per-sensor warning and critical thresholds
+//!
+//! Every [`crate::SensorData`] reports a warning and a critical threshold in
+//! watts. They start out unset (zero) and can be tuned per rail here; the C
+//! library keeps them with the sensor data, so they show up in every reading
+//! returned afterwards. [`PowerMonitor::on_threshold`] turns crossings of
+//! those thresholds into notifications, so long-running jobs do not need to
+//! poll.
+
+use std::collections::BTreeMap;
+use std::ffi::CString;
+
+use crate::{check_result, pm_set_thresholds, Error, PowerMonitor, SensorData};
+
+/// Warning and critical power thresholds of a sensor, in watts
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct SensorThresholds {
+    /// Warning threshold in watts
+    pub warning: f64,
+    /// Critical threshold in watts, at least `warning`
+    pub critical: f64,
+}
+
+/// Severity of a threshold crossing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThresholdLevel {
+    /// Power is at or above the warning threshold
+    Warning,
+    /// Power is at or above the critical threshold
+    Critical,
+}
+
+/// Whether `power` has reached `threshold`, which is unset unless positive
+fn reached(power: f64, threshold: f64) -> bool {
+    threshold > 0.0 && power >= threshold
+}
+
+impl SensorData {
+    /// Checks whether the power is at or above the warning threshold
+    ///
+    /// Always `false` while the warning threshold is zero (unset) or
+    /// negative, regardless of the critical threshold.
+    pub fn is_over_warning(&self) -> bool {
+        reached(self.power, self.warning_threshold)
+    }
+
+    /// Checks whether the power is at or above the critical threshold
+    ///
+    /// Always `false` while the critical threshold is zero (unset) or
+    /// negative.
+    pub fn is_over_critical(&self) -> bool {
+        reached(self.power, self.critical_threshold)
+    }
+
+    /// Gets the highest threshold the sensor's power is at or above
+    ///
+    /// Thresholds that are zero (unset) or negative are ignored, as in
+    /// [`SensorData::is_over_warning`] and [`SensorData::is_over_critical`].
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ThresholdLevel)` - The level that has been reached
+    /// * `None` - If the power is below every set threshold
+    pub fn threshold_level(&self) -> Option<ThresholdLevel> {
+        if self.is_over_critical() {
+            Some(ThresholdLevel::Critical)
+        } else if self.is_over_warning() {
+            Some(ThresholdLevel::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Sets the warning and critical power thresholds of a sensor
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Sensor name
+    /// * `warning` - Warning threshold in watts
+    /// * `critical` - Critical threshold in watts, at least `warning`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::NoSensors)` - If `name` does not name an existing sensor
+    /// * `Err(Error::InvalidArgument)` - If `warning` is above `critical`, either is NaN
+    ///   or `name` contains a NUL byte
+    pub fn set_sensor_thresholds(&self, name: &str, warning: f64, critical: f64) -> Result<(), Error> {
+        if warning.is_nan() || critical.is_nan() || warning > critical {
+            return Err(Error::InvalidArgument);
+        }
+        let name = CString::new(name).map_err(|_| Error::InvalidArgument)?;
+        let result = unsafe { pm_set_thresholds(self.handle.as_ptr(), name.as_ptr(), warning, critical) };
+        check_result("pm_set_thresholds", result)?;
+        Ok(())
+    }
+
+    /// Gets the thresholds of every sensor that has any set
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BTreeMap)` - Thresholds keyed by sensor name; sensors whose
+    ///   thresholds are both zero (unset) are left out
+    /// * `Err(Error)` - An error code if getting data fails
+    pub fn sensor_thresholds(&self) -> Result<BTreeMap<String, SensorThresholds>, Error> {
+        Ok(self
+            .latest_copy()?
+            .sensors
+            .iter()
+            .filter(|sensor| sensor.warning_threshold != 0.0 || sensor.critical_threshold != 0.0)
+            .map(|sensor| {
+                let thresholds = SensorThresholds {
+                    warning: sensor.warning_threshold,
+                    critical: sensor.critical_threshold,
+                };
+                (sensor.name().into_owned(), thresholds)
+            })
+            .collect())
+    }
+
+    /// Registers a callback for sensors crossing their thresholds
+    ///
+    /// The callback is invoked with the sensor reading and the level reached
+    /// whenever a sensor's power rises to its warning or critical threshold,
+    /// see [`SensorData::threshold_level`]. A sensor that stays above a
+    /// threshold is reported once; it is reported again after its power has
+    /// dropped below the threshold and crossed it anew, or when it goes on
+    /// from warning to critical. Thresholds are set with
+    /// [`PowerMonitor::set_sensor_thresholds`].
+    ///
+    /// The callback runs on the sampling thread, once per sample, and delays
+    /// the next sample for as long as it runs. It may read data through the
+    /// monitor, but it must not stop sampling, and it must not register
+    /// further callbacks, call [`PowerMonitor::ewma_total_power`] with a
+    /// new `alpha` or call [`PowerMonitor::disable_ewma`], which would
+    /// deadlock. A panic in the callback is caught
+    /// and drops the rest of that sample. The callback is dropped together
+    /// with the monitor.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Called with the sensor and the level it reached
+    pub fn on_threshold<F>(&self, mut callback: F)
+    where
+        F: FnMut(&SensorData, ThresholdLevel) + Send + 'static,
+    {
+        let mut levels: Vec<Option<ThresholdLevel>> = Vec::new();
+        self.sampler.add_listener(move |data| {
+            let sensors = data.sensors();
+            levels.resize(sensors.len(), None);
+            for (sensor, previous) in sensors.iter().zip(levels.iter_mut()) {
+                let level = sensor.threshold_level();
+                if let Some(reached) = level {
+                    if previous.map_or(true, |previous| reached > previous) {
+                        callback(sensor, reached);
+                    }
+                }
+                *previous = level;
+            }
+        });
+    }
+}
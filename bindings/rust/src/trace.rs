@@ -0,0 +1,624 @@
+//! Recording and offline replay of captures
+//!
+//! A [`TraceRecorder`] writes every sample taken by the sampling thread to a
+//! compact binary trace, and a [`ReplayMonitor`] reads such a trace back and
+//! offers the same read and statistics calls as a [`PowerMonitor`], so a run
+//! can be analysed again without the board.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::context::file_access_error;
+use crate::sampler::ListenerId;
+use crate::{
+    CaptureMetadata, Error, OwnedPowerData, OwnedPowerStats, PowerData, PowerMonitor, PowerSource,
+    PowerSummary, SensorData, SensorStats, SensorType, Stats,
+};
+
+const TRACE_MAGIC: &[u8; 8] = b"XPWTRACE";
+const TRACE_VERSION: u16 = 2;
+/// Oldest version [`ReplayMonitor::open`] still reads; it has no metadata
+const TRACE_VERSION_NO_METADATA: u16 = 1;
+
+/// The trace file and the first error that occurred while writing it
+struct TraceSink {
+    writer: BufWriter<File>,
+    sensor_count: usize,
+    error: Option<io::Error>,
+}
+
+fn write_descriptor(out: &mut impl Write, sensor: &SensorData) -> io::Result<()> {
+    out.write_all(&sensor.name)?;
+    out.write_all(&i32::from(sensor.type_).to_le_bytes())?;
+    out.write_all(&sensor.warning_threshold.to_le_bytes())?;
+    out.write_all(&sensor.critical_threshold.to_le_bytes())
+}
+
+fn write_reading(out: &mut impl Write, sensor: &SensorData) -> io::Result<()> {
+    out.write_all(&sensor.voltage.to_le_bytes())?;
+    out.write_all(&sensor.current.to_le_bytes())?;
+    out.write_all(&sensor.power.to_le_bytes())
+}
+
+fn write_opt_str(out: &mut impl Write, value: &Option<String>) -> io::Result<()> {
+    match value {
+        Some(value) => {
+            // Truncated at a character boundary to fit the u16 length
+            let mut len = value.len().min(u16::MAX as usize);
+            while !value.is_char_boundary(len) {
+                len -= 1;
+            }
+            out.write_all(&[1])?;
+            out.write_all(&(len as u16).to_le_bytes())?;
+            out.write_all(&value.as_bytes()[..len])
+        }
+        None => out.write_all(&[0]),
+    }
+}
+
+fn write_metadata(out: &mut impl Write, metadata: &CaptureMetadata) -> io::Result<()> {
+    write_opt_str(out, &metadata.hostname)?;
+    write_opt_str(out, &metadata.kernel_version)?;
+    write_opt_str(out, &metadata.board_model)?;
+    let start_ns = metadata
+        .start_time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX))
+        .unwrap_or(0);
+    out.write_all(&start_ns.to_le_bytes())?;
+    match metadata.sampling_frequency_hz {
+        Some(hz) => {
+            out.write_all(&[1])?;
+            out.write_all(&hz.to_le_bytes())
+        }
+        None => out.write_all(&[0]),
+    }
+}
+
+fn write_record(out: &mut impl Write, data: &PowerData) -> io::Result<()> {
+    out.write_all(&data.total.timestamp_ns.to_le_bytes())?;
+    write_reading(out, &data.total)?;
+    for sensor in data.sensors() {
+        write_reading(out, sensor)?;
+        out.write_all(&[sensor.online as u8])?;
+    }
+    Ok(())
+}
+
+/// Writes every sample to a binary trace until dropped
+///
+/// Created by [`PowerMonitor::record_to`]. Recording stops when the
+/// recorder is dropped, which flushes and closes the file but ignores
+/// errors; use [`TraceRecorder::finish`] to check for them.
+#[must_use = "recording stops as soon as the recorder is dropped"]
+pub struct TraceRecorder<'a> {
+    monitor: &'a PowerMonitor,
+    listener: ListenerId,
+    sink: Arc<Mutex<TraceSink>>,
+    finished: bool,
+}
+
+impl<'a> TraceRecorder<'a> {
+    /// Stops recording, then flushes and closes the file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every sample was written
+    /// * `Err(Error::FileAccess)` - If writing a sample or flushing failed
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), Error> {
+        self.finished = true;
+        self.monitor.sampler.remove_listener(self.listener);
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        let flushed = sink.writer.flush();
+        match sink.error.take() {
+            Some(_) => Err(Error::FileAccess),
+            None => flushed.map_err(|_| Error::FileAccess),
+        }
+    }
+}
+
+impl<'a> Drop for TraceRecorder<'a> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.close();
+        }
+    }
+}
+
+impl PowerMonitor {
+    /// Records every sample to a binary trace for [`ReplayMonitor`]
+    ///
+    /// The file is created (or truncated) and starts with the sensor names,
+    /// types and thresholds in effect now, followed by the
+    /// [`CaptureMetadata`] of the system, timestamped now. While the returned recorder is
+    /// alive, each sample taken by the sampling thread is appended as it was
+    /// read, without the processing configured through
+    /// [`crate::PowerMonitorBuilder`]. Recording does not start sampling by
+    /// itself. See [`ReplayMonitor`] for the file format.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the trace file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TraceRecorder)` - The recorder, which stops recording when dropped
+    /// * `Err(Error::FileAccess)` - If the file could not be created
+    /// * `Err(Error)` - An error code if reading the sensor layout fails
+    pub fn record_to<P: AsRef<Path>>(&self, path: P) -> Result<TraceRecorder<'_>, Error> {
+        let latest = self.latest_copy()?;
+        let sensor_count = latest.sensors.len();
+        let metadata = CaptureMetadata::collect(self.get_sampling_frequency().ok());
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| file_access_error(path, e))?;
+        let mut writer = BufWriter::new(file);
+        let header = (|| {
+            writer.write_all(TRACE_MAGIC)?;
+            writer.write_all(&TRACE_VERSION.to_le_bytes())?;
+            writer.write_all(&(sensor_count as u16).to_le_bytes())?;
+            write_descriptor(&mut writer, &latest.total)?;
+            for sensor in &latest.sensors {
+                write_descriptor(&mut writer, sensor)?;
+            }
+            write_metadata(&mut writer, &metadata)?;
+            Ok::<(), io::Error>(())
+        })();
+        header.map_err(|_| Error::FileAccess)?;
+
+        let sink = Arc::new(Mutex::new(TraceSink {
+            writer,
+            sensor_count,
+            error: None,
+        }));
+        let listener_sink = Arc::clone(&sink);
+        let listener = self.sampler.add_listener(move |data| {
+            let mut sink = listener_sink.lock().unwrap_or_else(|e| e.into_inner());
+            if sink.error.is_some() || data.sensors().len() != sink.sensor_count {
+                return;
+            }
+            if let Err(e) = write_record(&mut sink.writer, data) {
+                sink.error = Some(e);
+            }
+        });
+
+        Ok(TraceRecorder {
+            monitor: self,
+            listener,
+            sink,
+            finished: false,
+        })
+    }
+}
+
+/// How fast [`ReplayMonitor::play`] feeds the recorded samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayPace {
+    /// Wait between samples as long as the recording did
+    Original,
+    /// Feed all samples without waiting
+    AsFastAsPossible,
+}
+
+/// Reads little-endian values off the front of a byte slice
+struct TraceReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> TraceReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < len {
+            return None;
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let mut bytes = [0; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Some(u16::from_le_bytes(bytes))
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Some(i32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        self.u64().map(f64::from_bits)
+    }
+
+    fn opt_str(&mut self) -> Option<Option<String>> {
+        if self.u8()? == 0 {
+            return Some(None);
+        }
+        let len = self.u16()? as usize;
+        Some(Some(String::from_utf8_lossy(self.take(len)?).into_owned()))
+    }
+
+    fn metadata(&mut self) -> Option<CaptureMetadata> {
+        Some(CaptureMetadata {
+            hostname: self.opt_str()?,
+            kernel_version: self.opt_str()?,
+            board_model: self.opt_str()?,
+            start_time: UNIX_EPOCH + Duration::from_nanos(self.u64()?),
+            sampling_frequency_hz: if self.u8()? == 0 { None } else { Some(self.i32()?) },
+        })
+    }
+
+    fn descriptor(&mut self) -> Option<SensorData> {
+        let mut sensor = SensorData::default();
+        sensor.name.copy_from_slice(self.take(64)?);
+        sensor.type_ = SensorType::from_raw(self.i32()?);
+        sensor.warning_threshold = self.f64()?;
+        sensor.critical_threshold = self.f64()?;
+        Some(sensor)
+    }
+
+    fn reading(&mut self, sensor: &mut SensorData, timestamp_ns: u64) -> Option<()> {
+        sensor.voltage = self.f64()?;
+        sensor.current = self.f64()?;
+        sensor.power = self.f64()?;
+        sensor.timestamp_ns = timestamp_ns;
+        Some(())
+    }
+
+    fn record(&mut self, layout: &OwnedPowerData) -> Option<OwnedPowerData> {
+        let mut data = layout.clone();
+        let timestamp_ns = self.u64()?;
+        self.reading(&mut data.total, timestamp_ns)?;
+        data.total.online = true;
+        for sensor in &mut data.sensors {
+            self.reading(sensor, timestamp_ns)?;
+            sensor.online = self.u8()? != 0;
+            sensor.status = [0; 32];
+            if sensor.online {
+                sensor.status[..2].copy_from_slice(b"OK");
+            }
+        }
+        Some(data)
+    }
+}
+
+fn empty_stats() -> Stats {
+    Stats {
+        min: 0.0,
+        max: 0.0,
+        avg: 0.0,
+        total: 0.0,
+        count: 0,
+        sum_sq_dev: 0.0,
+        stddev: 0.0,
+    }
+}
+
+/// Compensated running sum behind a `Stats::total`, as kept by the C library
+#[derive(Debug, Clone, Copy, Default)]
+struct Sum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl Sum {
+    /// Adds a value with Neumaier's summation
+    fn add(&mut self, value: f64) {
+        let t = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - t) + value;
+        } else {
+            self.compensation += (value - t) + self.sum;
+        }
+        self.sum = t;
+    }
+}
+
+/// Sums of the three readings of one sensor
+#[derive(Debug, Clone, Copy, Default)]
+struct SensorSums {
+    voltage: Sum,
+    current: Sum,
+    power: Sum,
+}
+
+/// Adds a value to running statistics the way the C library does
+fn update_stats(stats: &mut Stats, sum: &mut Sum, value: f64) {
+    if stats.count == 0 {
+        *sum = Sum {
+            sum: value,
+            compensation: 0.0,
+        };
+        *stats = Stats {
+            min: value,
+            max: value,
+            avg: value,
+            total: value,
+            count: 1,
+            sum_sq_dev: 0.0,
+            stddev: 0.0,
+        };
+        return;
+    }
+    if value < stats.min {
+        stats.min = value;
+    }
+    if value > stats.max {
+        stats.max = value;
+    }
+    let previous_avg = stats.avg;
+    sum.add(value);
+    stats.total = sum.sum + sum.compensation;
+    stats.count += 1;
+    stats.avg = stats.total / stats.count as f64;
+    if stats.min != stats.max {
+        stats.sum_sq_dev += (value - previous_avg) * (value - stats.avg);
+    }
+    stats.stddev = (stats.sum_sq_dev / stats.count as f64).sqrt();
+}
+
+fn update_sensor_stats(stats: &mut SensorStats, sums: &mut SensorSums, sensor: &SensorData) {
+    update_stats(&mut stats.voltage, &mut sums.voltage, sensor.voltage);
+    update_stats(&mut stats.current, &mut sums.current, sensor.current);
+    update_stats(&mut stats.power, &mut sums.power, sensor.power);
+}
+
+fn empty_sensor_stats(sensor: &SensorData) -> SensorStats {
+    SensorStats {
+        name: sensor.name,
+        voltage: empty_stats(),
+        current: empty_stats(),
+        power: empty_stats(),
+    }
+}
+
+/// Plays back a trace written by [`PowerMonitor::record_to`]
+///
+/// Samples are fed in recording order by [`ReplayMonitor::step`] or
+/// [`ReplayMonitor::play`]. The latest data and statistics then behave as
+/// they did on the live monitor after the same samples: statistics of
+/// physical sensors only count samples where the sensor was online.
+///
+/// # Format
+///
+/// All integers and floats are little-endian. A trace starts with a header
+///
+/// ```text
+/// magic         8 bytes   "XPWTRACE"
+/// version       u16       2
+/// sensor_count  u16       number of sensors per sample, n
+/// descriptors   (n + 1) × { name: [u8; 64], type: i32, warning: f64, critical: f64 }
+/// metadata      hostname, kernel_version, board_model: 3 × string
+///               start_time_unix_ns: u64
+///               sampling_frequency_hz: { present: u8, value: i32 if present }
+/// ```
+///
+/// where the first descriptor is the total and the others follow the sensor
+/// order of [`PowerMonitor::get_latest_data`], and each string of the
+/// [`CaptureMetadata`] is `{ present: u8, len: u16, UTF-8 bytes }` with
+/// `len` and the bytes only written if present. Version 1 traces have no
+/// metadata block and are still read. The header is followed by one record
+/// per sample:
+///
+/// ```text
+/// timestamp_ns  u64
+/// total         { voltage: f64, current: f64, power: f64 }
+/// sensors       n × { voltage: f64, current: f64, power: f64, online: u8 }
+/// ```
+///
+/// A record cut short, e.g. because the recording process was killed, is
+/// ignored on replay. Readers must reject versions they do not know.
+#[derive(Debug, Clone)]
+pub struct ReplayMonitor {
+    layout: OwnedPowerData,
+    metadata: Option<CaptureMetadata>,
+    samples: Vec<OwnedPowerData>,
+    position: usize,
+    stats: OwnedPowerStats,
+    /// Sums behind the totals of `stats`, restarted with them
+    total_sums: SensorSums,
+    sums: Vec<SensorSums>,
+}
+
+impl ReplayMonitor {
+    /// Opens a trace file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of a file written by [`PowerMonitor::record_to`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReplayMonitor)` - The trace, positioned before its first sample
+    /// * `Err(Error::FileAccess)` - If the file could not be read
+    /// * `Err(Error::InvalidArgument)` - If the file is not a trace or has an
+    ///   unsupported version
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| file_access_error(path, e))?;
+        let mut reader = TraceReader { buf: &bytes };
+        if reader.take(TRACE_MAGIC.len()) != Some(&TRACE_MAGIC[..]) {
+            return Err(Error::InvalidArgument);
+        }
+        let version = reader.u16().ok_or(Error::InvalidArgument)?;
+        if version != TRACE_VERSION && version != TRACE_VERSION_NO_METADATA {
+            return Err(Error::InvalidArgument);
+        }
+        let sensor_count = reader.u16().ok_or(Error::InvalidArgument)?;
+        let total = reader.descriptor().ok_or(Error::InvalidArgument)?;
+        let sensors = (0..sensor_count)
+            .map(|_| reader.descriptor())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::InvalidArgument)?;
+        let layout = OwnedPowerData { total, sensors };
+        let metadata = if version == TRACE_VERSION_NO_METADATA {
+            None
+        } else {
+            Some(reader.metadata().ok_or(Error::InvalidArgument)?)
+        };
+
+        let mut samples = Vec::new();
+        while let Some(sample) = reader.record(&layout) {
+            samples.push(sample);
+        }
+
+        Ok(Self {
+            stats: OwnedPowerStats {
+                total: empty_sensor_stats(&layout.total),
+                sensors: layout.sensors.iter().map(empty_sensor_stats).collect(),
+            },
+            total_sums: SensorSums::default(),
+            sums: vec![SensorSums::default(); layout.sensors.len()],
+            layout,
+            metadata,
+            samples,
+            position: 0,
+        })
+    }
+
+    /// Gets the metadata of the system the trace was recorded on
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&CaptureMetadata)` - Metadata collected when recording started
+    /// * `None` - If the trace predates metadata (version 1)
+    pub fn metadata(&self) -> Option<&CaptureMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Gets the recorded samples, in recording order
+    pub fn samples(&self) -> &[OwnedPowerData] {
+        &self.samples
+    }
+
+    /// Gets the number of samples fed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Gets the names of the recorded sensors
+    pub fn sensor_names(&self) -> Vec<String> {
+        self.layout.sensors.iter().map(|sensor| sensor.name().into_owned()).collect()
+    }
+
+    /// Feeds the next sample into the statistics
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&OwnedPowerData)` - The sample that was fed
+    /// * `None` - If the end of the trace was reached
+    pub fn step(&mut self) -> Option<&OwnedPowerData> {
+        let sample = self.samples.get(self.position)?;
+        let sensors = self.stats.sensors.iter_mut().zip(&mut self.sums);
+        for ((stats, sums), sensor) in sensors.zip(&sample.sensors) {
+            if sensor.online || sensor.type_ == SensorType::System {
+                update_sensor_stats(stats, sums, sensor);
+            }
+        }
+        update_sensor_stats(&mut self.stats.total, &mut self.total_sums, &sample.total);
+        self.position += 1;
+        Some(sample)
+    }
+
+    /// Feeds all remaining samples into the statistics
+    ///
+    /// With [`ReplayPace::Original`] this blocks for as long as the remaining
+    /// part of the recording took.
+    pub fn play(&mut self, pace: ReplayPace) {
+        let started = Instant::now();
+        let origin_ns = match self.samples.get(self.position) {
+            Some(sample) => sample.total.timestamp_ns,
+            None => return,
+        };
+        while let Some(sample) = self.samples.get(self.position) {
+            if pace == ReplayPace::Original {
+                let due = Duration::from_nanos(sample.total.timestamp_ns.saturating_sub(origin_ns));
+                let elapsed = started.elapsed();
+                if due > elapsed {
+                    thread::sleep(due - elapsed);
+                }
+            }
+            self.step();
+        }
+    }
+
+    /// Moves back before the first sample and clears the statistics
+    pub fn rewind(&mut self) {
+        self.position = 0;
+        self.reset_statistics();
+    }
+
+    /// Clears the statistics without moving in the trace
+    pub fn reset_statistics(&mut self) {
+        self.stats.total = empty_sensor_stats(&self.layout.total);
+        for (stats, sensor) in self.stats.sensors.iter_mut().zip(&self.layout.sensors) {
+            *stats = empty_sensor_stats(sensor);
+        }
+    }
+
+    /// Gets the sample fed last, see [`PowerMonitor::get_latest_data`]
+    ///
+    /// Before the first sample this is the recorded sensor layout with all
+    /// readings zero, like a monitor that has not sampled yet.
+    pub fn get_latest_data(&self) -> Result<OwnedPowerData, Error> {
+        Ok(match self.position {
+            0 => self.layout.clone(),
+            position => self.samples[position - 1].clone(),
+        })
+    }
+
+    /// Gets the statistics of the samples fed since the last reset, see
+    /// [`PowerMonitor::get_statistics_owned`]
+    pub fn get_statistics_owned(&self) -> Result<OwnedPowerStats, Error> {
+        Ok(self.stats.clone())
+    }
+
+    /// Gets the PS, PL and total power of the sample fed last
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PowerSummary)` - The summary
+    /// * `Err(Error::NotRunning)` - If no sample has been fed yet
+    pub fn get_power_summary(&self) -> Result<PowerSummary, Error> {
+        if self.position == 0 {
+            return Err(Error::NotRunning);
+        }
+        let latest = &self.samples[self.position - 1];
+        let power = |name: &str| {
+            latest
+                .sensors
+                .iter()
+                .find(|sensor| sensor.name() == name)
+                .map_or(0.0, |sensor| sensor.power)
+        };
+        Ok(PowerSummary {
+            ps_total_power: power("PS_TOTAL_POWER"),
+            pl_total_power: power("PL_TOTAL_POWER"),
+            total_power: power("TOTAL_POWER"),
+        })
+    }
+}
+
+impl PowerSource for ReplayMonitor {
+    fn get_power_summary(&self) -> Result<PowerSummary, Error> {
+        ReplayMonitor::get_power_summary(self)
+    }
+}
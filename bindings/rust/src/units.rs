@@ -0,0 +1,198 @@
+//! Unit-carrying wrappers for electrical quantities
+//!
+//! The raw fields of [`SensorData`] are plain `f64`s, which makes it easy to
+//! add volts to watts by accident. [`Volts`], [`Amps`], [`Watts`] and
+//! [`Joules`] carry the unit in the type and only allow arithmetic that
+//! makes physical sense, e.g. `Volts * Amps = Watts`.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use std::time::Duration;
+
+use crate::SensorData;
+
+/// Smallest current, in amperes, a resistance is derived from
+///
+/// hwmon reports currents in whole milliamperes, so anything below one step
+/// is indistinguishable from no load.
+pub const MIN_RESISTANCE_CURRENT: f64 = 1e-3;
+
+macro_rules! unit {
+    ($(#[$doc:meta])* $name:ident, $symbol:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name(pub f64);
+
+        impl fmt::Display for $name {
+            /// Formats the value followed by the unit symbol, honoring the
+            /// precision, e.g. `format!("{:.2}", Watts(1.234))` is `1.23 W`
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                f.write_str(concat!(" ", $symbol))
+            }
+        }
+
+        impl From<f64> for $name {
+            fn from(value: f64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: f64) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = Self;
+
+            fn div(self, rhs: f64) -> Self {
+                Self(self.0 / rhs)
+            }
+        }
+    };
+}
+
+unit!(
+    /// Electric potential in volts
+    Volts,
+    "V"
+);
+unit!(
+    /// Electric current in amperes
+    Amps,
+    "A"
+);
+unit!(
+    /// Power in watts
+    Watts,
+    "W"
+);
+unit!(
+    /// Energy in joules
+    Joules,
+    "J"
+);
+
+impl Mul<Amps> for Volts {
+    type Output = Watts;
+
+    fn mul(self, rhs: Amps) -> Watts {
+        Watts(self.0 * rhs.0)
+    }
+}
+
+impl Mul<Volts> for Amps {
+    type Output = Watts;
+
+    fn mul(self, rhs: Volts) -> Watts {
+        Watts(self.0 * rhs.0)
+    }
+}
+
+impl Div<Volts> for Watts {
+    type Output = Amps;
+
+    fn div(self, rhs: Volts) -> Amps {
+        Amps(self.0 / rhs.0)
+    }
+}
+
+impl Div<Amps> for Watts {
+    type Output = Volts;
+
+    fn div(self, rhs: Amps) -> Volts {
+        Volts(self.0 / rhs.0)
+    }
+}
+
+impl Mul<Duration> for Watts {
+    type Output = Joules;
+
+    fn mul(self, rhs: Duration) -> Joules {
+        Joules(self.0 * rhs.as_secs_f64())
+    }
+}
+
+impl Div<Duration> for Joules {
+    type Output = Watts;
+
+    fn div(self, rhs: Duration) -> Watts {
+        Watts(self.0 / rhs.as_secs_f64())
+    }
+}
+
+impl SensorData {
+    /// Returns the voltage as [`Volts`]
+    pub fn voltage_v(&self) -> Volts {
+        Volts(self.voltage)
+    }
+
+    /// Returns the current as [`Amps`]
+    pub fn current_a(&self) -> Amps {
+        Amps(self.current)
+    }
+
+    /// Returns the power as [`Watts`]
+    pub fn power_w(&self) -> Watts {
+        Watts(self.power)
+    }
+
+    /// Gets the effective load resistance `voltage / current` in ohms
+    ///
+    /// # Returns
+    ///
+    /// * `Some(f64)` - The resistance
+    /// * `None` - If the magnitude of the current is below
+    ///   [`MIN_RESISTANCE_CURRENT`]
+    pub fn resistance_ohms(&self) -> Option<f64> {
+        if self.current.abs() < MIN_RESISTANCE_CURRENT {
+            return None;
+        }
+        Some(self.voltage / self.current)
+    }
+
+    /// Gets the power implied by the voltage and current, `voltage * current`
+    pub fn computed_power(&self) -> f64 {
+        self.voltage * self.current
+    }
+
+    /// Gets how far the reported power is from [`SensorData::computed_power`]
+    ///
+    /// The INA226 measures power separately, so a small difference from
+    /// rounding and sampling skew is normal; a large one points at a sensor
+    /// reporting inconsistent fields.
+    ///
+    /// # Returns
+    ///
+    /// `power - voltage * current` in watts
+    pub fn power_discrepancy(&self) -> f64 {
+        self.power - self.computed_power()
+    }
+}
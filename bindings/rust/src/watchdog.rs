@@ -0,0 +1,62 @@
+//! Power-cap watchdog for protecting hardware during experiments
+//!
+//! [`PowerMonitor::set_power_cap`] watches the total power of every sample
+//! and calls back once it has stayed above a cap for a given time, so the
+//! application can stop the workload before the board overheats or trips
+//! its supply.
+
+use std::time::Duration;
+
+use crate::{Error, PowerMonitor};
+
+impl PowerMonitor {
+    /// Registers a callback for total power staying above a cap
+    ///
+    /// The callback is invoked once the total power has been above `watts`
+    /// in every sample for at least `window`, measured between the sample
+    /// timestamps, so it reacts within one sampling period of the window
+    /// elapsing. It fires once per excursion: only after the power has
+    /// dropped to or below the cap does a new excursion start. A zero
+    /// `window` trips on the first sample above the cap.
+    ///
+    /// The callback runs on the sampling thread under the same rules as the
+    /// one of [`PowerMonitor::on_threshold`] and should return quickly, e.g.
+    /// by signalling the workload to stop rather than waiting for it. It is
+    /// dropped together with the monitor.
+    ///
+    /// # Arguments
+    ///
+    /// * `watts` - Cap on the total power in watts
+    /// * `window` - How long the power must stay above the cap
+    /// * `on_trip` - Called when the cap has been exceeded for `window`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Success
+    /// * `Err(Error::InvalidArgument)` - If `watts` is negative or not finite
+    pub fn set_power_cap<F>(&self, watts: f64, window: Duration, mut on_trip: F) -> Result<(), Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        if !(watts.is_finite() && watts >= 0.0) {
+            return Err(Error::InvalidArgument);
+        }
+        let window_ns = window.as_nanos().min(u64::MAX as u128) as u64;
+        // Timestamp of the first sample of the current excursion, and whether
+        // it has tripped already
+        let mut excursion: Option<(u64, bool)> = None;
+        self.sampler.add_listener(move |data| {
+            let total = &data.total;
+            if total.power <= watts {
+                excursion = None;
+                return;
+            }
+            let (since_ns, tripped) = excursion.get_or_insert((total.timestamp_ns, false));
+            if !*tripped && total.timestamp_ns.saturating_sub(*since_ns) >= window_ns {
+                *tripped = true;
+                on_trip();
+            }
+        });
+        Ok(())
+    }
+}
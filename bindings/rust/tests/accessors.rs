@@ -0,0 +1,249 @@
+//! Tests for the safe accessors on the raw FFI types that do not require sensor hardware
+
+use xlnpwmon::{Error, PowerData, PowerStats, SensorData, SensorStats, SensorType, Stats};
+
+/// Builds a synthetic sensor reading with the given power
+fn sensor(power: f64) -> SensorData {
+    SensorData {
+        power,
+        ..SensorData::default()
+    }
+}
+
+/// Test that `sensors()` views the sensor array
+#[test]
+fn test_sensors_slice() {
+    println!("\n=== Running test_sensors_slice ===");
+    let sensors = [sensor(1.0), sensor(2.0), sensor(3.0)];
+    let data = PowerData {
+        total: sensor(6.0),
+        sensors: sensors.as_ptr(),
+        sensor_count: sensors.len() as i32,
+    };
+    let powers: Vec<f64> = data.sensors().iter().map(|s| s.power).collect();
+    assert_eq!(powers, [1.0, 2.0, 3.0]);
+
+    let sensor_stats = [SensorStats::default(); 2];
+    let stats = PowerStats {
+        total: SensorStats::default(),
+        sensors: sensor_stats.as_ptr(),
+        sensor_count: sensor_stats.len() as i32,
+    };
+    assert_eq!(stats.sensors().len(), 2);
+}
+
+/// Test that a null pointer or a non-positive count yields an empty slice
+#[test]
+fn test_sensors_slice_empty() {
+    println!("\n=== Running test_sensors_slice_empty ===");
+    let null = PowerData {
+        total: sensor(0.0),
+        sensors: std::ptr::null(),
+        sensor_count: 4,
+    };
+    assert!(null.sensors().is_empty());
+
+    let sensors = [sensor(1.0)];
+    let negative = PowerData {
+        total: sensor(0.0),
+        sensors: sensors.as_ptr(),
+        sensor_count: -1,
+    };
+    assert!(negative.sensors().is_empty());
+
+    let stats = PowerStats {
+        total: SensorStats::default(),
+        sensors: std::ptr::null(),
+        sensor_count: 0,
+    };
+    assert!(stats.sensors().is_empty());
+}
+
+/// Test that `iter()` walks the sensor statistics and stays empty for a null
+/// pointer or a non-positive count
+#[test]
+fn test_stats_iter() {
+    println!("\n=== Running test_stats_iter ===");
+    let mut sensor_stats = [SensorStats::default(); 3];
+    for (i, stats) in sensor_stats.iter_mut().enumerate() {
+        stats.power.count = i as u64 + 1;
+    }
+    let stats = PowerStats {
+        total: SensorStats::default(),
+        sensors: sensor_stats.as_ptr(),
+        sensor_count: sensor_stats.len() as i32,
+    };
+    let counts: Vec<u64> = stats.iter().map(|s| s.power.count).collect();
+    assert_eq!(counts, [1, 2, 3]);
+    assert_eq!((&stats).into_iter().count(), 3);
+
+    let null = PowerStats {
+        total: SensorStats::default(),
+        sensors: std::ptr::null(),
+        sensor_count: 3,
+    };
+    assert_eq!(null.iter().count(), 0);
+    let negative = PowerStats {
+        total: SensorStats::default(),
+        sensors: sensor_stats.as_ptr(),
+        sensor_count: -2,
+    };
+    assert_eq!(negative.iter().count(), 0);
+}
+
+/// Test decoding of the fixed-size name and status buffers
+#[test]
+fn test_name_and_status() {
+    println!("\n=== Running test_name_and_status ===");
+    let mut sensor = sensor(0.0);
+    sensor.name[..6].copy_from_slice(b"VCCINT");
+    sensor.status[..2].copy_from_slice(b"OK");
+    assert_eq!(sensor.name(), "VCCINT");
+    assert_eq!(sensor.status(), "OK");
+
+    // Bytes after the first NUL are ignored
+    sensor.name[7..10].copy_from_slice(b"XYZ");
+    assert_eq!(sensor.name(), "VCCINT");
+
+    assert_eq!(SensorStats::new("VCCBRAM").name(), "VCCBRAM");
+}
+
+/// Test buffers without a NUL terminator and buffers of only NULs
+#[test]
+fn test_name_edge_cases() {
+    println!("\n=== Running test_name_edge_cases ===");
+    let mut sensor = sensor(0.0);
+    assert_eq!(sensor.name(), "");
+    assert_eq!(sensor.status(), "");
+
+    sensor.name = [b'A'; 64];
+    sensor.status = [b'B'; 32];
+    assert_eq!(sensor.name().len(), 64);
+    assert_eq!(sensor.status(), "B".repeat(32));
+
+    // Invalid UTF-8 is replaced instead of panicking
+    sensor.name = [0; 64];
+    sensor.name[..3].copy_from_slice(&[b'V', 0xff, b'X']);
+    assert_eq!(sensor.name(), "V\u{fffd}X");
+}
+
+/// Test the named constructors, which keep the NUL terminator of names
+/// that do not fit
+#[test]
+fn test_named_constructors() {
+    println!("\n=== Running test_named_constructors ===");
+    let sensor = SensorData::new("VCCINT");
+    assert_eq!(sensor.name(), "VCCINT");
+    assert_eq!(sensor, SensorData { name: sensor.name, ..SensorData::default() });
+    assert!(!sensor.online);
+    assert!(matches!(sensor.type_, SensorType::Unknown));
+
+    let long = "A".repeat(62) + "é";
+    assert_eq!(SensorData::new(&long).name(), "A".repeat(62));
+    assert_eq!(SensorStats::new(&"B".repeat(100)).name(), "B".repeat(63));
+    assert_eq!(SensorStats::new("VCCINT").power, Stats::default());
+}
+
+/// Test the one-line formatting of readings and statistics
+#[test]
+fn test_display() {
+    println!("\n=== Running test_display ===");
+    let mut sensor = sensor(1.02);
+    sensor.name[..6].copy_from_slice(b"VCCINT");
+    sensor.voltage = 0.85;
+    sensor.current = 1.2;
+    sensor.online = true;
+    assert_eq!(sensor.to_string(), "VCCINT: 0.85 V, 1.20 A, 1.02 W [online]");
+    sensor.online = false;
+    assert_eq!(format!("{:.1}", sensor), "VCCINT: 0.8 V, 1.2 A, 1.0 W [offline]");
+
+    let stats = Stats {
+        min: 0.98,
+        max: 1.1,
+        avg: 1.02,
+        total: 10.2,
+        count: 10,
+        sum_sq_dev: 0.0,
+        stddev: 0.0,
+    };
+    assert_eq!(stats.to_string(), "min/avg/max = 0.98 / 1.02 / 1.10");
+    assert_eq!(format!("{:.3}", stats), "min/avg/max = 0.980 / 1.020 / 1.100");
+}
+
+/// Test strict and lenient conversion of raw sensor types
+#[test]
+fn test_sensor_type_conversion() {
+    println!("\n=== Running test_sensor_type_conversion ===");
+    assert_eq!(SensorType::try_from(0).unwrap(), SensorType::Unknown);
+    assert_eq!(SensorType::try_from(1).unwrap(), SensorType::I2C);
+    assert_eq!(SensorType::try_from(2).unwrap(), SensorType::System);
+    assert!(matches!(SensorType::try_from(3), Err(Error::InvalidArgument)));
+    assert!(matches!(SensorType::try_from(-1), Err(Error::InvalidArgument)));
+
+    assert_eq!(SensorType::from_raw(1), SensorType::I2C);
+    assert_eq!(SensorType::from_raw(2), SensorType::System);
+    assert_eq!(SensorType::from_raw(42), SensorType::Unknown);
+
+    for sensor_type in [SensorType::Unknown, SensorType::I2C, SensorType::System] {
+        assert_eq!(SensorType::try_from(i32::from(sensor_type)).unwrap(), sensor_type);
+    }
+}
+
+/// Test that owned copies do not depend on the source buffer
+#[test]
+fn test_to_owned() {
+    println!("\n=== Running test_to_owned ===");
+    let owned = {
+        let sensors = [sensor(1.0), sensor(2.0)];
+        let data = PowerData {
+            total: sensor(3.0),
+            sensors: sensors.as_ptr(),
+            sensor_count: sensors.len() as i32,
+        };
+        data.to_owned()
+    };
+    assert_eq!(owned.total.power, 3.0);
+    let powers: Vec<f64> = owned.sensors.iter().map(|s| s.power).collect();
+    assert_eq!(powers, [1.0, 2.0]);
+
+    let sensor_stats = [SensorStats::default(); 3];
+    let stats = PowerStats {
+        total: SensorStats::default(),
+        sensors: sensor_stats.as_ptr(),
+        sensor_count: sensor_stats.len() as i32,
+    };
+    assert_eq!(stats.to_owned().sensors.len(), 3);
+
+    let empty = PowerData {
+        total: sensor(0.0),
+        sensors: std::ptr::null(),
+        sensor_count: 0,
+    };
+    assert!(empty.to_owned().sensors.is_empty());
+}
+
+/// Test the error messages and the conversion into `std::io::Error`
+#[test]
+fn test_error_into_io_error() {
+    println!("\n=== Running test_error_into_io_error ===");
+    assert_eq!(Error::FileAccess.to_string(), "Error accessing sensor files");
+    assert_eq!(Error::Unknown(-42).to_string(), "Unknown error (code -42)");
+
+    let cases = [
+        (Error::FileAccess, std::io::ErrorKind::PermissionDenied),
+        (Error::NoSensors, std::io::ErrorKind::NotFound),
+        (Error::InvalidArgument, std::io::ErrorKind::InvalidInput),
+        (Error::Timeout, std::io::ErrorKind::TimedOut),
+        (Error::Thread, std::io::ErrorKind::Other),
+    ];
+    for (error, kind) in cases {
+        let message = error.to_string();
+        let io_error = std::io::Error::from(error);
+        assert_eq!(io_error.kind(), kind);
+        assert_eq!(io_error.to_string(), message);
+    }
+
+    let io_error = std::io::Error::from(Error::FileAccess);
+    let inner = io_error.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert!(matches!(*inner, Error::FileAccess));
+}
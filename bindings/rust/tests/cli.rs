@@ -0,0 +1,43 @@
+//! Tests for the `xlnpwmon` binary, run against the mock backend
+#![cfg(feature = "mock")]
+
+use std::process::Command;
+
+fn xlnpwmon() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_xlnpwmon"))
+}
+
+/// Test that a short capture prints every sensor, the totals and a CSV file
+#[test]
+fn test_cli_table() {
+    println!("\n=== Running test_cli_table ===");
+    let csv = std::env::temp_dir().join(format!("xlnpwmon-cli-{}.csv", std::process::id()));
+    let output = xlnpwmon()
+        .args(["--freq", "100", "--duration", "0.1", "--csv"])
+        .arg(&csv)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("{}", stdout);
+    assert!(output.status.success());
+    for name in ["VCCPSINTFP", "VCCINT", "VCC3V3", "TOTAL_POWER"] {
+        assert!(stdout.contains(name), "missing {}", name);
+    }
+    assert!(stdout.contains("Total: 7.125 W (avg 7.125 W"));
+
+    let rows = std::fs::read_to_string(&csv).unwrap();
+    std::fs::remove_file(&csv).unwrap();
+    assert!(rows.starts_with("timestamp,sensor,voltage,current,power\n"));
+    assert!(rows.lines().count() > 1);
+}
+
+/// Test that bad arguments are reported with the usage and a failure status
+#[test]
+fn test_cli_bad_args() {
+    println!("\n=== Running test_cli_bad_args ===");
+    for args in [&["--bogus"][..], &["--freq", "fast"], &["--duration"]] {
+        let output = xlnpwmon().args(args).output().unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with("xlnpwmon: "));
+    }
+}
@@ -0,0 +1,72 @@
+//! Tests for clipping detection that do not require sensor hardware
+
+use xlnpwmon::{ClippingDetector, Error, SensorData};
+
+/// Builds a synthetic sensor reading
+fn sensor(name: &str, voltage: f64, current: f64) -> SensorData {
+    SensorData {
+        voltage,
+        current,
+        power: voltage * current,
+        online: true,
+        ..SensorData::new(name)
+    }
+}
+
+/// Test that a sensor pinned at full scale is reported after the window
+#[test]
+fn test_pinned_sensor() {
+    println!("\n=== Running test_pinned_sensor ===");
+    let mut detector = ClippingDetector::new();
+    detector.set_policy(0.95, 5).unwrap();
+    detector.set_full_scale("VCCINT", 1.0, 8.0).unwrap();
+    detector.set_full_scale("VCCAUX", 2.0, 4.0).unwrap();
+
+    // VCCINT current is pinned at 8 A, VCCAUX moves freely
+    for i in 0..4 {
+        detector.observe(&[sensor("VCCINT", 0.85, 8.0), sensor("VCCAUX", 1.8, 1.0 + i as f64 * 0.1)]);
+    }
+    assert!(detector.clipped().is_empty(), "reported before the window elapsed");
+
+    detector.observe(&[sensor("VCCINT", 0.85, 8.0), sensor("VCCAUX", 1.8, 1.2)]);
+    assert_eq!(detector.clipped(), vec!["VCCINT".to_string()]);
+
+    // Reports stay latched after the reading recovers
+    detector.observe(&[sensor("VCCINT", 0.85, 2.0), sensor("VCCAUX", 1.8, 1.2)]);
+    assert_eq!(detector.clipped(), vec!["VCCINT".to_string()]);
+
+    detector.clear();
+    assert!(detector.clipped().is_empty());
+}
+
+/// Test that an interrupted run of pinned samples is not reported
+#[test]
+fn test_transient_peak() {
+    println!("\n=== Running test_transient_peak ===");
+    let mut detector = ClippingDetector::new();
+    detector.set_policy(0.95, 3).unwrap();
+    detector.set_full_scale("VCCINT", 1.0, 8.0).unwrap();
+
+    for current in [8.0, 8.0, 5.0, 8.0, 8.0, 5.0] {
+        detector.observe(&[sensor("VCCINT", 0.85, current)]);
+    }
+    assert!(detector.clipped().is_empty());
+
+    // Sensors without a configured full scale are ignored
+    for _ in 0..10 {
+        detector.observe(&[sensor("MGTAVCC", 100.0, 100.0)]);
+    }
+    assert!(detector.clipped().is_empty());
+}
+
+/// Test validation of the detection policy and full-scale values
+#[test]
+fn test_clipping_validation() {
+    println!("\n=== Running test_clipping_validation ===");
+    let mut detector = ClippingDetector::new();
+    assert!(matches!(detector.set_policy(0.0, 5), Err(Error::InvalidArgument)));
+    assert!(matches!(detector.set_policy(1.1, 5), Err(Error::InvalidArgument)));
+    assert!(matches!(detector.set_policy(0.9, 0), Err(Error::InvalidArgument)));
+    assert!(matches!(detector.set_full_scale("VCCINT", 0.0, 1.0), Err(Error::InvalidArgument)));
+    assert!(matches!(detector.set_full_scale("VCCINT", 1.0, -1.0), Err(Error::InvalidArgument)));
+}
@@ -0,0 +1,79 @@
+//! Tests for exported monitor configurations that do not require sensor hardware
+
+use xlnpwmon::{
+    Error, FullScale, MonitorConfig, PowerMonitorConfig, SensorThresholds, DEFAULT_CLIPPING_FRACTION,
+    DEFAULT_CLIPPING_WINDOW,
+};
+
+/// Builds a configuration with every field set
+fn sample_config() -> MonitorConfig {
+    let mut config = MonitorConfig {
+        sampling_frequency_hz: Some(250),
+        quantize: Some(3),
+        reset_on_start: true,
+        clipping_fraction: 0.9,
+        clipping_window: 4,
+        history_capacity: 100,
+        sensors: Some(vec!["VCCINT".to_string(), "TOTAL_POWER".to_string()]),
+        ..MonitorConfig::default()
+    };
+    config
+        .groups
+        .insert("fabric".to_string(), vec!["VCCINT".to_string(), "VCCBRAM".to_string()]);
+    config.full_scales.insert(
+        "VCCINT".to_string(),
+        FullScale {
+            voltage: 1.0,
+            current: 8.0,
+        },
+    );
+    config.thresholds.insert(
+        "VCCINT".to_string(),
+        SensorThresholds {
+            warning: 3.5,
+            critical: 4.5,
+        },
+    );
+    config
+}
+
+/// Test that the default configuration matches `PowerMonitor::new`
+#[test]
+fn test_default_config() {
+    println!("\n=== Running test_default_config ===");
+    let config = MonitorConfig::default();
+    assert_eq!(config.sampling_frequency_hz, None);
+    assert_eq!(config.quantize, None);
+    assert!(config.reset_on_start);
+    assert!(config.groups.is_empty());
+    assert!(config.full_scales.is_empty());
+    assert_eq!(config.clipping_fraction, DEFAULT_CLIPPING_FRACTION);
+    assert_eq!(config.clipping_window, DEFAULT_CLIPPING_WINDOW);
+    assert_eq!(config.history_capacity, 0);
+    assert_eq!(config.sensors, None);
+    assert!(config.thresholds.is_empty());
+    assert_ne!(sample_config(), config);
+}
+
+/// Test a JSON round trip of a configuration
+#[cfg(feature = "serde")]
+#[test]
+fn test_config_json_round_trip() {
+    println!("\n=== Running test_config_json_round_trip ===");
+    let config = sample_config();
+    let json = serde_json::to_string_pretty(&config).unwrap();
+    println!("{}", json);
+    let restored: MonitorConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, config);
+    assert_eq!(restored.thresholds["VCCINT"].critical, 4.5);
+}
+
+/// Test that an invalid frequency is rejected before the library is touched
+#[test]
+fn test_builder_rejects_invalid_frequency() {
+    println!("\n=== Running test_builder_rejects_invalid_frequency ===");
+    for frequency_hz in [0, -10] {
+        let result = PowerMonitorConfig::new().frequency_hz(frequency_hz).build();
+        assert!(matches!(result, Err(Error::InvalidFrequency)));
+    }
+}
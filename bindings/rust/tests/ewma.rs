@@ -0,0 +1,55 @@
+//! Tests for EWMA smoothing that do not require sensor hardware
+
+use xlnpwmon::{Error, Ewma};
+
+/// Test that alpha is validated to (0, 1]
+#[test]
+fn test_ewma_alpha_validation() {
+    println!("\n=== Running test_ewma_alpha_validation ===");
+    assert!(matches!(Ewma::new(0.0), Err(Error::InvalidArgument)));
+    assert!(matches!(Ewma::new(-0.5), Err(Error::InvalidArgument)));
+    assert!(matches!(Ewma::new(1.5), Err(Error::InvalidArgument)));
+    assert!(matches!(Ewma::new(f64::NAN), Err(Error::InvalidArgument)));
+    assert!(Ewma::new(1.0).is_ok());
+    assert!(Ewma::new(0.25).is_ok());
+}
+
+/// Test the EWMA response to a step input
+#[test]
+fn test_ewma_step_response() {
+    println!("\n=== Running test_ewma_step_response ===");
+    let alpha = 0.5;
+    let mut ewma = Ewma::new(alpha).unwrap();
+    assert_eq!(ewma.value(), None);
+
+    // Settle at 2 W
+    for _ in 0..10 {
+        ewma.update(2.0);
+    }
+    assert_eq!(ewma.value(), Some(2.0));
+
+    // Step to 10 W: after n samples the remaining error is (1 - alpha)^n
+    let mut previous = 2.0;
+    for n in 1..=20 {
+        let value = ewma.update(10.0);
+        let expected = 10.0 - 8.0 * (1.0 - alpha).powi(n);
+        assert!((value - expected).abs() < 1e-9, "sample {}: {} != {}", n, value, expected);
+        assert!(value > previous && value <= 10.0);
+        previous = value;
+    }
+    assert!((ewma.value().unwrap() - 10.0).abs() < 1e-4);
+
+    ewma.reset();
+    assert_eq!(ewma.value(), None);
+    assert_eq!(ewma.update(4.0), 4.0);
+}
+
+/// Test that alpha = 1 follows the input exactly
+#[test]
+fn test_ewma_unsmoothed() {
+    println!("\n=== Running test_ewma_unsmoothed ===");
+    let mut ewma = Ewma::new(1.0).unwrap();
+    for sample in [1.0, 7.0, 3.0, 5.0] {
+        assert_eq!(ewma.update(sample), sample);
+    }
+}
@@ -0,0 +1,114 @@
+//! Core API checks that need neither optional features nor sensor hardware
+//!
+//! These must keep compiling with `--no-default-features`.
+
+use xlnpwmon::{
+    Error, Heartbeat, OwnedPowerData, OwnedPowerStats, PowerMonitorBuilder, PowerSummary,
+    ReplayMonitor, ReplayPace, SensorData, SensorStats, SensorType, Stats,
+};
+
+/// Test that the core types can be constructed without any optional feature
+#[test]
+fn test_core_types() {
+    let sensor = SensorData::default();
+    let stats = SensorStats::default();
+    let data = OwnedPowerData {
+        total: sensor,
+        sensors: vec![sensor],
+    };
+    let owned_stats = OwnedPowerStats {
+        total: stats,
+        sensors: vec![stats],
+    };
+    assert_eq!(data.sensors.len(), owned_stats.sensors.len());
+    assert!(matches!(sensor.type_, SensorType::Unknown));
+
+    let summary = PowerSummary {
+        ps_total_power: 0.0,
+        pl_total_power: 0.0,
+        total_power: 0.0,
+    };
+    assert_eq!(summary.total_power, 0.0);
+
+    assert_eq!(i32::from(Error::NoSensors), -6);
+    let _builder = PowerMonitorBuilder::new().quantize(3).reset_on_start(false);
+    let _heartbeat: Option<Heartbeat> = None;
+}
+
+/// Test that a monitor can be shared between threads
+#[test]
+fn test_monitor_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<xlnpwmon::PowerMonitor>();
+}
+
+/// Test that the C library version is a dotted numeric version
+#[test]
+fn test_version() {
+    let version = xlnpwmon::version();
+    assert!(!version.is_empty());
+    let parts: Vec<&str> = version.split('.').collect();
+    assert_eq!(parts.len(), 3, "unexpected version {:?}", version);
+    assert!(parts.iter().all(|part| part.parse::<u32>().is_ok()), "unexpected version {:?}", version);
+}
+
+/// Test that the plain data structs compare by value
+#[test]
+fn test_stats_equality() {
+    let stats = Stats {
+        min: 1.0,
+        max: 3.0,
+        avg: 2.0,
+        total: 6.0,
+        count: 3,
+        sum_sq_dev: 2.0,
+        stddev: (2.0f64 / 3.0).sqrt(),
+    };
+    let copy = stats;
+    assert_eq!(stats, copy);
+
+    let mut changed = stats;
+    changed.max = 3.5;
+    assert_ne!(stats, changed);
+
+    let sensor: SensorStats = SensorStats {
+        voltage: stats,
+        ..SensorStats::default()
+    };
+    assert_eq!(sensor, sensor.clone());
+    assert_ne!(sensor, SensorStats { power: changed, ..sensor });
+}
+
+/// Test the standard deviation of a known series, replayed from a trace
+/// holding only the total
+#[test]
+fn test_stats_stddev() {
+    println!("\n=== Running test_stats_stddev ===");
+    let mut trace = b"XPWTRACE".to_vec();
+    trace.extend_from_slice(&1u16.to_le_bytes());
+    trace.extend_from_slice(&0u16.to_le_bytes());
+    let mut name = [0u8; 64];
+    name[..5].copy_from_slice(b"Total");
+    trace.extend_from_slice(&name);
+    trace.extend_from_slice(&(SensorType::System as i32).to_le_bytes());
+    trace.extend_from_slice(&[0; 16]);
+    for (i, power) in [2.0f64, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().enumerate() {
+        trace.extend_from_slice(&(i as u64 * 1_000_000).to_le_bytes());
+        for value in [1.0, *power, *power] {
+            trace.extend_from_slice(&f64::to_le_bytes(value));
+        }
+    }
+    let path = std::env::temp_dir().join(format!("xlnpwmon-stddev-{}.bin", std::process::id()));
+    std::fs::write(&path, trace).unwrap();
+    let mut replay = ReplayMonitor::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    // Version 1 traces carry no metadata
+    assert!(replay.metadata().is_none());
+
+    replay.play(ReplayPace::AsFastAsPossible);
+    let stats = replay.get_statistics_owned().unwrap().total;
+    assert_eq!(stats.voltage.stddev, 0.0);
+    assert_eq!(stats.power.avg, 5.0);
+    assert!((stats.power.sum_sq_dev - 32.0).abs() < 1e-12);
+    assert!((stats.power.stddev - 2.0).abs() < 1e-12);
+}
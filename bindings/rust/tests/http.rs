@@ -0,0 +1,55 @@
+//! Tests for the `/metrics` endpoint, served from the mock backend
+#![cfg(all(feature = "http", feature = "mock"))]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use xlnpwmon::PowerMonitor;
+
+/// Sends a raw request and returns the whole response
+fn request(addr: SocketAddr, request: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// Test that `/metrics` serves the exposition text and other requests are
+/// rejected
+#[test]
+fn test_serve_metrics() {
+    println!("\n=== Running test_serve_metrics ===");
+    let monitor = Arc::new(PowerMonitor::new().unwrap());
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let server = Arc::clone(&monitor);
+    thread::spawn(move || xlnpwmon::serve_metrics(server, addr));
+
+    let mut response = None;
+    for _ in 0..50 {
+        if TcpStream::connect(addr).is_ok() {
+            response = Some(request(addr, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n"));
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let response = response.expect("server did not start");
+    println!("{}", response);
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+    assert!(response.contains("xlnpwmon_power_total_watts 7.125\n"));
+
+    let response = request(addr, "GET /other HTTP/1.1\r\n\r\n");
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    let response = request(addr, "POST /metrics HTTP/1.1\r\n\r\n");
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+    monitor.stop_sampling().unwrap();
+}
@@ -0,0 +1,216 @@
+//! Layout checks of the `#[repr(C)]` structs shared with the C library
+//!
+//! A field that is reordered or retyped on one side only does not fail to
+//! compile; it makes every read through the other side garbage. The fixed
+//! sizes and offsets below are those of the C definitions in `xlnpwmon.h` on
+//! the 64-bit Linux targets the library runs on (aarch64 boards, x86_64
+//! hosts), as reported by `offsetof`.
+
+#[cfg(feature = "bindgen")]
+use xlnpwmon::sys;
+use xlnpwmon::{PowerData, PowerStats, PowerSummary, PowerSummaryStats, SensorData, SensorStats, SensorType, Stats};
+
+/// Byte offset of `$field` within `$ty`
+macro_rules! offset_of {
+    ($ty:ty, $field:ident) => {{
+        let value = std::mem::MaybeUninit::<$ty>::uninit();
+        let base = value.as_ptr();
+        let field = unsafe { std::ptr::addr_of!((*base).$field) };
+        field as usize - base as usize
+    }};
+}
+
+/// Asserts the size, alignment and field offsets of a struct
+macro_rules! assert_layout {
+    ($ty:ty, size = $size:expr, align = $align:expr, [$($field:ident => $offset:expr),* $(,)?]) => {{
+        assert_eq!(std::mem::size_of::<$ty>(), $size, "size of {}", stringify!($ty));
+        assert_eq!(std::mem::align_of::<$ty>(), $align, "alignment of {}", stringify!($ty));
+        $(
+            assert_eq!(offset_of!($ty, $field), $offset, "offset of {}::{}", stringify!($ty), stringify!($field));
+        )*
+    }};
+}
+
+/// Asserts that two structs have the same size, alignment and field offsets
+#[cfg(feature = "bindgen")]
+macro_rules! assert_same_layout {
+    ($rust:ty, $c:ty, [$($field:ident),* $(,)?]) => {{
+        assert_eq!(std::mem::size_of::<$rust>(), std::mem::size_of::<$c>(), "size of {}", stringify!($rust));
+        assert_eq!(std::mem::align_of::<$rust>(), std::mem::align_of::<$c>(), "alignment of {}", stringify!($rust));
+        $(
+            assert_eq!(
+                offset_of!($rust, $field),
+                offset_of!($c, $field),
+                "offset of {}::{}",
+                stringify!($rust),
+                stringify!($field)
+            );
+        )*
+    }};
+}
+
+/// Test the layout of the per-sensor reading, including the padding around
+/// `bool online`
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_sensor_data_layout() {
+    println!("\n=== Running test_sensor_data_layout ===");
+    assert_eq!(std::mem::size_of::<SensorType>(), 4);
+    assert_layout!(SensorData, size = 160, align = 8, [
+        name => 0,
+        type_ => 64,
+        voltage => 72,
+        current => 80,
+        power => 88,
+        online => 96,
+        status => 97,
+        warning_threshold => 136,
+        critical_threshold => 144,
+        timestamp_ns => 152,
+    ]);
+}
+
+/// Test the layout of the statistics structs
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_stats_layout() {
+    println!("\n=== Running test_stats_layout ===");
+    assert_layout!(Stats, size = 56, align = 8, [
+        min => 0,
+        max => 8,
+        avg => 16,
+        total => 24,
+        count => 32,
+        sum_sq_dev => 40,
+        stddev => 48,
+    ]);
+    assert_layout!(SensorStats, size = 232, align = 8, [
+        name => 0,
+        voltage => 64,
+        current => 120,
+        power => 176,
+    ]);
+    assert_layout!(PowerSummary, size = 24, align = 8, [
+        ps_total_power => 0,
+        pl_total_power => 8,
+        total_power => 16,
+    ]);
+    assert_layout!(PowerSummaryStats, size = 168, align = 8, [
+        ps_total_power => 0,
+        pl_total_power => 56,
+        total_power => 112,
+    ]);
+}
+
+/// Test the layout of the structs pointing into the library's buffers
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_power_data_layout() {
+    println!("\n=== Running test_power_data_layout ===");
+    assert_layout!(PowerData, size = 176, align = 8, [
+        total => 0,
+        sensors => 160,
+        sensor_count => 168,
+    ]);
+    assert_layout!(PowerStats, size = 248, align = 8, [
+        total => 0,
+        sensors => 232,
+        sensor_count => 240,
+    ]);
+}
+
+/// Test that the hand-written structs and constants match the ones bindgen
+/// generates from the header
+#[cfg(feature = "bindgen")]
+#[test]
+fn test_layout_matches_bindgen() {
+    println!("\n=== Running test_layout_matches_bindgen ===");
+    assert_same_layout!(
+        SensorData,
+        sys::pm_sensor_data_t,
+        [name, type_, voltage, current, power, online, status, warning_threshold, critical_threshold, timestamp_ns]
+    );
+    assert_same_layout!(Stats, sys::pm_stats_t, [min, max, avg, total, count, sum_sq_dev, stddev]);
+    assert_same_layout!(SensorStats, sys::pm_sensor_stats_t, [name, voltage, current, power]);
+    assert_same_layout!(PowerData, sys::pm_power_data_t, [total, sensors, sensor_count]);
+    assert_same_layout!(PowerStats, sys::pm_power_stats_t, [total, sensors, sensor_count]);
+    assert_same_layout!(PowerSummary, sys::pm_power_summary_t, [ps_total_power, pl_total_power, total_power]);
+    assert_same_layout!(
+        PowerSummaryStats,
+        sys::pm_power_summary_stats_t,
+        [ps_total_power, pl_total_power, total_power]
+    );
+    assert_eq!(sys::PM_DEFAULT_SAMPLING_FREQUENCY_HZ as i32, xlnpwmon::DEFAULT_FREQUENCY_HZ);
+}
+
+/// Test that every function declared by hand has the signature bindgen
+/// generates from the header, with the hand-written types mapped to the
+/// generated ones
+///
+/// A parameter added, dropped or retyped in the header then fails to
+/// compile here instead of passing garbage at run time.
+#[cfg(feature = "bindgen")]
+#[test]
+fn test_extern_signatures_match_bindgen() {
+    use std::os::raw::{c_char, c_void};
+
+    use sys::{
+        pm_device_info_t as DeviceInfo, pm_error_t as Code, pm_handle_t as Handle, pm_power_data_t as Data,
+        pm_power_stats_t as PowerStat, pm_power_summary_stats_t as SummaryStats, pm_power_summary_t as Summary, pm_sample_callback_t as Callback,
+        pm_sensor_data_t as Sensor, pm_sensor_stats_t as SensorStat, pm_stats_t as Stat,
+    };
+
+    println!("\n=== Running test_extern_signatures_match_bindgen ===");
+    let _: unsafe extern "C" fn(*mut Handle) -> Code = sys::pm_init;
+    let _: unsafe extern "C" fn(*mut Handle, *const c_char) -> Code = sys::pm_init_device;
+    let _: unsafe extern "C" fn(*mut Handle, *const c_char) -> Code = sys::pm_init_sysfs;
+    let _: unsafe extern "C" fn(*mut DeviceInfo, *mut i32) -> Code = sys::pm_enumerate_devices;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_cleanup;
+    let _: unsafe extern "C" fn(Handle, i32) -> Code = sys::pm_set_sampling_frequency;
+    let _: unsafe extern "C" fn(Handle, i32, *mut i32) -> Code = sys::pm_swap_sampling_frequency;
+    let _: unsafe extern "C" fn(Handle, *mut i32) -> Code = sys::pm_get_sampling_frequency;
+    let _: unsafe extern "C" fn(Handle, *mut i32, *mut i32) -> Code = sys::pm_get_frequency_limits;
+    let _: unsafe extern "C" fn(Handle, u64) -> Code = sys::pm_set_sampling_interval;
+    let _: unsafe extern "C" fn(Handle, *mut u64) -> Code = sys::pm_get_sampling_interval;
+    let _: unsafe extern "C" fn(Handle, *const i32, i32) -> Code = sys::pm_set_sampling_thread_affinity;
+    let _: unsafe extern "C" fn(Handle, i32) -> Code = sys::pm_set_sampling_thread_priority;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_start_sampling;
+    let _: unsafe extern "C" fn(Handle, i32) -> Code = sys::pm_start_sampling_for;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_stop_sampling;
+    let _: unsafe extern "C" fn(Handle, *mut bool) -> Code = sys::pm_is_sampling;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_pause_sampling;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_resume_sampling;
+    let _: unsafe extern "C" fn(Handle, *mut bool) -> Code = sys::pm_is_paused;
+    let _: unsafe extern "C" fn(Handle, *mut Data) -> Code = sys::pm_get_latest_data;
+    let _: unsafe extern "C" fn(Handle, *mut Sensor, i32, *mut Sensor) -> Code = sys::pm_copy_latest_data;
+    let _: unsafe extern "C" fn(Handle, *mut Data, *mut bool) -> Code = sys::pm_try_get_latest_data;
+    let _: unsafe extern "C" fn(Handle, *mut Data) -> Code = sys::pm_read_once;
+    let _: unsafe extern "C" fn(Handle, *mut Data, i32) -> Code = sys::pm_wait_for_next_sample;
+    let _: unsafe extern "C" fn(Handle, *mut PowerStat) -> Code = sys::pm_get_statistics;
+    let _: unsafe extern "C" fn(Handle, *mut SensorStat, i32, *mut SensorStat) -> Code = sys::pm_copy_statistics;
+    let _: unsafe extern "C" fn(Handle) -> Code = sys::pm_reset_statistics;
+    let _: unsafe extern "C" fn(Handle, *const c_char, *mut SensorStat) -> Code = sys::pm_get_sensor_statistics;
+    let _: unsafe extern "C" fn(Handle, *const c_char) -> Code = sys::pm_reset_sensor_statistics;
+    let _: unsafe extern "C" fn(Handle, *mut Stat) -> Code = sys::pm_get_interval_statistics;
+    let _: unsafe extern "C" fn(Handle, *mut bool, *mut u64) -> Code = sys::pm_get_sampling_health;
+    let _: unsafe extern "C" fn(Handle, *mut Summary) -> Code = sys::pm_get_power_summary;
+    let _: unsafe extern "C" fn(Handle, *mut SummaryStats) -> Code = sys::pm_get_power_summary_stats;
+    let _: unsafe extern "C" fn(Handle, *mut i32) -> Code = sys::pm_get_sensor_count;
+    let _: unsafe extern "C" fn(Handle, *mut c_char, *mut i32) -> Code = sys::pm_copy_sensor_names;
+    let _: unsafe extern "C" fn(Handle, i32) -> Code = sys::pm_set_history_capacity;
+    let _: unsafe extern "C" fn(Handle, *mut Sensor, *mut Sensor, *mut i32) -> Code = sys::pm_get_history;
+    let _: unsafe extern "C" fn(Handle, *const c_char, f64, f64) -> Code = sys::pm_set_thresholds;
+    let _: unsafe extern "C" fn(Handle, Callback, *mut c_void) -> Code = sys::pm_set_sample_callback;
+    #[cfg(feature = "mock")]
+    let _: unsafe extern "C" fn(Handle, *const c_char, bool) -> Code = sys::pm_mock_set_sensor_online;
+    #[cfg(feature = "mock")]
+    let _: unsafe extern "C" fn(Handle, *const c_char, f64, u64) -> Code = sys::pm_mock_add_power_samples;
+    let _: unsafe extern "C" fn(Code) -> *const c_char = sys::pm_error_string;
+    let _: unsafe extern "C" fn(*mut i32, *mut c_char, i32) -> Code = sys::pm_get_last_file_error;
+    let _: unsafe extern "C" fn() -> *const c_char = sys::pm_version;
+
+    // The hand-written block returns the codes as `i32` and passes the
+    // handle as `*mut c_void`
+    let _: Code = 0i32;
+    assert_eq!(std::mem::size_of::<Handle>(), std::mem::size_of::<*mut c_void>());
+}
@@ -0,0 +1,51 @@
+//! Tests for capture metadata that do not require sensor hardware
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use xlnpwmon::CaptureMetadata;
+
+/// Test that metadata is gathered from the running system
+#[test]
+fn test_collect_metadata() {
+    println!("\n=== Running test_collect_metadata ===");
+    let before = SystemTime::now();
+    let metadata = CaptureMetadata::collect(Some(500));
+    println!("{:?}", metadata);
+
+    assert!(metadata.start_time >= before);
+    assert_eq!(metadata.sampling_frequency_hz, Some(500));
+    if cfg!(target_os = "linux") {
+        assert!(metadata.hostname.as_deref().map_or(false, |h| !h.is_empty()));
+        assert!(metadata.kernel_version.as_deref().map_or(false, |k| !k.is_empty()));
+    }
+}
+
+/// Test JSON serialization, including unknown fields and escaping
+#[test]
+fn test_metadata_json() {
+    println!("\n=== Running test_metadata_json ===");
+    let metadata = CaptureMetadata {
+        hostname: Some("zcu102-\"lab\"".to_string()),
+        kernel_version: Some("6.1.0-xilinx".to_string()),
+        board_model: None,
+        start_time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        sampling_frequency_hz: Some(1000),
+    };
+    assert_eq!(
+        metadata.to_json(),
+        "{\"hostname\":\"zcu102-\\\"lab\\\"\",\"kernel_version\":\"6.1.0-xilinx\",\
+         \"board_model\":null,\"start_time_unix_ms\":1700000000123,\"sampling_frequency_hz\":1000}"
+    );
+
+    let unknown = CaptureMetadata {
+        hostname: None,
+        kernel_version: None,
+        board_model: None,
+        start_time: UNIX_EPOCH,
+        sampling_frequency_hz: None,
+    };
+    assert_eq!(
+        unknown.to_json(),
+        "{\"hostname\":null,\"kernel_version\":null,\"board_model\":null,\
+         \"start_time_unix_ms\":0,\"sampling_frequency_hz\":null}"
+    );
+}
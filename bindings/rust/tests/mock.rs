@@ -0,0 +1,862 @@
+//! Tests against the simulated sensors of the `mock` feature
+//!
+//! The mock backend reports three rails with constant readings, so every
+//! statistic of a capture is known exactly:
+//!
+//! | Sensor     | Rail | Voltage | Current | Power   |
+//! |------------|------|---------|---------|---------|
+//! | VCCPSINTFP | PS   | 0.75 V  | 2.0 A   | 1.5 W   |
+//! | VCCINT     | PL   | 1.0 V   | 4.0 A   | 4.0 W   |
+//! | VCC3V3     | PL   | 3.25 V  | 0.5 A   | 1.625 W |
+#![cfg(feature = "mock")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use xlnpwmon::{
+    Baseline, Error, PowerMonitor, ReplayMonitor, ReplayPace, SamplingState, SensorType, SpikeEvent,
+    DEFAULT_FREQUENCY_HZ, MAX_FREQUENCY_HZ, MIN_FREQUENCY_HZ, MIN_SPIKE_SAMPLES,
+};
+
+const SENSOR_NAMES: [&str; 6] = [
+    "VCCPSINTFP",
+    "VCCINT",
+    "VCC3V3",
+    "PS_TOTAL_POWER",
+    "PL_TOTAL_POWER",
+    "TOTAL_POWER",
+];
+const SENSOR_POWER: [f64; 6] = [1.5, 4.0, 1.625, 1.5, 5.625, 7.125];
+
+/// Test that the synthetic sensors are discovered
+#[test]
+fn test_mock_sensors() {
+    println!("\n=== Running test_mock_sensors ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+    assert_eq!(monitor.sensor_names().unwrap(), SENSOR_NAMES);
+}
+
+/// Test a one-shot read of the fixed values
+#[test]
+fn test_mock_read_once() {
+    println!("\n=== Running test_mock_read_once ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let data = monitor.read_once().unwrap();
+    let powers: Vec<f64> = data.sensors.iter().map(|s| s.power).collect();
+    assert_eq!(powers, SENSOR_POWER);
+    assert_eq!(data.total.power, 7.125);
+    assert_eq!(data.total.current, 6.5);
+    assert_eq!(data.sensors[1].voltage, 1.0);
+    assert!(data.sensors.iter().all(|s| s.online && s.status() == "OK"));
+}
+
+/// Test start, stop and the resulting statistics
+#[test]
+fn test_mock_statistics() {
+    println!("\n=== Running test_mock_statistics ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(matches!(monitor.stop_sampling(), Err(Error::NotRunning)));
+
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    assert!(matches!(monitor.start_sampling(), Err(Error::AlreadyRunning)));
+    thread::sleep(Duration::from_millis(200));
+    monitor.stop_sampling().unwrap();
+
+    let stats = monitor.get_statistics().unwrap();
+    let count = stats.total.power.count;
+    println!("Samples: {}", count);
+    assert!(count > 0);
+    assert_eq!(stats.total.power.min, 7.125);
+    assert_eq!(stats.total.power.max, 7.125);
+    assert_eq!(stats.total.power.avg, 7.125);
+    assert_eq!(stats.total.power.total, 7.125 * count as f64);
+    for (sensor, power) in stats.sensors().iter().zip(SENSOR_POWER) {
+        assert_eq!(sensor.power.count, count);
+        assert_eq!(sensor.power.min, power);
+        assert_eq!(sensor.power.max, power);
+        assert_eq!(sensor.power.avg, power);
+    }
+
+    let summary = monitor.get_power_summary().unwrap();
+    assert_eq!(summary.ps_total_power, 1.5);
+    assert_eq!(summary.pl_total_power, 5.625);
+    assert_eq!(summary.total_power, 7.125);
+
+    monitor.reset_statistics().unwrap();
+    assert_eq!(monitor.get_statistics().unwrap().total.power.count, 0);
+}
+
+/// Test capturing an idle baseline and subtracting it from a capture
+#[test]
+fn test_mock_baseline() {
+    println!("\n=== Running test_mock_baseline ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    let baseline = monitor.capture_baseline(Duration::from_millis(100)).unwrap();
+    assert_eq!(baseline.total_power, 7.125);
+    for (name, power) in SENSOR_NAMES.iter().zip(SENSOR_POWER) {
+        assert_eq!(baseline.power(name), Some(power));
+    }
+
+    // The mock readings are constant, so nothing is left above idle
+    let stats = monitor.sample_for(Duration::from_millis(100)).unwrap();
+    let dynamic = stats.minus_baseline(&baseline);
+    assert_eq!(dynamic.total.power.avg, 0.0);
+    assert_eq!(dynamic.total.power.total, 0.0);
+    assert_eq!(dynamic.total.power.count, stats.total.power.count);
+    assert!(dynamic.sensors.iter().all(|sensor| sensor.power.max == 0.0));
+    assert_eq!(dynamic.sensors[1].voltage.avg, stats.sensors[1].voltage.avg);
+
+    // A lower baseline leaves the difference; a higher one clamps to zero
+    let partial = Baseline {
+        total_power: 5.0,
+        sensors: [("VCCINT".to_string(), 3.0), ("VCC3V3".to_string(), 2.0)]
+            .into_iter()
+            .collect(),
+    };
+    let dynamic = stats.minus_baseline(&partial);
+    assert_eq!(dynamic.total.power.avg, 2.125);
+    assert_eq!(dynamic.total.power.total, 2.125 * stats.total.power.count as f64);
+    assert_eq!(dynamic.sensors[0].power.avg, 1.5);
+    assert_eq!(dynamic.sensors[1].power.avg, 1.0);
+    assert_eq!(dynamic.sensors[2].power.avg, 0.0);
+}
+
+/// Test recording samples to a CSV file and parsing it back
+#[test]
+fn test_mock_record_csv() {
+    println!("\n=== Running test_mock_record_csv ===");
+    let path = std::env::temp_dir().join(format!("xlnpwmon-record-{}.csv", std::process::id()));
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let recorder = monitor.record_csv(&path).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    monitor.stop_sampling().unwrap();
+    recorder.finish().unwrap();
+    let samples = monitor.get_statistics().unwrap().total.power.count as usize;
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("timestamp,sensor,voltage,current,power"));
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    println!("Samples: {}, rows: {}", samples, rows.len());
+    assert!(samples > 0);
+    assert_eq!(rows.len(), samples * SENSOR_NAMES.len());
+
+    let mut last_timestamp = 0.0;
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row.len(), 5);
+        let timestamp: f64 = row[0].parse().unwrap();
+        assert!(timestamp >= last_timestamp);
+        last_timestamp = timestamp;
+        assert_eq!(row[1], SENSOR_NAMES[i % SENSOR_NAMES.len()]);
+        assert_eq!(row[4].parse::<f64>().unwrap(), SENSOR_POWER[i % SENSOR_NAMES.len()]);
+    }
+
+    // A file that cannot be created is reported up front
+    assert!(matches!(monitor.record_csv("/nonexistent/dir/x.csv"), Err(Error::FileAccess)));
+}
+
+/// Test that every sample is reported as gauges
+#[cfg(feature = "metrics")]
+#[test]
+fn test_mock_emit_metrics() {
+    println!("\n=== Running test_mock_emit_metrics ===");
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use xlnpwmon::{METRIC_CURRENT, METRIC_POWER, METRIC_VOLTAGE};
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    // The gauges are set on the sampling thread, so the recorder is global
+    recorder.install().unwrap();
+
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.emit_metrics();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    monitor.stop_sampling().unwrap();
+
+    let gauges: Vec<(String, String, f64)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let key = key.key();
+            let sensor = key.labels().find(|label| label.key() == "sensor").unwrap();
+            let value = match value {
+                DebugValue::Gauge(value) => value.into_inner(),
+                other => panic!("not a gauge: {:?}", other),
+            };
+            (key.name().to_string(), sensor.value().to_string(), value)
+        })
+        .collect();
+    assert_eq!(gauges.len(), SENSOR_NAMES.len() * 3);
+    for (name, power) in SENSOR_NAMES.iter().zip(SENSOR_POWER) {
+        let gauge = |metric: &str| {
+            gauges
+                .iter()
+                .find(|(n, sensor, _)| n == metric && sensor == name)
+                .map(|(_, _, value)| *value)
+                .unwrap()
+        };
+        assert_eq!(gauge(METRIC_POWER), power);
+        assert!(gauge(METRIC_VOLTAGE) > 0.0);
+        assert!(gauge(METRIC_CURRENT) > 0.0);
+    }
+}
+
+/// Collects the message and fields of every `tracing` event
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct EventLog(std::sync::Mutex<Vec<String>>);
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for EventLog {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+    fn event(&self, event: &tracing::Event<'_>) {
+        struct Fields(String);
+        impl tracing::field::Visit for Fields {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                self.0.push_str(&format!(" {}={:?}", field.name(), value));
+            }
+        }
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+        self.0.lock().unwrap().push(fields.0.trim_start().to_string());
+    }
+    fn enter(&self, _: &tracing::span::Id) {}
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+/// Test that the sampling lifecycle and failed FFI calls emit events
+#[cfg(feature = "tracing")]
+#[test]
+fn test_mock_tracing_events() {
+    println!("\n=== Running test_mock_tracing_events ===");
+    let log = Arc::new(EventLog::default());
+    tracing::subscriber::with_default(Arc::clone(&log), || {
+        let monitor = PowerMonitor::new().unwrap();
+        monitor.set_sampling_frequency(100).unwrap();
+        monitor.start_sampling().unwrap();
+        assert!(matches!(monitor.start_sampling(), Err(Error::AlreadyRunning)));
+        monitor.stop_sampling().unwrap();
+        // Dropping a stopped monitor tolerates the failed stop
+        drop(monitor);
+    });
+
+    let events = log.0.lock().unwrap();
+    let expected = [
+        "message=power monitor initialized sensor_count=6 frequency_hz=10",
+        "message=sampling frequency set frequency_hz=100",
+        "message=sampling started sensor_count=6 frequency_hz=100 timed=false",
+        "message=FFI call failed call=\"pm_start_sampling\" code=-3 error=Sampling already running",
+        "message=sampling stopped sensor_count=6",
+        "message=FFI call failed call=\"pm_stop_sampling\" code=-4 error=Sampling not running",
+    ];
+    assert_eq!(*events, expected);
+}
+
+/// Test the energy of a capture of known length at constant power
+#[test]
+fn test_mock_energy_joules() {
+    println!("\n=== Running test_mock_energy_joules ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    let stats = monitor.sample_for(Duration::from_millis(500)).unwrap();
+    let energy = stats.energy_joules(100);
+    println!("Samples: {}, energy: {:.3} J", stats.total.power.count, energy);
+    // 7.125 W for 0.5 s, give or take a few sampling periods
+    assert!((energy - 7.125 * 0.5).abs() <= 7.125 * 0.05);
+}
+
+/// Test that dropping a sampling monitor stops its thread and that a fresh
+/// monitor can be initialized afterwards
+#[test]
+fn test_mock_drop_while_sampling() {
+    println!("\n=== Running test_mock_drop_while_sampling ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    drop(monitor);
+
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert!(monitor.is_sampling().unwrap());
+    assert!(monitor.close().is_ok());
+
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(monitor.close().is_ok());
+}
+
+/// Test that the mock board is enumerated and can be selected by id
+#[test]
+fn test_mock_devices() {
+    println!("\n=== Running test_mock_devices ===");
+    let devices = xlnpwmon::enumerate_devices().unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].id, "mock");
+    assert_eq!(devices[0].sensor_count, 3);
+
+    let monitor = PowerMonitor::with_device(&devices[0].id).unwrap();
+    assert_eq!(monitor.sensor_names().unwrap(), SENSOR_NAMES);
+    assert!(matches!(PowerMonitor::with_device("i2c-99"), Err(Error::NoSensors)));
+}
+
+/// Test that a recorded trace replays to the statistics of the live capture
+#[test]
+fn test_mock_record_replay() {
+    println!("\n=== Running test_mock_record_replay ===");
+    let path = std::env::temp_dir().join(format!("xlnpwmon-trace-{}.bin", std::process::id()));
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let before = std::time::SystemTime::now();
+    let recorder = monitor.record_to(&path).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    monitor.stop_sampling().unwrap();
+    recorder.finish().unwrap();
+    let live = monitor.get_statistics_owned().unwrap();
+
+    let mut replay = ReplayMonitor::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replay.sensor_names(), SENSOR_NAMES);
+    assert!(matches!(replay.get_power_summary(), Err(Error::NotRunning)));
+
+    // The metadata of the recording system is read back
+    let metadata = replay.metadata().unwrap();
+    let system = xlnpwmon::CaptureMetadata::collect(None);
+    assert_eq!(metadata.hostname, system.hostname);
+    assert_eq!(metadata.kernel_version, system.kernel_version);
+    assert_eq!(metadata.board_model, system.board_model);
+    assert_eq!(metadata.sampling_frequency_hz, Some(100));
+    assert!(metadata.start_time >= before && metadata.start_time <= system.start_time);
+    println!("Samples: {}", replay.samples().len());
+    assert_eq!(replay.samples().len() as u64, live.total.power.count);
+
+    replay.play(ReplayPace::AsFastAsPossible);
+    assert_eq!(replay.get_statistics_owned().unwrap(), live);
+    let latest = replay.get_latest_data().unwrap();
+    let powers: Vec<f64> = latest.sensors.iter().map(|s| s.power).collect();
+    assert_eq!(powers, SENSOR_POWER);
+    assert_eq!(replay.get_power_summary().unwrap().total_power, 7.125);
+
+    // Replaying at the original cadence takes about as long as the capture
+    replay.rewind();
+    let samples = replay.samples();
+    let span = samples[samples.len() - 1].total.timestamp_ns - samples[0].total.timestamp_ns;
+    let started = std::time::Instant::now();
+    replay.play(ReplayPace::Original);
+    assert!(started.elapsed() >= Duration::from_nanos(span));
+    assert_eq!(replay.get_statistics_owned().unwrap(), live);
+
+    assert!(matches!(ReplayMonitor::open(file!()), Err(Error::InvalidArgument)));
+}
+
+/// Test that the decoded sensors match the raw readings
+#[test]
+fn test_mock_latest_sensors() {
+    println!("\n=== Running test_mock_latest_sensors ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    monitor.stop_sampling().unwrap();
+    let sensors = monitor.latest_sensors().unwrap();
+    let names: Vec<&str> = sensors.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, SENSOR_NAMES);
+    let powers: Vec<f64> = sensors.iter().map(|s| s.power).collect();
+    assert_eq!(powers, SENSOR_POWER);
+    assert!(sensors.iter().all(|s| s.online && s.status == "OK"));
+    assert_eq!(sensors[0].kind, SensorType::I2C);
+    assert_eq!(sensors[5].kind, SensorType::System);
+}
+
+/// Test that the sampling thread reports healthy shortly after start
+#[test]
+fn test_mock_sampling_health() {
+    println!("\n=== Running test_mock_sampling_health ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(!monitor.sampling_health().unwrap().thread_alive);
+
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    let health = monitor.sampling_health().unwrap();
+    println!("Health: {:?}", health);
+    assert!(health.thread_alive);
+    assert!(!health.is_stale(Duration::from_millis(500)));
+
+    monitor.stop_sampling().unwrap();
+    let health = monitor.sampling_health().unwrap();
+    assert!(!health.thread_alive);
+    assert!(health.is_stale(Duration::from_secs(60)));
+}
+
+/// Test that the sampling period can be set as a duration
+#[test]
+fn test_mock_sampling_interval() {
+    println!("\n=== Running test_mock_sampling_interval ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_interval(Duration::from_millis(2)).unwrap();
+    assert_eq!(monitor.get_sampling_interval().unwrap(), Duration::from_millis(2));
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 500);
+
+    monitor.set_sampling_interval(Duration::from_micros(1500)).unwrap();
+    assert_eq!(monitor.get_sampling_interval().unwrap(), Duration::from_micros(1500));
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 667);
+
+    monitor.set_sampling_frequency(3).unwrap();
+    let interval = monitor.get_sampling_interval().unwrap();
+    assert!((interval.as_secs_f64() - 1.0 / 3.0).abs() < 1e-6);
+
+    assert!(matches!(monitor.set_sampling_interval(Duration::ZERO), Err(Error::InvalidFrequency)));
+}
+
+/// Test that frequencies outside the reported limits are rejected
+#[test]
+fn test_mock_frequency_limits() {
+    println!("\n=== Running test_mock_frequency_limits ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let (min, max) = monitor.frequency_limits().unwrap();
+    println!("Limits: {} Hz to {} Hz", min, max);
+    assert!(min >= 1 && min <= max);
+
+    monitor.set_sampling_frequency(max).unwrap();
+    monitor.set_sampling_frequency(min).unwrap();
+    assert!(matches!(monitor.set_sampling_frequency(max + 1), Err(Error::InvalidFrequency)));
+    assert!(matches!(monitor.set_sampling_frequency(min - 1), Err(Error::InvalidFrequency)));
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), min);
+
+    let shortest = Duration::from_nanos(1_000_000_000 / max as u64);
+    monitor.set_sampling_interval(shortest).unwrap();
+    assert!(matches!(
+        monitor.set_sampling_interval(shortest - Duration::from_nanos(1)),
+        Err(Error::InvalidFrequency)
+    ));
+}
+
+/// Test that a new monitor samples at the default frequency, and that the
+/// frequency constants match the limits of the library
+#[test]
+fn test_mock_default_frequency() {
+    println!("\n=== Running test_mock_default_frequency ===");
+    assert!((MIN_FREQUENCY_HZ..=MAX_FREQUENCY_HZ).contains(&DEFAULT_FREQUENCY_HZ));
+    let monitor = PowerMonitor::new().unwrap();
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), DEFAULT_FREQUENCY_HZ);
+    assert_eq!(monitor.frequency_limits().unwrap(), (MIN_FREQUENCY_HZ, MAX_FREQUENCY_HZ));
+
+    for frequency_hz in [0, MIN_FREQUENCY_HZ - 1, MAX_FREQUENCY_HZ + 1, i32::MIN, i32::MAX] {
+        assert!(matches!(monitor.set_sampling_frequency(frequency_hz), Err(Error::InvalidFrequency)));
+        assert!(matches!(
+            monitor.set_sampling_frequency_swap(frequency_hz),
+            Err(Error::InvalidFrequency)
+        ));
+    }
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), DEFAULT_FREQUENCY_HZ);
+}
+
+/// Test that swapping the sampling frequency returns the one set before,
+/// and leaves it unchanged when the new one is rejected
+#[test]
+fn test_mock_set_sampling_frequency_swap() {
+    println!("\n=== Running test_mock_set_sampling_frequency_swap ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+    assert_eq!(monitor.set_sampling_frequency_swap(100).unwrap(), 10);
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 100);
+    assert_eq!(monitor.set_sampling_frequency_swap(10).unwrap(), 100);
+
+    let (_, max) = monitor.frequency_limits().unwrap();
+    assert!(matches!(monitor.set_sampling_frequency_swap(0), Err(Error::InvalidFrequency)));
+    assert!(matches!(monitor.set_sampling_frequency_swap(-5), Err(Error::InvalidFrequency)));
+    assert!(matches!(monitor.set_sampling_frequency_swap(max + 1), Err(Error::InvalidFrequency)));
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 10);
+}
+
+/// Test that the cached sensor count matches the sensors the library
+/// reports, across start/stop cycles
+#[test]
+fn test_mock_sensor_count_cached() {
+    println!("\n=== Running test_mock_sensor_count_cached ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    for _ in 0..3 {
+        assert_eq!(monitor.get_sensor_count().unwrap(), SENSOR_NAMES.len() as i32);
+        assert_eq!(monitor.sensor_names().unwrap().len(), SENSOR_NAMES.len());
+        monitor.start_sampling().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(monitor.get_sensor_count().unwrap(), monitor.get_latest_data().unwrap().sensor_count);
+        monitor.stop_sampling().unwrap();
+    }
+}
+
+/// Test that sensor names with a NUL byte are rejected as invalid rather
+/// than reported as unknown sensors
+#[test]
+fn test_mock_nul_sensor_names() {
+    println!("\n=== Running test_mock_nul_sensor_names ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let name = "VCC\0INT";
+    assert!(matches!(monitor.get_statistics_for_sensor(name), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.reset_statistics_for_sensor(name), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.set_sensor_thresholds(name, 1.0, 2.0), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.mock_add_power_samples(name, 1.0, 1), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.mock_set_sensor_online(name, false), Err(Error::InvalidArgument)));
+    assert!(matches!(PowerMonitor::with_device(name), Err(Error::InvalidArgument)));
+}
+
+/// Test that the total of a long capture of small readings does not drift:
+/// ten million samples of 0.1 W, hours at 1 kHz, sum to 10^6 W exactly
+/// to within rounding, where a plain sum would be off by about 2e-4
+#[test]
+fn test_mock_compensated_total() {
+    println!("\n=== Running test_mock_compensated_total ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.mock_add_power_samples("VCCINT", 0.1, 10_000_000).unwrap();
+    assert!(matches!(
+        monitor.mock_add_power_samples("VCCBOGUS", 0.1, 1),
+        Err(Error::NoSensors)
+    ));
+
+    let stats = monitor.get_statistics_owned().unwrap();
+    let power = stats.sensors.iter().find(|s| s.name() == "VCCINT").unwrap().power;
+    println!("Total: {:.12}, average: {:.17}", power.total, power.avg);
+    assert_eq!(power.count, 10_000_000);
+    assert!((power.total - 1e6).abs() <= 1e-9, "total drifted to {}", power.total);
+    assert!((power.avg - 0.1).abs() <= 1e-15, "average drifted to {}", power.avg);
+    assert_eq!(power.min, 0.1);
+    assert_eq!(power.max, 0.1);
+}
+
+/// Test that reinitializing in the middle of a capture, as after a device
+/// disconnect, rediscovers the sensors and keeps the sampling interval
+#[test]
+fn test_mock_reinitialize() {
+    println!("\n=== Running test_mock_reinitialize ===");
+    let mut monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(250).unwrap();
+    monitor.set_history_capacity(16).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    monitor.reinitialize().unwrap();
+    assert!(!monitor.is_sampling().unwrap());
+    assert!(monitor.get_sensor_count().unwrap() > 0);
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 250);
+    assert_eq!(monitor.history_capacity(), 16);
+
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    monitor.stop_sampling().unwrap();
+    assert_eq!(monitor.get_latest_data().unwrap().total.power, 7.125);
+    assert!(!monitor.get_history().unwrap().is_empty());
+}
+
+/// Test that a spike injected into a mock capture is the only one flagged,
+/// and that the constant mock power and short histories have none
+#[test]
+fn test_mock_detect_spikes() {
+    println!("\n=== Running test_mock_detect_spikes ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.set_history_capacity(32).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(500));
+    monitor.stop_sampling().unwrap();
+
+    assert!(monitor.detect_spikes(3.0).unwrap().is_empty());
+    assert!(matches!(monitor.detect_spikes(-1.0), Err(Error::InvalidArgument)));
+
+    let mut history = monitor.get_history().unwrap();
+    assert_eq!(history.len(), 32);
+    history[20].total.power = 20.0;
+    let spikes = SpikeEvent::detect(&history, 3.0).unwrap();
+    println!("{:?}", spikes);
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].index, 20);
+    let offset_ns = history[20].total.timestamp_ns - history[0].total.timestamp_ns;
+    assert_eq!(spikes[0].offset, Duration::from_nanos(offset_ns));
+    assert!(spikes[0].offset > Duration::from_millis(100));
+    assert_eq!(spikes[0].power, 20.0);
+    assert!(spikes[0].sigma > 3.0);
+
+    let short = &history[20 - MIN_SPIKE_SAMPLES + 2..=20];
+    assert!(SpikeEvent::detect(short, 0.0).unwrap().is_empty());
+}
+
+/// Test that the constant mock readings have no spread
+#[test]
+fn test_mock_stats_stddev() {
+    println!("\n=== Running test_mock_stats_stddev ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    monitor.stop_sampling().unwrap();
+
+    let stats = monitor.get_statistics_owned().unwrap();
+    assert!(stats.total.power.count > 1);
+    for sensor in stats.sensors.iter().chain(std::iter::once(&stats.total)) {
+        for metric in [&sensor.voltage, &sensor.current, &sensor.power] {
+            assert_eq!(metric.sum_sq_dev, 0.0);
+            assert_eq!(metric.stddev, 0.0);
+        }
+    }
+}
+
+/// Test the standard deviation kept by the C library for a known series
+/// of varying readings
+#[test]
+fn test_mock_stats_stddev_varying() {
+    println!("\n=== Running test_mock_stats_stddev_varying ===");
+    let monitor = PowerMonitor::new().unwrap();
+    for power in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+        monitor.mock_add_power_samples("VCCINT", power, 1).unwrap();
+    }
+
+    let stats = monitor.get_statistics_owned().unwrap();
+    let power = stats.sensors.iter().find(|s| s.name() == "VCCINT").unwrap().power;
+    println!("{:?}", power);
+    assert_eq!(power.count, 8);
+    assert_eq!(power.avg, 5.0);
+    assert!((power.sum_sq_dev - 32.0).abs() < 1e-12);
+    assert!((power.stddev - 2.0).abs() < 1e-12);
+}
+
+/// Test that the sampling thread can be pinned to CPU 0 before and while
+/// sampling, and that invalid settings are rejected
+#[test]
+fn test_mock_sampling_thread_affinity() {
+    println!("\n=== Running test_mock_sampling_thread_affinity ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.set_sampling_thread_affinity(&[0]).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert!(monitor.sampling_health().unwrap().thread_alive);
+
+    monitor.set_sampling_thread_affinity(&[]).unwrap();
+    monitor.set_sampling_thread_affinity(&[0]).unwrap();
+    monitor.set_sampling_thread_priority(0).unwrap();
+    assert!(matches!(monitor.set_sampling_thread_affinity(&[1 << 20]), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.set_sampling_thread_priority(-1), Err(Error::InvalidArgument)));
+    assert!(matches!(monitor.set_sampling_thread_priority(1000), Err(Error::InvalidArgument)));
+    monitor.stop_sampling().unwrap();
+}
+
+/// Test that a sensor going offline and coming back is reported once each
+/// way, together with the virtual sensor it feeds
+#[test]
+fn test_mock_on_status_change() {
+    println!("\n=== Running test_mock_on_status_change ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    monitor.on_status_change(move |sensor| {
+        let _ = tx.send((sensor.name().into_owned(), sensor.online));
+    });
+
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+    monitor.mock_set_sensor_online("VCCPSINTFP", false).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    monitor.mock_set_sensor_online("VCCPSINTFP", true).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    monitor.stop_sampling().unwrap();
+
+    let events: Vec<_> = rx.try_iter().collect();
+    println!("Status events: {:?}", events);
+    assert_eq!(
+        events,
+        [
+            ("VCCPSINTFP".to_string(), false),
+            ("PS_TOTAL_POWER".to_string(), false),
+            ("VCCPSINTFP".to_string(), true),
+            ("PS_TOTAL_POWER".to_string(), true),
+        ]
+    );
+    assert!(matches!(monitor.mock_set_sensor_online("TOTAL_POWER", false), Err(Error::NoSensors)));
+}
+
+/// Test that polling into the same buffer matches `get_latest_owned` and
+/// does not reallocate while the sensor count stays the same
+#[test]
+fn test_mock_get_latest_data_into() {
+    println!("\n=== Running test_mock_get_latest_data_into ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let mut buf = monitor.get_latest_owned().unwrap();
+    buf.sensors.clear();
+    monitor.get_latest_data_into(&mut buf).unwrap();
+    assert_eq!(buf.sensors.len(), 6);
+    let (ptr, capacity) = (buf.sensors.as_ptr(), buf.sensors.capacity());
+
+    const CALLS: u32 = 10_000;
+    let start = std::time::Instant::now();
+    for _ in 0..CALLS {
+        monitor.get_latest_data_into(&mut buf).unwrap();
+        assert_eq!(buf.sensors.as_ptr(), ptr);
+        assert_eq!(buf.sensors.capacity(), capacity);
+    }
+    println!("{:?} per call", start.elapsed() / CALLS);
+    monitor.stop_sampling().unwrap();
+
+    let latest = monitor.get_latest_owned().unwrap();
+    monitor.get_latest_data_into(&mut buf).unwrap();
+    assert_eq!(buf, latest);
+}
+
+/// Test that the power cap trips once when the synthetic total power stays
+/// above it, and not at all when it stays below
+#[test]
+fn test_mock_power_cap() {
+    println!("\n=== Running test_mock_power_cap ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let trips = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&trips);
+    monitor
+        .set_power_cap(5.0, Duration::from_millis(50), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    let spurious = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&spurious);
+    monitor
+        .set_power_cap(10.0, Duration::ZERO, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    assert!(matches!(
+        monitor.set_power_cap(f64::NAN, Duration::ZERO, || {}),
+        Err(Error::InvalidArgument)
+    ));
+
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    monitor.stop_sampling().unwrap();
+    assert_eq!(trips.load(Ordering::SeqCst), 1);
+    assert_eq!(spurious.load(Ordering::SeqCst), 0);
+}
+
+/// Test that every subscriber, including a clone, receives every sample
+#[test]
+fn test_mock_subscribe() {
+    println!("\n=== Running test_mock_subscribe ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    let logger = monitor.subscribe();
+    let dashboard = monitor.subscribe();
+    let watchdog = dashboard.clone();
+
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    monitor.stop_sampling().unwrap();
+
+    let drain = |receiver: &xlnpwmon::SampleReceiver| {
+        let mut count = 0;
+        while let Ok(data) = receiver.try_recv() {
+            assert_eq!(data.sensors.len(), SENSOR_NAMES.len());
+            count += 1;
+        }
+        count
+    };
+    let count = drain(&logger);
+    assert!(count > 0);
+    assert_eq!(drain(&dashboard), count);
+    assert_eq!(drain(&watchdog), count);
+    assert_eq!(logger.dropped() + dashboard.dropped() + watchdog.dropped(), 0);
+
+    drop(monitor);
+    assert!(logger.recv().is_err());
+    assert!(watchdog.clone().recv().is_err());
+}
+
+/// Test that no latest data is reported before the first sample of a run,
+/// and the synthetic readings afterwards
+#[test]
+fn test_mock_try_get_latest_data() {
+    println!("\n=== Running test_mock_try_get_latest_data ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    assert!(monitor.try_get_latest_data().unwrap().is_none());
+
+    monitor.start_sampling().unwrap();
+    let mut polls = 0;
+    let data = loop {
+        if let Some(data) = monitor.try_get_latest_data().unwrap() {
+            break data;
+        }
+        polls += 1;
+        assert!(polls < 1000, "no sample published");
+        thread::sleep(Duration::from_millis(1));
+    };
+    println!("first sample after {} empty polls", polls);
+    assert_eq!(data.total.power, 7.125);
+    assert_eq!(data.sensors.len(), SENSOR_NAMES.len());
+
+    // The last sample of a run stays available once sampling stops
+    monitor.stop_sampling().unwrap();
+    assert!(monitor.try_get_latest_data().unwrap().is_some());
+}
+
+/// Test that a paused monitor takes no samples but keeps its statistics,
+/// and continues counting from there once resumed
+#[test]
+fn test_mock_pause_resume() {
+    println!("\n=== Running test_mock_pause_resume ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(200).unwrap();
+    assert_eq!(monitor.sampling_state().unwrap(), SamplingState::Stopped);
+    assert!(matches!(monitor.pause(), Err(Error::NotRunning)));
+
+    monitor.start_sampling().unwrap();
+    monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    monitor.pause().unwrap();
+    assert_eq!(monitor.sampling_state().unwrap(), SamplingState::Paused);
+    assert!(monitor.is_sampling().unwrap());
+
+    let count = |monitor: &PowerMonitor| monitor.get_statistics_owned().unwrap().total.power.count;
+    let paused_count = count(&monitor);
+    println!("Samples when paused: {}", paused_count);
+    assert!(paused_count >= 2);
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(count(&monitor), paused_count);
+
+    monitor.resume().unwrap();
+    assert_eq!(monitor.sampling_state().unwrap(), SamplingState::Running);
+    monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let resumed_count = count(&monitor);
+    println!("Samples after resuming: {}", resumed_count);
+    assert!(resumed_count > paused_count);
+
+    // Stopping a paused monitor works as well
+    monitor.pause().unwrap();
+    monitor.stop_sampling().unwrap();
+    assert_eq!(monitor.sampling_state().unwrap(), SamplingState::Stopped);
+    assert!(matches!(monitor.resume(), Err(Error::NotRunning)));
+}
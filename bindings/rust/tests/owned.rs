@@ -0,0 +1,421 @@
+//! Tests for the owned data types that do not require sensor hardware
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use xlnpwmon::{
+    CaptureMetadata, OwnedPowerData, OwnedPowerStats, SensorData, SensorDelta, SensorStats, SensorType, Stats,
+};
+
+/// Builds a synthetic sensor reading
+fn sensor(name: &str, voltage: f64, current: f64, power: f64) -> SensorData {
+    SensorData {
+        voltage,
+        current,
+        power,
+        online: true,
+        ..SensorData::new(name)
+    }
+}
+
+/// Builds synthetic statistics with a constant value
+fn stats(value: f64, count: u64) -> Stats {
+    Stats {
+        min: value,
+        max: value,
+        avg: value,
+        total: value * count as f64,
+        count,
+        sum_sq_dev: 0.0,
+        stddev: 0.0,
+    }
+}
+
+/// Test quantization of owned power data
+#[test]
+fn test_quantized_data() {
+    let data = OwnedPowerData {
+        total: sensor("Total", 0.85149, 3.20567, 2.67509),
+        sensors: vec![sensor("VCCINT", 0.85149, 3.20567, 2.67509)],
+    };
+
+    let quantized = data.quantized(2);
+    assert_eq!(quantized.total.voltage, 0.85);
+    assert_eq!(quantized.total.current, 3.21);
+    assert_eq!(quantized.sensors[0].power, 2.68);
+
+    // The source data is left untouched
+    assert_eq!(data.sensors[0].power, 2.67509);
+}
+
+/// Test quantization of owned statistics
+#[test]
+fn test_quantized_stats() {
+    let total = SensorStats {
+        power: stats(1.23456, 7),
+        ..SensorStats::default()
+    };
+    let stats = OwnedPowerStats {
+        total,
+        sensors: vec![total],
+    };
+
+    let quantized = stats.quantized(1);
+    assert_eq!(quantized.total.power.avg, 1.2);
+    assert_eq!(quantized.sensors[0].power.min, 1.2);
+    assert_eq!(quantized.total.power.count, 7);
+}
+
+/// Test the Markdown sensor table
+#[test]
+fn test_markdown_table() {
+    let data = OwnedPowerData {
+        total: sensor("Total", 0.85, 3.0, 2.55),
+        sensors: vec![sensor("VCCINT", 0.85, 2.0, 1.7), sensor("VCC|AUX", 1.8, 1.0, 0.85)],
+    };
+
+    let table = data.to_markdown_table();
+    let lines: Vec<&str> = table.lines().collect();
+    println!("{}", table);
+
+    assert_eq!(lines.len(), 2 + data.sensors.len() + 1);
+    assert!(lines[0].starts_with("| Name | Type | Voltage (V) | Current (A) | Power (W) | Online | Status |"));
+    assert!(lines[1].chars().all(|c| matches!(c, '|' | ':' | '-')));
+    assert!(lines[2].contains("VCCINT"));
+    assert!(lines[3].contains("VCC\\|AUX"));
+    assert!(lines.last().unwrap().contains("**Total**"));
+
+    // Every row has the same number of (unescaped) column separators
+    let columns = |line: &str| line.replace("\\|", "").matches('|').count();
+    for line in &lines {
+        assert_eq!(columns(line), 8, "bad column count in {:?}", line);
+    }
+}
+
+/// Test headroom to configured critical thresholds
+#[test]
+fn test_headroom() {
+    println!("\n=== Running test_headroom ===");
+    let with_threshold = |name: &str, power: f64, critical: f64| {
+        let mut sensor = sensor(name, 1.0, power, power);
+        sensor.critical_threshold = critical;
+        sensor
+    };
+    let data = OwnedPowerData {
+        total: sensor("TOTAL_POWER", 0.0, 0.0, 9.0),
+        sensors: vec![
+            with_threshold("VCCINT", 4.0, 6.0),
+            with_threshold("VCCAUX", 1.0, 0.0),
+            with_threshold("VCCBRAM", 2.5, 3.0),
+            with_threshold("MGTAVCC", 1.5, 1.0),
+        ],
+    };
+
+    let headroom = data.headroom();
+    let names: Vec<&str> = headroom.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["VCCINT", "VCCBRAM", "MGTAVCC"]);
+    assert!((headroom[0].1 - 2.0).abs() < 1e-9);
+    assert!((headroom[1].1 - 0.5).abs() < 1e-9);
+    assert!((headroom[2].1 + 0.5).abs() < 1e-9);
+
+    let (name, tightest) = data.min_headroom().unwrap();
+    assert_eq!(name, "MGTAVCC");
+    assert!((tightest + 0.5).abs() < 1e-9);
+
+    let unset = OwnedPowerData {
+        total: data.total,
+        sensors: vec![sensor("VCCINT", 1.0, 1.0, 1.0)],
+    };
+    assert!(unset.headroom().is_empty());
+    assert!(unset.min_headroom().is_none());
+}
+
+/// Test ranking sensors by power
+#[test]
+fn test_top_consumers() {
+    println!("\n=== Running test_top_consumers ===");
+    let data = OwnedPowerData {
+        total: sensor("TOTAL_POWER", 0.0, 0.0, 8.5),
+        sensors: vec![
+            sensor("VCCAUX", 1.8, 0.5, 0.9),
+            sensor("VCCINT", 0.85, 4.0, 3.4),
+            sensor("VCCBRAM", 0.85, 1.0, 0.85),
+            sensor("MGTAVCC", 0.9, 3.7, 3.35),
+            sensor("PS_TOTAL_POWER", 0.0, 0.0, 0.0),
+            sensor("PL_TOTAL_POWER", 0.0, 0.0, 8.5),
+            sensor("TOTAL_POWER", 0.0, 0.0, 8.5),
+        ],
+    };
+
+    let top = data.top_consumers(2);
+    let names: Vec<&str> = top.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["VCCINT", "MGTAVCC"]);
+    assert_eq!(top[0].1, 3.4);
+
+    // Asking for more than there are returns every physical sensor
+    let all = data.top_consumers(100);
+    assert_eq!(all.len(), 4);
+    assert!(all.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(data.top_consumers(0).is_empty());
+}
+
+/// Test per-sensor shares of the total power
+#[test]
+fn test_contributions() {
+    println!("\n=== Running test_contributions ===");
+    let data = OwnedPowerData {
+        total: sensor("TOTAL_POWER", 0.0, 0.0, 8.0),
+        sensors: vec![
+            sensor("VCCINT", 1.0, 4.0, 4.0),
+            sensor("VCCAUX", 1.0, 1.0, 1.0),
+            sensor("VCCBRAM", 1.0, 3.0, 3.0),
+            sensor("TOTAL_POWER", 0.0, 0.0, 8.0),
+        ],
+    };
+    assert_eq!(data.total_power(), 8.0);
+
+    let shares = data.contributions();
+    let names: Vec<&str> = shares.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["VCCINT", "VCCAUX", "VCCBRAM"]);
+    assert_eq!(shares[0].1, 50.0);
+    assert_eq!(shares[1].1, 12.5);
+    assert_eq!(shares[2].1, 37.5);
+
+    // A zero total gives every sensor a zero share instead of NaN
+    let idle = OwnedPowerData {
+        total: sensor("TOTAL_POWER", 0.0, 0.0, 0.0),
+        sensors: vec![sensor("VCCINT", 0.0, 0.0, 0.0)],
+    };
+    assert_eq!(idle.contributions(), [("VCCINT".to_string(), 0.0)]);
+}
+
+/// Test the run report: a total section, one section per sensor and
+/// energy shares for the physical sensors only
+#[test]
+fn test_stats_report() {
+    println!("\n=== Running test_stats_report ===");
+    let named = |name: &str, power: f64| SensorStats {
+        power: stats(power, 100),
+        ..SensorStats::new(name)
+    };
+    let stats = OwnedPowerStats {
+        total: named("TOTAL_POWER", 4.0),
+        sensors: vec![named("VCCINT", 3.0), named("VCCAUX", 1.0), named("TOTAL_POWER", 4.0)],
+    };
+
+    let report = stats.report(10);
+    println!("{}", report);
+    assert!(report.starts_with(
+        "Total:\n  Power (W): min/avg/max = 4.00 / 4.00 / 4.00\n  Energy: 40.00 J\n  Samples: 100\n"
+    ));
+    assert!(report.contains("\nSensor VCCINT:\n  Power (W): min/avg/max = 3.00 / 3.00 / 3.00\n  Energy: 30.00 J\n"));
+    assert!(report.contains("  Share: 75.0% of total energy\n"));
+    assert!(report.contains("\nSensor VCCAUX:\n"));
+    assert!(report.contains("  Share: 25.0% of total energy\n"));
+    assert!(report.ends_with("\nSensor TOTAL_POWER:\n  Power (W): min/avg/max = 4.00 / 4.00 / 4.00\n  Energy: 40.00 J\n  Samples: 100\n"));
+    assert_eq!(report.matches("Share:").count(), 2);
+}
+
+/// Test the JSON export of statistics
+#[test]
+fn test_stats_to_json() {
+    println!("\n=== Running test_stats_to_json ===");
+    let named = |name: &str, power: f64| SensorStats {
+        voltage: stats(1.0, 4),
+        current: stats(power, 4),
+        power: stats(power, 4),
+        ..SensorStats::new(name)
+    };
+    let mut total = named("Total", 3.5);
+    total.power.max = f64::INFINITY;
+    let stats = OwnedPowerStats {
+        total,
+        sensors: vec![named("VCCINT", 2.5), named("VCC\"AUX", 1.0)],
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+    let sensors = json["sensors"].as_object().unwrap();
+    let names: Vec<&str> = sensors.keys().map(|name| name.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"VCCINT") && names.contains(&"VCC\"AUX"));
+    assert_eq!(json["sensors"]["VCCINT"]["power"]["avg"], 2.5);
+    assert_eq!(json["sensors"]["VCCINT"]["power"]["total"], 10.0);
+    assert_eq!(json["sensors"]["VCCINT"]["voltage"]["count"], 4);
+    assert_eq!(json["total"]["power"]["min"], 3.5);
+    assert!(json["total"]["power"]["max"].is_null());
+}
+
+/// Test that the JSON export carries the capture metadata
+#[test]
+fn test_stats_to_json_with_metadata() {
+    println!("\n=== Running test_stats_to_json_with_metadata ===");
+    let sensor = SensorStats {
+        power: stats(2.5, 4),
+        ..SensorStats::new("VCCINT")
+    };
+    let stats = OwnedPowerStats {
+        total: sensor,
+        sensors: vec![sensor],
+    };
+    let metadata = CaptureMetadata {
+        hostname: Some("zcu102".to_string()),
+        kernel_version: Some("6.1.0-xilinx".to_string()),
+        board_model: None,
+        start_time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        sampling_frequency_hz: Some(1000),
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&stats.to_json_with_metadata(&metadata)).unwrap();
+    assert_eq!(json["metadata"]["hostname"], "zcu102");
+    assert_eq!(json["metadata"]["kernel_version"], "6.1.0-xilinx");
+    assert!(json["metadata"]["board_model"].is_null());
+    assert_eq!(json["metadata"]["start_time_unix_ms"], 1_700_000_000_123u64);
+    assert_eq!(json["metadata"]["sampling_frequency_hz"], 1000);
+    assert_eq!(json["sensors"]["VCCINT"]["power"]["avg"], 2.5);
+
+    // The statistics are those of the plain export
+    let plain: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+    assert_eq!(json["total"], plain["total"]);
+    assert_eq!(json["sensors"], plain["sensors"]);
+}
+
+/// Test energy derived from the power samples and the sampling frequency
+#[test]
+fn test_energy_joules() {
+    println!("\n=== Running test_energy_joules ===");
+    // 2 W held for 100 samples at 50 Hz is 2 s, i.e. 4 J
+    let total = SensorStats {
+        power: stats(2.0, 100),
+        ..SensorStats::default()
+    };
+    let mut rail = total;
+    rail.power = stats(0.5, 100);
+    let stats = OwnedPowerStats {
+        total,
+        sensors: vec![rail],
+    };
+    assert!((stats.energy_joules(50) - 4.0).abs() < 1e-9);
+    assert!((stats.sensors[0].energy_joules(50) - 1.0).abs() < 1e-9);
+    assert_eq!(stats.energy_joules(0), 0.0);
+}
+
+/// Test grouping and summing sensors by type
+#[test]
+fn test_group_by_type() {
+    let mut ps_total = sensor("PS_TOTAL_POWER", 0.85, 1.0, 0.85);
+    ps_total.type_ = SensorType::System;
+    let mut rails = vec![
+        sensor("VCCPSINTFP", 0.85, 1.0, 0.85),
+        sensor("VCCINT", 0.85, 4.0, 3.4),
+        sensor("VCC3V3", 3.3, 0.5, 1.65),
+    ];
+    for rail in &mut rails {
+        rail.type_ = SensorType::I2C;
+    }
+    rails.insert(2, ps_total);
+    let data = OwnedPowerData {
+        total: sensor("Total", 0.0, 0.0, 5.9),
+        sensors: rails,
+    };
+
+    let groups = data.group_by_type();
+    assert_eq!(groups.len(), 2);
+    let i2c: Vec<&str> = groups[&SensorType::I2C].iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(i2c, ["VCCPSINTFP", "VCCINT", "VCC3V3"]);
+    assert_eq!(groups[&SensorType::System].len(), 1);
+    assert_eq!(groups[&SensorType::System][0].kind, SensorType::System);
+
+    let sums = data.sum_by_type();
+    assert!((sums[&SensorType::I2C] - 5.9).abs() < 1e-12);
+    assert_eq!(sums[&SensorType::System], 0.85);
+    assert!(!sums.contains_key(&SensorType::Unknown));
+}
+
+/// Test merging two runs with known statistics
+#[test]
+fn test_merge_stats() {
+    println!("\n=== Running test_merge_stats ===");
+    let sensor_stats = |name: &str, power: Stats| SensorStats {
+        power,
+        ..SensorStats::new(name)
+    };
+    // Samples 1 and 3 in the first run, 5 in the second
+    let first_run = Stats {
+        min: 1.0,
+        max: 3.0,
+        avg: 2.0,
+        total: 4.0,
+        count: 2,
+        sum_sq_dev: 2.0,
+        stddev: 1.0,
+    };
+    let first = OwnedPowerStats {
+        total: sensor_stats("Total", first_run),
+        sensors: vec![sensor_stats("VCCINT", first_run), sensor_stats("VCCAUX", stats(0.5, 2))],
+    };
+    let second = OwnedPowerStats {
+        total: sensor_stats("Total", stats(5.0, 1)),
+        sensors: vec![sensor_stats("VCC3V3", stats(1.5, 1)), sensor_stats("VCCINT", stats(5.0, 1))],
+    };
+
+    let merged = first.merge(&second);
+    let names: Vec<String> = merged.sensors.iter().map(|s| s.name().into_owned()).collect();
+    assert_eq!(names, ["VCCINT", "VCCAUX", "VCC3V3"]);
+    for power in [&merged.total.power, &merged.sensors[0].power] {
+        assert_eq!((power.min, power.max, power.total, power.count), (1.0, 5.0, 9.0, 3));
+        assert_eq!(power.avg, 3.0);
+        assert!((power.sum_sq_dev - 8.0).abs() < 1e-12);
+        assert!((power.stddev - (8.0f64 / 3.0).sqrt()).abs() < 1e-12);
+    }
+    assert_eq!(merged.sensors[1], first.sensors[1]);
+    assert_eq!(merged.sensors[2], second.sensors[0]);
+
+    let empty = OwnedPowerStats {
+        total: sensor_stats("Total", stats(0.0, 0)),
+        sensors: Vec::new(),
+    };
+    assert_eq!(empty.merge(&first), first);
+}
+
+/// Test the deltas between an idle and a loaded snapshot
+#[test]
+fn test_diff_data() {
+    let idle = OwnedPowerData {
+        total: sensor("Total", 1.0, 3.0, 3.0),
+        sensors: vec![
+            sensor("VCCINT", 1.0, 2.0, 2.0),
+            sensor("VCCAUX", 1.5, 0.5, 0.75),
+            sensor("VCCBRAM", 0.9, 0.5, 0.45),
+        ],
+    };
+    let load = OwnedPowerData {
+        total: sensor("Total", 1.0, 6.5, 6.0),
+        sensors: vec![
+            sensor("VCCAUX", 1.5, 0.75, 1.125),
+            sensor("VCCINT", 0.75, 6.0, 4.5),
+            sensor("MGTAVCC", 0.9, 0.25, 0.225),
+        ],
+    };
+
+    let diff = load.diff(&idle);
+    assert_eq!(
+        diff.total,
+        SensorDelta {
+            name: "Total".to_string(),
+            d_voltage: 0.0,
+            d_current: 3.5,
+            d_power: 3.0,
+        }
+    );
+    let names: Vec<&str> = diff.sensors.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["VCCAUX", "VCCINT"]);
+    assert_eq!(diff.sensors[0].d_current, 0.25);
+    assert_eq!(diff.sensors[0].d_power, 0.375);
+    assert_eq!(diff.sensors[1].d_voltage, -0.25);
+    assert_eq!(diff.sensors[1].d_power, 2.5);
+    assert_eq!(diff.added, ["MGTAVCC"]);
+    assert_eq!(diff.removed, ["VCCBRAM"]);
+
+    let unchanged = idle.diff(&idle);
+    assert!(unchanged.sensors.iter().all(|d| d.d_voltage == 0.0 && d.d_current == 0.0 && d.d_power == 0.0));
+    assert!(unchanged.added.is_empty() && unchanged.removed.is_empty());
+}
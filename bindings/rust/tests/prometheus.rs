@@ -0,0 +1,48 @@
+//! Tests for the Prometheus exporter that do not require sensor hardware
+#![cfg(feature = "prometheus")]
+
+use xlnpwmon::{OwnedPowerData, SensorData};
+
+/// Builds a synthetic sensor reading
+fn sensor(name: &str, voltage: f64, current: f64, power: f64) -> SensorData {
+    SensorData {
+        voltage,
+        current,
+        power,
+        online: true,
+        ..SensorData::new(name)
+    }
+}
+
+/// Test the exposition text against known metric lines
+#[test]
+fn test_prometheus_text() {
+    println!("\n=== Running test_prometheus_text ===");
+    let data = OwnedPowerData {
+        total: sensor("Total", 0.0, 0.0, 3.25),
+        sensors: vec![
+            sensor("VCCINT", 0.85, 2.5, 2.125),
+            sensor("odd \"rail\"\\\n", 1.8, 0.5, f64::NAN),
+        ],
+    };
+    let text = data.to_prometheus();
+    println!("{}", text);
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(lines.contains(&"# TYPE xlnpwmon_power_watts gauge"));
+    assert!(lines.contains(&"xlnpwmon_power_watts{sensor=\"VCCINT\"} 2.125"));
+    assert!(lines.contains(&"xlnpwmon_voltage_volts{sensor=\"VCCINT\"} 0.85"));
+    assert!(lines.contains(&"xlnpwmon_current_amperes{sensor=\"VCCINT\"} 2.5"));
+    assert!(lines.contains(&"xlnpwmon_power_watts{sensor=\"odd \\\"rail\\\"\\\\\\n\"} NaN"));
+    assert_eq!(lines.last(), Some(&"xlnpwmon_power_total_watts 3.25"));
+
+    // Every line is a comment or `name{labels} value` / `name value`
+    for line in &lines {
+        if line.starts_with('#') {
+            continue;
+        }
+        let (series, value) = line.rsplit_once(' ').unwrap();
+        assert!(series.starts_with("xlnpwmon_"), "bad series in {:?}", line);
+        assert!(value == "NaN" || value.parse::<f64>().is_ok(), "bad value in {:?}", line);
+    }
+}
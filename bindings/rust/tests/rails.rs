@@ -0,0 +1,49 @@
+//! Tests for the rail classification that do not require sensor hardware
+
+use xlnpwmon::{PowerDomain, Rail, SensorData};
+
+/// Builds a synthetic sensor reading with the given name
+fn sensor(name: &str) -> SensorData {
+    SensorData::new(name)
+}
+
+/// Test the classification of rail names reported by ZCU102 and Versal boards
+#[test]
+fn test_rail_from_name() {
+    println!("\n=== Running test_rail_from_name ===");
+    let cases = [
+        ("VCCINT", Rail::VccInt, PowerDomain::Pl),
+        ("VCCPSINTFP", Rail::VccPsIntFp, PowerDomain::Ps),
+        ("VCC_PSINTLP", Rail::VccPsIntLp, PowerDomain::Ps),
+        ("VCC_PSLP", Rail::VccPsIntLp, PowerDomain::Ps),
+        ("VCCO_PSDDR_504", Rail::VccoPsDdr, PowerDomain::Ps),
+        ("VCCOPS3", Rail::VccoPs, PowerDomain::Ps),
+        ("VCC_SOC", Rail::VccSoc, PowerDomain::Pl),
+        ("MGTAVCC", Rail::MgtAvcc, PowerDomain::Transceiver),
+        ("MGTYAVTT", Rail::MgtAvtt, PowerDomain::Transceiver),
+        ("MGTRAVCC", Rail::MgtrAvcc, PowerDomain::Transceiver),
+        ("VADJ_FMC", Rail::VadjFmc, PowerDomain::Other),
+        ("vcc3v3", Rail::Vcc3V3, PowerDomain::Other),
+    ];
+    for (name, rail, domain) in cases {
+        assert_eq!(Rail::from_name(name), rail, "{}", name);
+        assert_eq!(rail.domain(), domain, "{}", name);
+    }
+}
+
+/// Test that unknown names are kept verbatim
+#[test]
+fn test_rail_unknown() {
+    println!("\n=== Running test_rail_unknown ===");
+    assert_eq!(Rail::from_name("TOTAL_POWER"), Rail::Unknown("TOTAL_POWER".to_string()));
+    assert_eq!(Rail::from_name("VCC1V8"), Rail::Unknown("VCC1V8".to_string()));
+    assert_eq!(Rail::from_name("").domain(), PowerDomain::Other);
+}
+
+/// Test that a reading is classified by its decoded name
+#[test]
+fn test_sensor_rail() {
+    println!("\n=== Running test_sensor_rail ===");
+    assert_eq!(sensor("VCCBRAM").rail(), Rail::VccBram);
+    assert_eq!(sensor("VCCBRAM").rail().domain(), PowerDomain::Pl);
+}
@@ -0,0 +1,51 @@
+//! Tests for the multi-board registry using simulated boards
+
+use xlnpwmon::{Error, PowerMonitorRegistry, PowerSource, PowerSummary};
+
+/// A simulated board reporting a fixed power summary
+struct SimulatedBoard {
+    ps: f64,
+    pl: f64,
+    online: bool,
+}
+
+impl PowerSource for SimulatedBoard {
+    fn get_power_summary(&self) -> Result<PowerSummary, Error> {
+        if !self.online {
+            return Err(Error::NotRunning);
+        }
+        Ok(PowerSummary {
+            ps_total_power: self.ps,
+            pl_total_power: self.pl,
+            total_power: self.ps + self.pl,
+        })
+    }
+}
+
+/// Test aggregating the summaries of two boards
+#[test]
+fn test_aggregate_summary() {
+    let mut registry = PowerMonitorRegistry::new();
+    registry.insert("0", SimulatedBoard { ps: 1.5, pl: 4.0, online: true });
+    registry.insert("1", SimulatedBoard { ps: 2.0, pl: 3.5, online: true });
+
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.ids().collect::<Vec<_>>(), ["0", "1"]);
+
+    let summary = registry.aggregate_summary().unwrap();
+    assert_eq!(summary.ps_total_power, 3.5);
+    assert_eq!(summary.pl_total_power, 7.5);
+    assert_eq!(summary.total_power, 11.0);
+}
+
+/// Test that a failing board fails the aggregate
+#[test]
+fn test_aggregate_summary_error() {
+    let mut registry = PowerMonitorRegistry::new();
+    registry.insert("0", SimulatedBoard { ps: 1.0, pl: 1.0, online: true });
+    registry.insert("1", SimulatedBoard { ps: 1.0, pl: 1.0, online: false });
+    assert!(matches!(registry.aggregate_summary().unwrap_err(), Error::NotRunning));
+
+    registry.remove("1");
+    assert_eq!(registry.aggregate_summary().unwrap().total_power, 2.0);
+}
@@ -0,0 +1,173 @@
+//! Round-trip tests for the `serde` feature that do not require sensor hardware
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+use xlnpwmon::{
+    CaptureMetadata, Error, OwnedPowerData, OwnedPowerStats, PowerData, PowerMonitor, PowerStats,
+    SensorData, SensorStats, SensorType, Stats,
+};
+
+/// Builds a synthetic sensor reading
+fn sensor(name: &str, power: f64) -> SensorData {
+    let mut sensor = SensorData {
+        type_: SensorType::I2C,
+        voltage: 0.85,
+        current: power / 0.85,
+        power,
+        online: true,
+        ..SensorData::new(name)
+    };
+    sensor.status[..2].copy_from_slice(b"OK");
+    sensor
+}
+
+/// Builds synthetic statistics for a sensor
+fn sensor_stats(name: &str, count: u64) -> SensorStats {
+    SensorStats {
+        power: Stats {
+            min: 1.0,
+            max: 3.0,
+            avg: 2.0,
+            total: 2.0 * count as f64,
+            count,
+            sum_sq_dev: count as f64,
+            stddev: 1.0,
+        },
+        ..SensorStats::new(name)
+    }
+}
+
+/// Test that names and statuses are written as trimmed strings
+#[test]
+fn test_sensor_data_format() {
+    println!("\n=== Running test_sensor_data_format ===");
+    let value = serde_json::to_value(sensor("VCCINT", 1.7)).unwrap();
+    assert_eq!(value["name"], "VCCINT");
+    assert_eq!(value["status"], "OK");
+    assert_eq!(value["type_"], "I2C");
+    assert_eq!(value["online"], true);
+}
+
+/// Test a round trip from raw power data to owned power data
+#[test]
+fn test_power_data_round_trip() {
+    println!("\n=== Running test_power_data_round_trip ===");
+    let sensors = [sensor("VCCINT", 1.7), sensor("VCCAUX", 0.4)];
+    let data = PowerData {
+        total: sensor("Total", 2.1),
+        sensors: sensors.as_ptr(),
+        sensor_count: sensors.len() as i32,
+    };
+    let json = serde_json::to_string(&data).unwrap();
+    let owned: OwnedPowerData = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(owned.total.name(), "Total");
+    assert_eq!(owned.sensors.len(), 2);
+    for (restored, original) in owned.sensors.iter().zip(&sensors) {
+        assert_eq!(restored.name(), original.name());
+        assert_eq!(restored.status(), original.status());
+        assert_eq!(restored.type_, original.type_);
+        assert_eq!(restored.power, original.power);
+        assert_eq!(restored.current, original.current);
+    }
+    assert_eq!(serde_json::to_string(&owned).unwrap(), json);
+}
+
+/// Test a round trip from raw power statistics to owned statistics
+#[test]
+fn test_power_stats_round_trip() {
+    println!("\n=== Running test_power_stats_round_trip ===");
+    let sensors = [sensor_stats("VCCINT", 10)];
+    let stats = PowerStats {
+        total: sensor_stats("Total", 10),
+        sensors: sensors.as_ptr(),
+        sensor_count: 1,
+    };
+    let json = serde_json::to_string(&stats).unwrap();
+    let owned: OwnedPowerStats = serde_json::from_str(&json).unwrap();
+    assert_eq!(owned.sensors[0].name(), "VCCINT");
+    assert_eq!(owned.sensors[0].power.count, 10);
+    assert_eq!(owned.total.power.total, 20.0);
+    assert_eq!(serde_json::to_string(&owned).unwrap(), json);
+}
+
+/// Test that over-long names are truncated and enums use readable tags
+#[test]
+fn test_names_and_tags() {
+    println!("\n=== Running test_names_and_tags ===");
+    let mut value = serde_json::to_value(sensor("VCCINT", 1.0)).unwrap();
+    value["name"] = serde_json::Value::String("Ä".repeat(40));
+    let restored: SensorData = serde_json::from_value(value).unwrap();
+    assert_eq!(restored.name(), "Ä".repeat(31));
+
+    assert_eq!(serde_json::to_string(&SensorType::System).unwrap(), "\"System\"");
+    assert_eq!(serde_json::to_string(&Error::NoSensors).unwrap(), "\"NoSensors\"");
+    let unknown: Error = serde_json::from_str("{\"Unknown\":-42}").unwrap();
+    assert!(matches!(unknown, Error::Unknown(-42)));
+}
+
+/// Opens a monitor on sensors named like the fixture presets: the mock
+/// sensors, or the hwmon fixture of `tests/sysfs.rs`
+fn fixture_monitor() -> PowerMonitor {
+    if cfg!(feature = "mock") {
+        PowerMonitor::new().unwrap()
+    } else {
+        PowerMonitor::with_sysfs_root(fixture_path("sysfs")).unwrap()
+    }
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// Test that capture metadata round-trips in the format of `to_json`
+#[test]
+fn test_metadata_round_trip() {
+    println!("\n=== Running test_metadata_round_trip ===");
+    let metadata = CaptureMetadata {
+        hostname: Some("zcu102".to_string()),
+        kernel_version: None,
+        board_model: Some("ZynqMP ZCU102 Rev1.0".to_string()),
+        start_time: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        sampling_frequency_hz: Some(100),
+    };
+    let json = serde_json::to_string(&metadata).unwrap();
+    assert_eq!(json, metadata.to_json());
+    assert!(json.contains("\"start_time_unix_ms\":1700000000123"));
+    let restored: CaptureMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, metadata);
+}
+
+/// Test applying the threshold presets of a file, and that a file naming
+/// unknown sensors is rejected as a whole
+#[test]
+fn test_apply_thresholds_from_file() {
+    println!("\n=== Running test_apply_thresholds_from_file ===");
+    let monitor = fixture_monitor();
+    assert_eq!(monitor.apply_thresholds_from_file(fixture_path("thresholds.json")).unwrap(), 2);
+    let data = monitor.get_latest_owned().unwrap();
+    let vccint = data.sensors.iter().find(|s| s.name() == "VCCINT").unwrap();
+    assert_eq!((vccint.warning_threshold, vccint.critical_threshold), (3.5, 4.5));
+    let vcc3v3 = data.sensors.iter().find(|s| s.name() == "VCC3V3").unwrap();
+    assert_eq!((vcc3v3.warning_threshold, vcc3v3.critical_threshold), (0.0, 0.0));
+
+    let path = std::env::temp_dir().join(format!("xlnpwmon-thresholds-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"{"VCC3V3": {"warning": 1.0, "critical": 2.0}, "VCCBOGUS": {"warning": 1.0, "critical": 2.0}}"#,
+    )
+    .unwrap();
+    let error = monitor.apply_thresholds_from_file(&path).unwrap_err();
+    assert!(matches!(error, Error::InvalidArgument));
+    assert!(error.context().unwrap().ends_with("unknown sensors: VCCBOGUS"));
+    let data = monitor.get_latest_owned().unwrap();
+    let vcc3v3 = data.sensors.iter().find(|s| s.name() == "VCC3V3").unwrap();
+    assert_eq!(vcc3v3.warning_threshold, 0.0);
+
+    std::fs::write(&path, r#"{"VCC3V3": {"warning": 1.0}}"#).unwrap();
+    assert!(matches!(monitor.apply_thresholds_from_file(&path), Err(Error::InvalidArgument)));
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(monitor.apply_thresholds_from_file(&path), Err(Error::FileAccess)));
+}
@@ -0,0 +1,187 @@
+//! Tests that parse the hwmon fixture in `tests/fixtures/sysfs`
+//!
+//! The fixture holds three INA226 sensors (hwmon0, hwmon1, hwmon10) and one
+//! unrelated hwmon device that must be skipped. The mock backend does not
+//! read sysfs, so these only run without the `mock` feature.
+#![cfg(not(feature = "mock"))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use xlnpwmon::{Error, PowerMonitor};
+
+fn fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sysfs")
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Test that the fixture sensors are discovered in hwmon order and their
+/// raw millivolt, milliampere and microwatt readings are converted
+#[test]
+fn test_sysfs_fixture() {
+    println!("\n=== Running test_sysfs_fixture ===");
+    let monitor = PowerMonitor::with_sysfs_root(fixture_root()).unwrap();
+    assert_eq!(
+        monitor.sensor_names().unwrap(),
+        ["VCCPSINTFP", "VCCINT", "VCC3V3", "PS_TOTAL_POWER", "PL_TOTAL_POWER", "TOTAL_POWER"]
+    );
+
+    let data = monitor.read_once().unwrap();
+    let readings: Vec<(f64, f64, f64)> = data
+        .sensors
+        .iter()
+        .take(3)
+        .map(|sensor| (sensor.voltage, sensor.current, sensor.power))
+        .collect();
+    assert_eq!(readings, [(0.85, 1.2, 1.02), (0.85, 3.0, 2.55), (3.3, 0.5, 1.65)]);
+    assert!((data.sensors[5].power - 5.22).abs() < 1e-9);
+    assert!(data.sensors.iter().all(|s| s.online && s.status() == "OK"));
+}
+
+/// Test that the environment variable redirects the default constructor
+#[test]
+fn test_sysfs_root_env() {
+    println!("\n=== Running test_sysfs_root_env ===");
+    std::env::set_var("XLNPWMON_SYSFS_ROOT", fixture_root());
+    let monitor = PowerMonitor::new().unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+
+    let missing = fixture_root().join("missing");
+    assert!(matches!(PowerMonitor::with_sysfs_root(missing), Err(Error::NoSensors)));
+}
+
+/// Test that a monitor survives its sensors disappearing and is restored by
+/// `reinitialize` once they are back
+#[test]
+fn test_sysfs_reinitialize() {
+    println!("\n=== Running test_sysfs_reinitialize ===");
+    let root = std::env::temp_dir().join(format!("xlnpwmon-reinit-{}", std::process::id()));
+    let hwmon = root.join("class/hwmon");
+    copy_dir(&fixture_root(), &root);
+
+    let mut monitor = PowerMonitor::with_sysfs_root(&root).unwrap();
+    monitor.set_sampling_frequency(50).unwrap();
+
+    fs::rename(&hwmon, root.join("unplugged")).unwrap();
+    let data = monitor.read_once().unwrap();
+    assert!(data.sensors.iter().take(3).all(|s| !s.online));
+    assert!(matches!(monitor.reinitialize(), Err(Error::NoSensors)));
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+
+    fs::rename(root.join("unplugged"), &hwmon).unwrap();
+    monitor.reinitialize().unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 50);
+    assert!(monitor.read_once().unwrap().sensors.iter().all(|s| s.online));
+
+    drop(monitor);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test that `reinitialize` refreshes the cached sensor count when a
+/// sensor is gone
+#[test]
+fn test_sysfs_reinitialize_sensor_count() {
+    println!("\n=== Running test_sysfs_reinitialize_sensor_count ===");
+    let root = std::env::temp_dir().join(format!("xlnpwmon-count-{}", std::process::id()));
+    copy_dir(&fixture_root(), &root);
+
+    let mut monitor = PowerMonitor::with_sysfs_root(&root).unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+    fs::remove_dir_all(root.join("class/hwmon/hwmon10")).unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 6);
+    monitor.reinitialize().unwrap();
+    assert_eq!(monitor.get_sensor_count().unwrap(), 5);
+    assert_eq!(monitor.sensor_names().unwrap().len(), 5);
+    assert_eq!(monitor.get_latest_data().unwrap().sensor_count, 5);
+
+    drop(monitor);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test that a missing hwmon directory is reported with its path
+#[test]
+fn test_sysfs_missing_context() {
+    println!("\n=== Running test_sysfs_missing_context ===");
+    let missing = fixture_root().join("missing");
+    let error = PowerMonitor::with_sysfs_root(&missing).err().unwrap();
+    assert!(matches!(error, Error::NoSensors));
+    let context = error.context().unwrap();
+    println!("Context: {}", context);
+    assert!(context.contains(&missing.join("class/hwmon").display().to_string()));
+    assert!(context.contains("No such file or directory"));
+    assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+}
+
+/// Test that the cause of a failed init is not attached to a later error
+/// with the same code
+#[test]
+fn test_sysfs_stale_context() {
+    println!("\n=== Running test_sysfs_stale_context ===");
+    let error = PowerMonitor::with_sysfs_root(fixture_root().join("missing")).err().unwrap();
+    assert!(matches!(error, Error::NoSensors));
+    assert!(error.context().is_some());
+
+    let monitor = PowerMonitor::with_sysfs_root(fixture_root()).unwrap();
+    let error = monitor.reset_statistics_for_sensor("VCCBOGUS").err().unwrap();
+    assert!(matches!(error, Error::NoSensors));
+    assert_eq!(error.context(), None);
+    assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+}
+
+/// Test that an unreadable hwmon directory yields `FileAccess` naming it
+#[test]
+fn test_sysfs_permission_denied_context() {
+    use std::os::unix::fs::PermissionsExt;
+
+    println!("\n=== Running test_sysfs_permission_denied_context ===");
+    let root = std::env::temp_dir().join(format!("xlnpwmon-denied-{}", std::process::id()));
+    let hwmon = root.join("class/hwmon");
+    copy_dir(&fixture_root(), &root);
+    fs::set_permissions(&hwmon, fs::Permissions::from_mode(0o000)).unwrap();
+
+    if fs::read_dir(&hwmon).is_ok() {
+        println!("Permissions are not enforced for this user, skipping");
+    } else {
+        let error = PowerMonitor::with_sysfs_root(&root).err().unwrap();
+        assert!(matches!(error, Error::FileAccess));
+        let context = error.context().unwrap();
+        println!("Context: {}", context);
+        assert!(context.contains(&hwmon.display().to_string()));
+        assert!(context.contains("Permission denied"));
+        assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    fs::set_permissions(&hwmon, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test that a CSV file that cannot be created is reported with its path,
+/// while bare error codes carry no context
+#[test]
+fn test_csv_file_access_context() {
+    println!("\n=== Running test_csv_file_access_context ===");
+    let monitor = PowerMonitor::with_sysfs_root(fixture_root()).unwrap();
+    let path = fixture_root().join("no-such-dir/capture.csv");
+    let error = monitor.record_csv(&path).err().unwrap();
+    assert!(matches!(error, Error::FileAccess));
+    let context = error.context().unwrap();
+    assert!(context.contains(&path.display().to_string()));
+    assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+
+    let bare = Error::from(-7);
+    assert!(matches!(bare, Error::FileAccess));
+    assert_eq!(bare.context(), None);
+}
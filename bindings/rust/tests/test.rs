@@ -1,4 +1,4 @@
-use xlnpwmon::{PowerMonitor, Error, SensorType};
+use xlnpwmon::{PowerMonitor, PowerMonitorBuilder, PowerMonitorConfig, Error, SensorType, ThresholdLevel};
 use std::thread;
 use std::time::Duration;
 
@@ -174,7 +174,7 @@ fn test_sensor_info() {
     
     // Get sensor names
     println!("Getting sensor names...");
-    let names = monitor.get_sensor_names().unwrap();
+    let names = monitor.sensor_names().unwrap();
     println!("Got {} sensor names", names.len());
     assert_eq!(names.len(), count as usize);
     
@@ -215,6 +215,9 @@ fn test_power_summary() {
     println!("\n=== Running test_power_summary ===");
     let monitor = PowerMonitor::new().unwrap();
 
+    // No summary exists before the first sample
+    assert!(matches!(monitor.get_power_summary(), Err(Error::NotRunning)));
+
     // Set sampling frequency and start sampling
     monitor.set_sampling_frequency(10).unwrap();
     monitor.start_sampling().unwrap();
@@ -264,6 +267,10 @@ fn test_power_summary_stats() {
     // Get power summary statistics
     let summary_stats = monitor.get_power_summary_stats().unwrap();
 
+    // All three statistics come from the same samples
+    assert_eq!(summary_stats.ps_total_power.count, summary_stats.total_power.count);
+    assert_eq!(summary_stats.pl_total_power.count, summary_stats.total_power.count);
+
     // Verify PS total power statistics
     println!("PS Total Power Stats - Min: {}, Max: {}, Avg: {}, Count: {}",
              summary_stats.ps_total_power.min,
@@ -311,4 +318,890 @@ fn test_power_summary_stats() {
                "PS and PL should have same sample count");
     assert_eq!(summary_stats.ps_total_power.count, summary_stats.total_power.count,
                "PS and Total should have same sample count");
-}
\ No newline at end of file
+}
+/// Test sensor group definition and group power
+#[test]
+fn test_sensor_groups() {
+    println!("\n=== Running test_sensor_groups ===");
+    let monitor = PowerMonitor::new().unwrap();
+
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(500));
+    monitor.stop_sampling().unwrap();
+
+    let data = monitor.get_latest_data().unwrap();
+    let names: Vec<String> = data
+        .sensors()
+        .iter()
+        .take(2)
+        .map(|s| s.name().into_owned())
+        .collect();
+    let members: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+
+    monitor.define_group("accelerator", &members).unwrap();
+    let expected: f64 = data.sensors().iter().take(2).map(|s| s.power).sum();
+    let actual = monitor.group_power("accelerator").unwrap();
+    assert!((actual - expected).abs() < 1e-9, "group power {} != {}", actual, expected);
+
+    // Unknown members and unknown groups are rejected
+    assert!(matches!(
+        monitor.define_group("bogus", &["NO_SUCH_RAIL"]).unwrap_err(),
+        Error::InvalidArgument
+    ));
+    assert!(matches!(
+        monitor.group_power("missing").unwrap_err(),
+        Error::NoSensors
+    ));
+}
+
+/// Test the liveness heartbeat
+#[test]
+fn test_heartbeat() {
+    println!("\n=== Running test_heartbeat ===");
+    let monitor = PowerMonitor::new().unwrap();
+
+    let idle = monitor.heartbeat();
+    assert!(!idle.sampling);
+    assert_eq!(idle.effective_rate, 0.0);
+    assert_eq!(idle.sensors_total, monitor.get_sensor_count().unwrap() as usize);
+
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.reset_statistics().unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(500));
+
+    let beat = monitor.heartbeat();
+    println!("Heartbeat: {:?}", beat);
+    assert!(beat.sampling);
+    assert!(beat.effective_rate > 0.0);
+    assert!(beat.last_sample_age < Duration::from_millis(500));
+    assert!(beat.sensors_online > 0);
+    assert!(beat.sensors_online <= beat.sensors_total);
+
+    monitor.stop_sampling().unwrap();
+    assert!(!monitor.heartbeat().sampling);
+}
+
+/// Test that quantization applies to processed outputs only
+#[test]
+fn test_quantize() {
+    println!("\n=== Running test_quantize ===");
+    let monitor = xlnpwmon::PowerMonitorBuilder::new().quantize(1).build().unwrap();
+
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(500));
+    monitor.stop_sampling().unwrap();
+
+    let raw = monitor.get_latest_data().unwrap();
+    let processed = monitor.get_latest_owned().unwrap();
+
+    let round = |v: f64| (v * 10.0).round() / 10.0;
+    assert_eq!(processed.total.power, round(raw.total.power));
+    for (raw, processed) in raw.sensors().iter().zip(&processed.sensors) {
+        assert_eq!(processed.voltage, round(raw.voltage));
+        assert_eq!(processed.current, round(raw.current));
+        assert_eq!(processed.power, round(raw.power));
+    }
+
+    let stats = monitor.get_statistics_owned().unwrap();
+    assert_eq!(stats.total.power.avg, round(stats.total.power.avg));
+}
+
+/// Test that the deprecated name accessor forwards to `sensor_names`
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_sensor_names() {
+    println!("\n=== Running test_deprecated_sensor_names ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert_eq!(monitor.get_sensor_names().unwrap(), monitor.sensor_names().unwrap());
+}
+
+/// Test labeled phase recording
+#[test]
+fn test_phase_recorder() {
+    println!("\n=== Running test_phase_recorder ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.reset_statistics().unwrap();
+    monitor.start_sampling().unwrap();
+
+    let mut recorder = monitor.phase_recorder();
+    recorder.mark("first").unwrap();
+    thread::sleep(Duration::from_millis(300));
+    recorder.mark("second").unwrap();
+    thread::sleep(Duration::from_millis(300));
+    let phases = recorder.finish().unwrap();
+    monitor.stop_sampling().unwrap();
+
+    assert_eq!(phases.len(), 2);
+    assert_eq!(phases[0].name, "first");
+    assert_eq!(phases[1].name, "second");
+    for phase in &phases {
+        println!("{:?}", phase);
+        assert!(phase.duration >= Duration::from_millis(300));
+        assert!(phase.samples > 0);
+        assert!(phase.avg_power >= 0.0);
+        let expected = phase.avg_power * phase.duration.as_secs_f64();
+        assert!((phase.energy - expected).abs() < 1e-9);
+    }
+}
+
+/// Test that data and statistics list sensors in the same order
+#[test]
+fn test_sensor_ordering() {
+    println!("\n=== Running test_sensor_ordering ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    monitor.stop_sampling().unwrap();
+
+    let data = monitor.get_latest_data().unwrap();
+    let stats = monitor.get_statistics().unwrap();
+    assert_eq!(data.sensor_count, stats.sensor_count);
+
+    for (i, (sensor, stat)) in data.sensors().iter().zip(stats.sensors()).enumerate() {
+        let data_name = sensor.name();
+        let stats_name = stat.name();
+        assert_eq!(data_name, stats_name, "sensor {} differs between data and statistics", i);
+    }
+
+    // The order is stable across calls and matches sensor_names()
+    let names = monitor.sensor_names().unwrap();
+    assert_eq!(names, monitor.sensor_names().unwrap());
+    let tail: Vec<&str> = names.iter().rev().take(3).rev().map(|n| n.as_str()).collect();
+    assert_eq!(tail, ["PS_TOTAL_POWER", "PL_TOTAL_POWER", "TOTAL_POWER"]);
+}
+
+/// Runs two sampling cycles and returns the sample counts after each
+fn two_sampling_runs(monitor: &PowerMonitor) -> (u64, u64) {
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(500));
+    monitor.stop_sampling().unwrap();
+    let first = monitor.get_statistics().unwrap().total.power.count;
+
+    monitor.start_sampling().unwrap();
+    monitor.stop_sampling().unwrap();
+    let second = monitor.get_statistics().unwrap().total.power.count;
+    (first, second)
+}
+
+/// Test that the builder resets statistics on start by default
+#[test]
+fn test_reset_on_start() {
+    println!("\n=== Running test_reset_on_start ===");
+    let monitor = xlnpwmon::PowerMonitorBuilder::new().build().unwrap();
+    let (first, second) = two_sampling_runs(&monitor);
+    println!("Counts with reset: {} then {}", first, second);
+    assert!(second < first, "second run should start from a clean capture");
+}
+
+/// Test that statistics accumulate across runs when reset-on-start is disabled
+#[test]
+fn test_reset_on_start_disabled() {
+    println!("\n=== Running test_reset_on_start_disabled ===");
+    let monitor = xlnpwmon::PowerMonitorBuilder::new()
+        .reset_on_start(false)
+        .build()
+        .unwrap();
+    let (first, second) = two_sampling_runs(&monitor);
+    println!("Counts without reset: {} then {}", first, second);
+    assert!(second >= first, "statistics should accumulate across runs");
+}
+
+/// Test the reset-and-measure protocol
+#[test]
+fn test_reset_and_measure() {
+    println!("\n=== Running test_reset_and_measure ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+
+    let (value, stats) = monitor
+        .reset_and_measure(Duration::from_millis(300), || {
+            thread::sleep(Duration::from_millis(300));
+            42
+        })
+        .unwrap();
+
+    assert_eq!(value, 42);
+    assert!(!monitor.is_sampling().unwrap());
+    // Warmup samples are discarded: only ~3 samples of the 300 ms window remain
+    assert!(stats.total.power.count > 0);
+    assert!(stats.total.power.count <= 5, "count {}", stats.total.power.count);
+}
+
+/// Test that sampling is stopped when the measured workload panics
+#[test]
+fn test_reset_and_measure_panic() {
+    println!("\n=== Running test_reset_and_measure_panic ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _ = monitor.reset_and_measure(Duration::from_millis(10), || panic!("workload failed"));
+    }));
+    assert!(result.is_err());
+    assert!(!monitor.is_sampling().unwrap());
+}
+
+/// Test EWMA smoothing of the total power
+#[test]
+fn test_ewma_total_power() {
+    println!("\n=== Running test_ewma_total_power ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(matches!(monitor.ewma_total_power(0.0), Err(Error::InvalidArgument)));
+    // There is no sample to seed the filter with before sampling starts
+    assert!(matches!(monitor.ewma_total_power(0.2), Err(Error::NotRunning)));
+
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    let first = monitor.ewma_total_power(0.2).unwrap();
+    thread::sleep(Duration::from_millis(200));
+    let smoothed = monitor.ewma_total_power(0.2).unwrap();
+    monitor.stop_sampling().unwrap();
+
+    let stats = monitor.get_statistics().unwrap();
+    println!("EWMA: first={:.3} W, later={:.3} W", first, smoothed);
+    assert!(smoothed >= stats.total.power.min - 1e-9);
+    assert!(smoothed <= stats.total.power.max + 1e-9);
+
+    // Another alpha replaces the filter, reseeded with the latest total
+    let latest = monitor.get_latest_data().unwrap().total.power;
+    assert_eq!(monitor.ewma_total_power(0.5).unwrap(), latest);
+    monitor.disable_ewma();
+    monitor.disable_ewma();
+    assert_eq!(monitor.ewma_total_power(0.5).unwrap(), latest);
+}
+
+/// Test clipping detection on real sensors
+#[test]
+fn test_clipping_report() {
+    println!("\n=== Running test_clipping_report ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(matches!(monitor.set_sensor_full_scale("NO_SUCH_SENSOR", 1.0, 1.0), Err(Error::NoSensors)));
+
+    // A tiny full scale pins every reading of the first sensor
+    let name = monitor.sensor_names().unwrap()[0].clone();
+    monitor.set_sensor_full_scale(&name, 1e-9, 1e-9).unwrap();
+    monitor.set_clipping_policy(0.5, 3).unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    monitor.stop_sampling().unwrap();
+    println!("Clipped sensors: {:?}", monitor.clipping_report());
+    assert_eq!(monitor.clipping_report(), vec![name.clone()]);
+    let config = monitor.export_config();
+    assert_eq!(config.clipping_fraction, 0.5);
+    assert_eq!(config.clipping_window, 3);
+    assert_eq!(config.full_scales[&name].current, 1e-9);
+
+    monitor.reset_statistics().unwrap();
+    assert!(monitor.clipping_report().is_empty());
+}
+
+/// Test that the cached sampling state tracks start/stop
+#[test]
+fn test_is_sampling_cache() {
+    println!("\n=== Running test_is_sampling_cache ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(!monitor.is_sampling().unwrap());
+    assert!(!monitor.is_sampling_uncached().unwrap());
+
+    for _ in 0..3 {
+        monitor.start_sampling().unwrap();
+        assert!(monitor.is_sampling().unwrap());
+        assert!(monitor.is_sampling_uncached().unwrap());
+
+        // Failed calls leave the cache consistent with the library
+        assert!(monitor.start_sampling().is_err());
+        assert!(monitor.is_sampling().unwrap());
+
+        monitor.stop_sampling().unwrap();
+        assert!(!monitor.is_sampling().unwrap());
+        assert!(!monitor.is_sampling_uncached().unwrap());
+
+        assert!(monitor.stop_sampling().is_err());
+        assert!(!monitor.is_sampling().unwrap());
+    }
+}
+
+/// Test that starting a capture records its metadata
+#[test]
+fn test_capture_metadata() {
+    println!("\n=== Running test_capture_metadata ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(monitor.capture_metadata().is_none());
+
+    monitor.set_sampling_frequency(200).unwrap();
+    monitor.start_sampling().unwrap();
+    monitor.stop_sampling().unwrap();
+
+    let metadata = monitor.capture_metadata().unwrap();
+    println!("{}", metadata.to_json());
+    assert_eq!(metadata.sampling_frequency_hz, Some(200));
+    assert!(metadata.kernel_version.is_some());
+}
+
+/// Test exporting a configuration and rebuilding a monitor from it
+#[test]
+fn test_config_round_trip() {
+    println!("\n=== Running test_config_round_trip ===");
+    let names = PowerMonitor::new().unwrap().sensor_names().unwrap();
+    let first = names[0].as_str();
+
+    let monitor = PowerMonitorBuilder::new()
+        .quantize(2)
+        .reset_on_start(false)
+        .sampling_frequency(50)
+        .group("first", &[first])
+        .sensor_full_scale(first, 2.0, 10.0)
+        .clipping_policy(0.9, 5)
+        .history_capacity(10)
+        .sensor_thresholds(first, 1.5, 2.5)
+        .build()
+        .unwrap();
+    let config = monitor.export_config();
+    assert_eq!(config.sampling_frequency_hz, Some(50));
+    assert_eq!(config.quantize, Some(2));
+    assert!(!config.reset_on_start);
+    assert_eq!(config.groups["first"], vec![first.to_string()]);
+    assert_eq!(config.full_scales[first].current, 10.0);
+    assert_eq!(config.clipping_window, 5);
+    assert_eq!(config.history_capacity, 10);
+    assert_eq!(config.thresholds.len(), 1);
+    assert_eq!(config.thresholds[first].warning, 1.5);
+    assert_eq!(config.thresholds[first].critical, 2.5);
+
+    let rebuilt = PowerMonitorBuilder::from_config(config.clone()).build().unwrap();
+    assert_eq!(rebuilt.export_config(), config);
+}
+
+/// Test the latest data snapshot
+#[test]
+fn test_latest_snapshot() {
+    println!("\n=== Running test_latest_snapshot ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    monitor.stop_sampling().unwrap();
+
+    let snapshot = monitor.latest().unwrap();
+    let raw = monitor.get_latest_data().unwrap();
+    assert_eq!(snapshot.sensors().len(), raw.sensor_count as usize);
+    assert_eq!(snapshot.total().power, raw.total.power);
+
+    let owned = snapshot.to_owned_data();
+    assert_eq!(owned.sensors.len(), snapshot.sensors().len());
+    for (copy, sensor) in owned.sensors.iter().zip(snapshot.sensors()) {
+        assert_eq!(copy.name(), sensor.name());
+    }
+
+    // The snapshot owns its data and outlives the monitor
+    drop(monitor);
+    assert_eq!(snapshot.sensors().len(), owned.sensors.len());
+    assert_eq!(snapshot.total().power, owned.total.power);
+}
+
+/// Test sharing one monitor between threads
+#[test]
+fn test_shared_between_threads() {
+    println!("\n=== Running test_shared_between_threads ===");
+    let monitor = std::sync::Arc::new(PowerMonitor::new().unwrap());
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let monitor = std::sync::Arc::clone(&monitor);
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    let data = monitor.get_latest_owned().unwrap();
+                    assert!(!data.sensors.is_empty());
+                    assert!(monitor.get_latest_data().unwrap().total.power >= 0.0);
+                    thread::sleep(Duration::from_millis(2));
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    monitor.stop_sampling().unwrap();
+    assert!(!monitor.is_sampling_uncached().unwrap());
+}
+
+/// Test that a sampling guard stops sampling when it goes out of scope
+#[test]
+fn test_sampling_guard() {
+    println!("\n=== Running test_sampling_guard ===");
+    let monitor = PowerMonitor::new().unwrap();
+    {
+        let sampling = monitor.start_sampling_guarded().unwrap();
+        assert!(sampling.is_sampling().unwrap());
+        thread::sleep(Duration::from_millis(50));
+        assert!(sampling.get_latest_data().is_ok());
+    }
+    assert!(!monitor.is_sampling().unwrap());
+    assert!(!monitor.is_sampling_uncached().unwrap());
+
+    // An explicit stop reports errors, and a manual stop does not make the guard panic
+    let sampling = monitor.start_sampling_guarded().unwrap();
+    sampling.stop().unwrap();
+    let sampling = monitor.start_sampling_guarded().unwrap();
+    monitor.stop_sampling().unwrap();
+    drop(sampling);
+    assert!(!monitor.is_sampling().unwrap());
+}
+
+/// Test a synchronous one-shot read
+#[test]
+fn test_read_once() {
+    println!("\n=== Running test_read_once ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(!monitor.is_sampling().unwrap());
+
+    let data = monitor.read_once().unwrap();
+    assert_eq!(data.sensors.len(), monitor.get_sensor_count().unwrap() as usize);
+    let names: Vec<String> = data.sensors.iter().map(|s| s.name().into_owned()).collect();
+    assert_eq!(names, monitor.sensor_names().unwrap());
+    assert!(data.total.power >= 0.0);
+
+    // Statistics are not touched
+    let stats = monitor.get_statistics().unwrap();
+    assert_eq!(stats.total.power.count, 0);
+    assert!(!monitor.is_sampling().unwrap());
+}
+
+/// Test setting sensor thresholds and reading them back
+#[test]
+fn test_sensor_thresholds() {
+    println!("\n=== Running test_sensor_thresholds ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let name = monitor.sensor_names().unwrap()[0].clone();
+
+    monitor.set_sensor_thresholds(&name, 2.5, 4.0).unwrap();
+    let data = monitor.get_latest_data().unwrap();
+    let sensor = data.sensors().iter().find(|s| s.name() == name.as_str()).unwrap();
+    assert_eq!(sensor.warning_threshold, 2.5);
+    assert_eq!(sensor.critical_threshold, 4.0);
+
+    assert!(matches!(
+        monitor.set_sensor_thresholds(&name, 4.0, 2.5),
+        Err(Error::InvalidArgument)
+    ));
+    assert!(matches!(
+        monitor.set_sensor_thresholds("NO_SUCH_SENSOR", 1.0, 2.0),
+        Err(Error::NoSensors)
+    ));
+}
+
+/// Test that threshold crossings are reported once per crossing
+#[test]
+fn test_on_threshold() {
+    println!("\n=== Running test_on_threshold ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.set_sensor_thresholds("TOTAL_POWER", 1e-9, 1e9).unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    monitor.on_threshold(move |sensor, level| {
+        let _ = tx.send((sensor.name().into_owned(), level));
+    });
+
+    let sampling = monitor.start_sampling_guarded().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    sampling.stop().unwrap();
+
+    let events: Vec<_> = rx.try_iter().collect();
+    println!("Threshold events: {:?}", events);
+    assert_eq!(events, [("TOTAL_POWER".to_string(), ThresholdLevel::Warning)]);
+}
+
+/// Test sampling for a fixed window
+#[test]
+fn test_sample_for() {
+    println!("\n=== Running test_sample_for ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let stats = monitor.sample_for(Duration::from_millis(200)).unwrap();
+    println!("Samples: {}", stats.total.power.count);
+    assert!(stats.total.power.count > 0);
+    assert!(!monitor.is_sampling().unwrap());
+
+    // A running capture is left alone
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(100));
+    assert!(matches!(
+        monitor.sample_for(Duration::from_millis(10)),
+        Err(Error::AlreadyRunning)
+    ));
+    assert!(monitor.is_sampling().unwrap());
+    assert!(monitor.get_statistics().unwrap().total.power.count > 0);
+    monitor.stop_sampling().unwrap();
+}
+
+/// Test measuring a closure, including one that panics
+#[test]
+fn test_measure() {
+    println!("\n=== Running test_measure ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+
+    let (value, stats) = monitor
+        .measure(|| {
+            thread::sleep(Duration::from_millis(200));
+            42
+        })
+        .unwrap();
+    assert_eq!(value, 42);
+    assert!(stats.total.power.count > 0);
+    assert!(!monitor.is_sampling().unwrap());
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        monitor.measure(|| panic!("workload failed")).unwrap();
+    }));
+    assert!(result.is_err());
+    assert!(!monitor.is_sampling().unwrap());
+}
+
+/// Test that sample timestamps increase while sampling
+#[test]
+fn test_sample_timestamps() {
+    println!("\n=== Running test_sample_timestamps ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+
+    thread::sleep(Duration::from_millis(50));
+    let first = monitor.get_latest_data().unwrap().total.timestamp_ns;
+    thread::sleep(Duration::from_millis(50));
+    let second = monitor.get_latest_data().unwrap().total.timestamp_ns;
+    monitor.stop_sampling().unwrap();
+
+    println!("Timestamps: {} ns, {} ns", first, second);
+    assert!(first > 0);
+    assert!(second > first);
+    let data = monitor.get_latest_data().unwrap();
+    for sensor in data.sensors().iter().filter(|s| s.online) {
+        assert!(sensor.timestamp_ns <= data.total.timestamp_ns);
+    }
+}
+
+/// Test that the history retains the most recent samples
+#[test]
+fn test_history() {
+    println!("\n=== Running test_history ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(monitor.get_history().unwrap().is_empty());
+
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.set_history_capacity(5).unwrap();
+    assert_eq!(monitor.history_capacity(), 5);
+
+    // Three samples fit
+    monitor.start_sampling().unwrap();
+    while monitor.get_statistics().unwrap().total.power.count < 3 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    monitor.stop_sampling().unwrap();
+    let count = monitor.get_statistics().unwrap().total.power.count as usize;
+    let history = monitor.get_history().unwrap();
+    assert_eq!(history.len(), count.min(5));
+    assert_eq!(history[0].sensors.len(), monitor.get_sensor_count().unwrap() as usize);
+
+    // Overflow drops the oldest samples
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    monitor.stop_sampling().unwrap();
+    let history = monitor.get_history().unwrap();
+    assert_eq!(history.len(), 5);
+    for pair in history.windows(2) {
+        assert!(pair[0].total.timestamp_ns < pair[1].total.timestamp_ns);
+    }
+
+    monitor.set_history_capacity(0).unwrap();
+    assert!(monitor.get_history().unwrap().is_empty());
+}
+
+/// Test power percentiles over the history
+#[test]
+fn test_percentiles() {
+    println!("\n=== Running test_percentiles ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(matches!(
+        monitor.get_percentiles("TOTAL_POWER", &[0.5]),
+        Err(Error::NotRunning)
+    ));
+
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.set_history_capacity(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    monitor.stop_sampling().unwrap();
+
+    let ps = monitor.get_percentiles("TOTAL_POWER", &[-1.0, 0.5, 0.95, 0.99, 2.0]).unwrap();
+    println!("Percentiles: {:?}", ps);
+    let stats = monitor.get_statistics().unwrap();
+    let total = stats.sensors().iter().find(|s| s.name() == "TOTAL_POWER").unwrap();
+    assert_eq!(ps[0], total.power.min);
+    assert_eq!(ps[4], total.power.max);
+    for pair in ps.windows(2) {
+        assert!(pair[0] <= pair[1]);
+    }
+
+    assert!(matches!(
+        monitor.get_percentiles("NO_SUCH_SENSOR", &[0.5]),
+        Err(Error::NoSensors)
+    ));
+    assert!(matches!(
+        monitor.get_percentiles("TOTAL_POWER", &[f64::NAN]),
+        Err(Error::InvalidArgument)
+    ));
+}
+
+/// Test that the sample stream yields samples and ends when sampling stops
+#[cfg(feature = "tokio")]
+#[test]
+fn test_sample_stream() {
+    use futures_util::StreamExt;
+
+    println!("\n=== Running test_sample_stream ===");
+    let monitor = PowerMonitor::new().unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    let mut stream = monitor.sample_stream();
+    runtime.block_on(async {
+        assert!(matches!(stream.next().await, Some(Err(Error::NotRunning))));
+        assert!(stream.next().await.is_none());
+    });
+
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    let mut stream = monitor.sample_stream();
+    runtime.block_on(async {
+        for _ in 0..3 {
+            let data = stream.next().await.unwrap().unwrap();
+            assert_eq!(data.sensors.len(), monitor.get_sensor_count().unwrap() as usize);
+        }
+        monitor.stop_sampling().unwrap();
+        while let Some(item) = stream.next().await {
+            item.unwrap();
+        }
+    });
+}
+
+/// Test stepping through consecutive samples at 10 Hz
+#[test]
+fn test_wait_for_next_sample() {
+    println!("\n=== Running test_wait_for_next_sample ===");
+    let monitor = PowerMonitor::new().unwrap();
+    assert!(matches!(
+        monitor.wait_for_next_sample(Duration::from_millis(10)),
+        Err(Error::NotRunning)
+    ));
+
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    let first = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let start = std::time::Instant::now();
+    let second = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let interval = start.elapsed();
+    println!("Interval: {:?}", interval);
+    assert!(interval >= Duration::from_millis(50));
+    assert!(interval <= Duration::from_millis(250));
+    assert!(second.total.timestamp_ns > first.total.timestamp_ns);
+
+    assert!(matches!(
+        monitor.wait_for_next_sample(Duration::from_millis(1)),
+        Err(Error::Timeout)
+    ));
+    monitor.stop_sampling().unwrap();
+}
+
+/// Test reading and resetting the statistics of a single sensor
+#[test]
+fn test_statistics_for_sensor() {
+    println!("\n=== Running test_statistics_for_sensor ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(200));
+    monitor.stop_sampling().unwrap();
+
+    let names = monitor.sensor_names().unwrap();
+    let first = &names[0];
+    let before = monitor.get_statistics_for_sensor(first).unwrap();
+    assert_eq!(before.name(), first.as_str());
+    assert!(before.power.count > 0);
+    let counts: Vec<u64> = monitor.get_statistics().unwrap().sensors().iter().map(|s| s.power.count).collect();
+
+    monitor.reset_statistics_for_sensor(first).unwrap();
+    assert_eq!(monitor.get_statistics_for_sensor(first).unwrap().power.count, 0);
+    let stats = monitor.get_statistics().unwrap();
+    for (sensor, count) in stats.sensors().iter().zip(counts) {
+        if sensor.name() != first.as_str() {
+            assert_eq!(sensor.power.count, count, "{} was reset", sensor.name());
+        }
+    }
+    assert!(stats.total.power.count > 0);
+
+    assert!(matches!(
+        monitor.get_statistics_for_sensor("NO_SUCH_SENSOR"),
+        Err(Error::NoSensors)
+    ));
+    assert!(matches!(
+        monitor.reset_statistics_for_sensor("NO_SUCH_SENSOR"),
+        Err(Error::NoSensors)
+    ));
+
+    // The virtual totals are only reset together with their inputs
+    let total = monitor.get_statistics_for_sensor("TOTAL_POWER").unwrap().power.count;
+    for name in ["TOTAL_POWER", "PS_TOTAL_POWER", "PL_TOTAL_POWER"] {
+        assert!(matches!(monitor.reset_statistics_for_sensor(name), Err(Error::InvalidArgument)));
+    }
+    assert_eq!(monitor.get_statistics_for_sensor("TOTAL_POWER").unwrap().power.count, total);
+}
+
+/// Test building a monitor with a sensor allowlist
+#[test]
+fn test_config_sensor_allowlist() {
+    println!("\n=== Running test_config_sensor_allowlist ===");
+    let monitor = PowerMonitorConfig::new()
+        .frequency_hz(100)
+        .history_capacity(10)
+        .sensors(&["TOTAL_POWER"])
+        .build()
+        .unwrap();
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 100);
+    assert_eq!(monitor.history_capacity(), 10);
+    let data = monitor.get_latest_owned().unwrap();
+    assert_eq!(data.sensors.len(), 1);
+    assert_eq!(data.sensors[0].name(), "TOTAL_POWER");
+    assert_eq!(monitor.get_statistics_owned().unwrap().sensors.len(), 1);
+
+    assert!(matches!(
+        PowerMonitorConfig::new().sensors(&["NO_SUCH_SENSOR"]).build(),
+        Err(Error::NoSensors)
+    ));
+}
+
+/// Test that `PowerMonitor::new` matches the default configuration
+#[test]
+fn test_new_matches_default_config() {
+    println!("\n=== Running test_new_matches_default_config ===");
+    let configured = PowerMonitorConfig::default().build().unwrap().export_config();
+    assert_eq!(PowerMonitor::new().unwrap().export_config(), configured);
+    assert_eq!(PowerMonitorBuilder::new().build().unwrap().export_config(), configured);
+    assert!(configured.reset_on_start);
+}
+
+/// Test changing the sampling frequency while sampling is active
+#[test]
+fn test_sampling_frequency_while_running() {
+    println!("\n=== Running test_sampling_frequency_while_running ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    let first = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let second = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let slow_ns = second.total.timestamp_ns - first.total.timestamp_ns;
+    println!("Interval at 10 Hz: {} ns", slow_ns);
+    assert!(slow_ns >= 50_000_000);
+
+    // The period already in progress is shortened to the new frequency
+    monitor.set_sampling_frequency(100).unwrap();
+    assert!(matches!(monitor.set_sampling_frequency(0), Err(Error::InvalidFrequency)));
+    assert_eq!(monitor.get_sampling_frequency().unwrap(), 100);
+    let third = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let fourth = monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    let fast_ns = fourth.total.timestamp_ns - third.total.timestamp_ns;
+    println!("Interval at 100 Hz: {} ns", fast_ns);
+    assert!(third.total.timestamp_ns - second.total.timestamp_ns < 80_000_000);
+    assert!(fast_ns < 50_000_000);
+    monitor.stop_sampling().unwrap();
+}
+
+/// Test fetching a single sensor's latest reading by name
+#[test]
+fn test_get_sensor_by_name() {
+    println!("\n=== Running test_get_sensor_by_name ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    monitor.start_sampling().unwrap();
+    monitor.wait_for_next_sample(Duration::from_secs(1)).unwrap();
+    monitor.stop_sampling().unwrap();
+
+    let data = monitor.get_latest_data().unwrap();
+    let expected = data.sensors()[0];
+    let name = expected.name().into_owned();
+    let sensor = monitor.get_sensor_by_name(&name).unwrap();
+    println!("{}: {:.3} W", name, sensor.power);
+    assert_eq!(sensor.name(), expected.name());
+    assert_eq!(sensor.power, expected.power);
+    assert_eq!(sensor.timestamp_ns, expected.timestamp_ns);
+
+    assert!(matches!(monitor.get_sensor_by_name("NO_SUCH_RAIL"), Err(Error::NoSensors)));
+    assert!(matches!(monitor.get_sensor_by_name(""), Err(Error::NoSensors)));
+}
+
+/// Test sampling for a fixed duration with an automatic stop
+#[test]
+fn test_start_sampling_for() {
+    println!("\n=== Running test_start_sampling_for ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(100).unwrap();
+    assert!(matches!(monitor.start_sampling_for(Duration::ZERO), Err(Error::InvalidArgument)));
+
+    let start = std::time::Instant::now();
+    monitor.start_sampling_for(Duration::from_millis(200)).unwrap();
+    assert!(start.elapsed() < Duration::from_millis(100));
+    assert!(monitor.is_sampling().unwrap());
+    assert!(matches!(monitor.start_sampling(), Err(Error::AlreadyRunning)));
+
+    thread::sleep(Duration::from_millis(350));
+    assert!(!monitor.is_sampling().unwrap());
+    assert!(!monitor.is_sampling_uncached().unwrap());
+    let count = monitor.get_statistics().unwrap().total.power.count;
+    println!("Samples: {}", count);
+    assert!(count > 0 && count <= 25);
+    assert!(matches!(monitor.stop_sampling(), Err(Error::NotRunning)));
+
+    // Stopping before the deadline cancels it; a new capture starts cleanly
+    monitor.start_sampling_for(Duration::from_millis(200)).unwrap();
+    monitor.stop_sampling().unwrap();
+    assert!(!monitor.is_sampling().unwrap());
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(300));
+    assert!(monitor.is_sampling().unwrap());
+    monitor.stop_sampling().unwrap();
+}
+
+/// Test the statistics of the achieved sampling interval
+#[test]
+fn test_jitter_stats() {
+    println!("\n=== Running test_jitter_stats ===");
+    let monitor = PowerMonitor::new().unwrap();
+    monitor.set_sampling_frequency(10).unwrap();
+    monitor.start_sampling().unwrap();
+    thread::sleep(Duration::from_millis(650));
+    monitor.stop_sampling().unwrap();
+
+    let jitter = monitor.get_jitter_stats().unwrap();
+    let samples = monitor.get_statistics().unwrap().total.power.count;
+    println!(
+        "Intervals: {} (min {:.0} us, max {:.0} us, avg {:.0} us)",
+        jitter.count, jitter.min, jitter.max, jitter.avg
+    );
+    assert_eq!(jitter.count, samples - 1);
+    assert!(jitter.min <= jitter.avg && jitter.avg <= jitter.max);
+    assert!((jitter.avg - 100_000.0).abs() < 20_000.0);
+
+    monitor.reset_statistics().unwrap();
+    assert_eq!(monitor.get_jitter_stats().unwrap().count, 0);
+}
@@ -0,0 +1,59 @@
+//! Tests for threshold levels that do not require sensor hardware
+
+use xlnpwmon::{SensorData, ThresholdLevel};
+
+/// Builds a synthetic sensor reading with the given power and thresholds
+fn sensor(power: f64, warning: f64, critical: f64) -> SensorData {
+    SensorData {
+        power,
+        warning_threshold: warning,
+        critical_threshold: critical,
+        ..SensorData::default()
+    }
+}
+
+/// Test the level reached for readings around the thresholds
+#[test]
+fn test_threshold_level() {
+    println!("\n=== Running test_threshold_level ===");
+    assert_eq!(sensor(1.0, 2.0, 4.0).threshold_level(), None);
+    assert_eq!(sensor(2.0, 2.0, 4.0).threshold_level(), Some(ThresholdLevel::Warning));
+    assert_eq!(sensor(3.9, 2.0, 4.0).threshold_level(), Some(ThresholdLevel::Warning));
+    assert_eq!(sensor(4.0, 2.0, 4.0).threshold_level(), Some(ThresholdLevel::Critical));
+    assert!(ThresholdLevel::Critical > ThresholdLevel::Warning);
+}
+
+/// Test that unset thresholds are ignored
+#[test]
+fn test_unset_thresholds() {
+    println!("\n=== Running test_unset_thresholds ===");
+    assert_eq!(sensor(5.0, 0.0, 0.0).threshold_level(), None);
+    assert_eq!(sensor(5.0, 0.0, 4.0).threshold_level(), Some(ThresholdLevel::Critical));
+    assert_eq!(sensor(5.0, 2.0, 0.0).threshold_level(), Some(ThresholdLevel::Warning));
+}
+
+/// Test the individual threshold checks below, between and above the
+/// thresholds
+#[test]
+fn test_is_over_thresholds() {
+    println!("\n=== Running test_is_over_thresholds ===");
+    let below = sensor(1.0, 2.0, 4.0);
+    assert!(!below.is_over_warning());
+    assert!(!below.is_over_critical());
+
+    let between = sensor(3.0, 2.0, 4.0);
+    assert!(between.is_over_warning());
+    assert!(!between.is_over_critical());
+
+    let above = sensor(5.0, 2.0, 4.0);
+    assert!(above.is_over_warning());
+    assert!(above.is_over_critical());
+
+    // Reaching a threshold exactly counts, like in `threshold_level`
+    assert!(sensor(2.0, 2.0, 4.0).is_over_warning());
+    assert!(sensor(4.0, 2.0, 4.0).is_over_critical());
+
+    let unset = sensor(5.0, 0.0, 0.0);
+    assert!(!unset.is_over_warning());
+    assert!(!unset.is_over_critical());
+}
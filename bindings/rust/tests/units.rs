@@ -0,0 +1,74 @@
+//! Tests for the unit newtypes
+
+use std::time::Duration;
+
+use xlnpwmon::{Amps, Joules, SensorData, Volts, Watts, MIN_RESISTANCE_CURRENT};
+
+/// Test that volts times amps gives watts and the inverse relations hold
+#[test]
+fn test_power_relation() {
+    let power = Volts(0.85) * Amps(4.0);
+    assert_eq!(power, Watts(3.4));
+    assert_eq!(Amps(4.0) * Volts(0.85), power);
+    assert_eq!(Watts(3.4) / Volts(0.85), Amps(4.0));
+    assert_eq!(Watts(3.4) / Amps(4.0), Volts(0.85));
+
+    let energy = Watts(2.0) * Duration::from_millis(1500);
+    assert_eq!(energy, Joules(3.0));
+    assert_eq!(energy / Duration::from_secs(3), Watts(1.0));
+
+    assert_eq!(Watts(1.5) + Watts(2.0), Watts(3.5));
+    assert_eq!(Watts(1.5) * 2.0, Watts(3.0));
+    assert_eq!(f64::from(Volts::from(1.2)), 1.2);
+    assert!(Watts(1.0) < Watts(2.0));
+}
+
+/// Test that values are formatted with their unit symbol
+#[test]
+fn test_unit_display() {
+    assert_eq!(Watts(1.23).to_string(), "1.23 W");
+    assert_eq!(format!("{:.1}", Volts(0.851)), "0.9 V");
+    assert_eq!(Amps(2.0).to_string(), "2 A");
+    assert_eq!(format!("{:.3}", Joules(10.0)), "10.000 J");
+}
+
+/// Test the typed accessors of a sensor reading
+#[test]
+fn test_sensor_accessors() {
+    let sensor = SensorData {
+        voltage: 0.75,
+        current: 2.0,
+        power: 1.5,
+        ..SensorData::default()
+    };
+    assert_eq!(sensor.voltage_v(), Volts(0.75));
+    assert_eq!(sensor.current_a(), Amps(2.0));
+    assert_eq!(sensor.power_w(), Watts(1.5));
+    assert_eq!(sensor.voltage_v() * sensor.current_a(), sensor.power_w());
+}
+
+/// Test the derived resistance and the power consistency check
+#[test]
+fn test_derived_metrics() {
+    let mut sensor = SensorData {
+        voltage: 0.75,
+        current: 2.0,
+        power: 1.5,
+        ..SensorData::default()
+    };
+    assert_eq!(sensor.resistance_ohms(), Some(0.375));
+    assert_eq!(sensor.computed_power(), 1.5);
+    assert_eq!(sensor.power_discrepancy(), 0.0);
+
+    sensor.power = 1.75;
+    assert_eq!(sensor.power_discrepancy(), 0.25);
+
+    sensor.current = 0.0;
+    assert_eq!(sensor.resistance_ohms(), None);
+    assert_eq!(sensor.computed_power(), 0.0);
+    assert_eq!(sensor.power_discrepancy(), 1.75);
+    sensor.current = MIN_RESISTANCE_CURRENT / 2.0;
+    assert_eq!(sensor.resistance_ohms(), None);
+    sensor.current = -0.5;
+    assert_eq!(sensor.resistance_ohms(), Some(-1.5));
+}
@@ -18,6 +18,14 @@
 extern "C" {
 #endif
 
+/**
+ * @brief Library version the header belongs to
+ */
+#define XLNPWMON_VERSION_MAJOR 0
+#define XLNPWMON_VERSION_MINOR 0
+#define XLNPWMON_VERSION_PATCH 3
+#define XLNPWMON_VERSION "0.0.3"
+
 /**
  * @brief Error codes returned by library functions
  */
@@ -31,9 +39,21 @@ typedef enum {
     PM_ERROR_NO_SENSORS = -6,        /**< No power sensors found */
     PM_ERROR_FILE_ACCESS = -7,       /**< Error accessing sensor files */
     PM_ERROR_MEMORY = -8,            /**< Memory allocation error */
-    PM_ERROR_THREAD = -9             /**< Thread creation/management error */
+    PM_ERROR_THREAD = -9,            /**< Thread creation/management error */
+    PM_ERROR_INVALID_ARGUMENT = -10, /**< Invalid argument */
+    PM_ERROR_TIMEOUT = -11           /**< Timed out waiting */
 } pm_error_t;
 
+/**
+ * @brief Size of a sensor name buffer, including the terminating NUL
+ */
+#define PM_SENSOR_NAME_LEN 64
+
+/**
+ * @brief Sampling frequency in Hz that pm_init() and its variants start with
+ */
+#define PM_DEFAULT_SAMPLING_FREQUENCY_HZ 10
+
 /**
  * @brief Sensor types
  */
@@ -56,17 +76,26 @@ typedef struct {
     char status[32];                 /**< Status string (if available) */
     double warning_threshold;        /**< Warning threshold in watts */
     double critical_threshold;       /**< Critical threshold in watts */
+    uint64_t timestamp_ns;           /**< Monotonic time of the reading in ns since pm_init() */
 } pm_sensor_data_t;
 
 /**
  * @brief Statistical data
+ *
+ * total is accumulated with compensated (Neumaier) summation, so it does
+ * not drift with the number of samples: for the non-negative readings of a
+ * power sensor its relative error stays within a few units in the last
+ * place of a double for any count below about 10^15 samples, e.g. years at
+ * 10 kHz. avg is derived from it.
  */
 typedef struct {
     double min;                      /**< Minimum value */
     double max;                      /**< Maximum value */
     double avg;                      /**< Average value */
-    double total;                    /**< Sum of all samples */
+    double total;                    /**< Sum of all samples, see below */
     uint64_t count;                  /**< Number of samples */
+    double sum_sq_dev;               /**< Sum of squared deviations from the average */
+    double stddev;                   /**< Population standard deviation */
 } pm_stats_t;
 
 /**
@@ -115,22 +144,90 @@ typedef struct {
     pm_stats_t total_power;          /**< Total power statistics */
 } pm_power_summary_stats_t;
 
+/**
+ * @brief Maximum length of a device id, including the NUL terminator
+ */
+#define PM_DEVICE_ID_LEN 64
+
+/**
+ * @brief A group of sensors sharing one parent device, usually an I2C bus
+ */
+typedef struct {
+    char id[PM_DEVICE_ID_LEN];       /**< Device id, e.g. "i2c-3" */
+    char path[256];                  /**< Resolved sysfs path of the device */
+    int sensor_count;                /**< Number of physical sensors it carries */
+} pm_device_info_t;
+
 /**
  * @brief Library handle
+ *
+ * All functions taking a handle may be called concurrently from several
+ * threads, except pm_cleanup(), which must not race with any other call on
+ * the same handle. Starting and stopping are serialized internally.
  */
 typedef struct pm_handle_s* pm_handle_t;
 
+/**
+ * @brief Callback invoked by the sampling thread after every sample
+ *
+ * @param data Snapshot of the sample. The sensors pointer refers to a
+ *             buffer that is only valid for the duration of the call.
+ * @param user_data The pointer passed to pm_set_sample_callback()
+ */
+typedef void (*pm_sample_callback_t)(const pm_power_data_t* data, void* user_data);
+
 /**
  * @brief Initialize the power monitor
  *
  * This function discovers power sensors on the system and initializes
- * the power monitor library.
+ * the power monitor library. Sensors are looked up below
+ * $XLNPWMON_SYSFS_ROOT/class/hwmon if that variable is set, and below
+ * /sys/class/hwmon otherwise. The handle samples at
+ * PM_DEFAULT_SAMPLING_FREQUENCY_HZ until another frequency is set.
  *
  * @param[out] handle Pointer to store the library handle
- * @return Error code
+ * @return Error code; PM_ERROR_NO_SENSORS if no sensor was found, or
+ *         PM_ERROR_FILE_ACCESS if none was found because sysfs could not be
+ *         read for lack of permission. pm_get_last_file_error() tells which
+ *         path failed.
  */
 pm_error_t pm_init(pm_handle_t* handle);
 
+/**
+ * @brief List the devices that carry power sensors
+ *
+ * Sensors are grouped by the sysfs parent of their hwmon device, so every
+ * I2C bus with monitored rails is reported as one device.
+ *
+ * @param[out] devices Array to store the devices
+ * @param[inout] count On input: size of the array; On output: number of
+ *                     devices copied
+ * @return Error code
+ */
+pm_error_t pm_enumerate_devices(pm_device_info_t* devices, int* count);
+
+/**
+ * @brief Initialize the power monitor for the sensors of a single device
+ *
+ * @param[out] handle Pointer to store the library handle
+ * @param device_id Id reported by pm_enumerate_devices(), or NULL for all
+ *                  sensors like pm_init()
+ * @return Error code, PM_ERROR_NO_SENSORS if the device has no sensors
+ */
+pm_error_t pm_init_device(pm_handle_t* handle, const char* device_id);
+
+/**
+ * @brief Initialize the power monitor from the sensors below a sysfs root
+ *
+ * Reads <sysfs_root>/class/hwmon instead of /sys/class/hwmon, e.g. to
+ * parse a fixture tree in tests. Ignored when built with XLNPWMON_MOCK.
+ *
+ * @param[out] handle Pointer to store the library handle
+ * @param sysfs_root Directory standing in for /sys
+ * @return Error code
+ */
+pm_error_t pm_init_sysfs(pm_handle_t* handle, const char* sysfs_root);
+
 /**
  * @brief Clean up resources
  *
@@ -145,12 +242,33 @@ pm_error_t pm_cleanup(pm_handle_t handle);
 /**
  * @brief Set the sampling frequency
  *
+ * May be called while sampling is active; the sampling thread picks up the
+ * new frequency immediately, starting with the period in progress.
+ *
  * @param handle Library handle
- * @param frequency_hz Sampling frequency in Hz (must be > 0)
- * @return Error code
+ * @param frequency_hz Sampling frequency in Hz, within the range reported by
+ *                     pm_get_frequency_limits()
+ * @return Error code, PM_ERROR_INVALID_FREQUENCY if out of range
  */
 pm_error_t pm_set_sampling_frequency(pm_handle_t handle, int frequency_hz);
 
+/**
+ * @brief Set the sampling frequency and get the one it replaces
+ *
+ * Like pm_set_sampling_frequency(), but reads the previous frequency under
+ * the same lock, so a caller restoring it later cannot miss a change made
+ * in between by another thread.
+ *
+ * @param handle Library handle
+ * @param frequency_hz Sampling frequency in Hz, within the range reported by
+ *                     pm_get_frequency_limits()
+ * @param[out] previous_hz Pointer to store the frequency before the change,
+ *                         as pm_get_sampling_frequency() reported it
+ * @return Error code, PM_ERROR_INVALID_FREQUENCY if out of range, in which
+ *         case nothing is stored
+ */
+pm_error_t pm_swap_sampling_frequency(pm_handle_t handle, int frequency_hz, int* previous_hz);
+
 /**
  * @brief Get the current sampling frequency
  *
@@ -160,6 +278,68 @@ pm_error_t pm_set_sampling_frequency(pm_handle_t handle, int frequency_hz);
  */
 pm_error_t pm_get_sampling_frequency(pm_handle_t handle, int* frequency_hz);
 
+/**
+ * @brief Get the range of accepted sampling frequencies
+ *
+ * @param handle Library handle
+ * @param[out] min_hz Pointer to store the lowest accepted frequency
+ * @param[out] max_hz Pointer to store the highest accepted frequency
+ * @return Error code
+ */
+pm_error_t pm_get_frequency_limits(pm_handle_t handle, int* min_hz, int* max_hz);
+
+/**
+ * @brief Set the time between samples
+ *
+ * Alternative to pm_set_sampling_frequency() for periods that are not a
+ * whole number of Hz. pm_get_sampling_frequency() then reports the nearest
+ * whole rate.
+ *
+ * @param handle Library handle
+ * @param interval_ns Sampling period in nanoseconds, corresponding to a rate
+ *                    within the range reported by pm_get_frequency_limits()
+ * @return Error code, PM_ERROR_INVALID_FREQUENCY if out of range
+ */
+pm_error_t pm_set_sampling_interval(pm_handle_t handle, uint64_t interval_ns);
+
+/**
+ * @brief Get the time between samples
+ *
+ * @param handle Library handle
+ * @param[out] interval_ns Pointer to store the period in nanoseconds
+ * @return Error code
+ */
+pm_error_t pm_get_sampling_interval(pm_handle_t handle, uint64_t* interval_ns);
+
+/**
+ * @brief Restrict the sampling thread to a set of CPUs
+ *
+ * Keeps the sampling thread off the cores running the measured workload.
+ * Applies immediately if sampling is active and to every later run.
+ * Linux only, via pthread_setaffinity_np().
+ *
+ * @param handle Library handle
+ * @param cpus CPU numbers the thread may run on
+ * @param count Number of entries in cpus, 0 to allow every CPU again
+ * @return Error code, PM_ERROR_THREAD if the kernel rejects the set (e.g. no
+ *         listed CPU is online)
+ */
+pm_error_t pm_set_sampling_thread_affinity(pm_handle_t handle, const int* cpus, int count);
+
+/**
+ * @brief Set the real-time priority of the sampling thread
+ *
+ * A positive priority runs the thread under SCHED_FIFO, which usually needs
+ * CAP_SYS_NICE or root; 0 restores the default SCHED_OTHER policy. Applies
+ * immediately if sampling is active and to every later run.
+ *
+ * @param handle Library handle
+ * @param priority SCHED_FIFO priority from 1 to sched_get_priority_max(), or 0
+ * @return Error code, PM_ERROR_THREAD if the policy cannot be applied, in
+ *         which case pm_start_sampling() fails the same way
+ */
+pm_error_t pm_set_sampling_thread_priority(pm_handle_t handle, int priority);
+
 /**
  * @brief Start sampling
  *
@@ -171,6 +351,20 @@ pm_error_t pm_get_sampling_frequency(pm_handle_t handle, int* frequency_hz);
  */
 pm_error_t pm_start_sampling(pm_handle_t handle);
 
+/**
+ * @brief Start sampling for a fixed duration
+ *
+ * Like pm_start_sampling(), but the sampling thread stops by itself once
+ * duration_ms has elapsed, after which pm_is_sampling() reports false. The
+ * call does not block. Stopping earlier with pm_stop_sampling() cancels
+ * the deadline; calling it after the deadline returns PM_ERROR_NOT_RUNNING.
+ *
+ * @param handle Library handle
+ * @param duration_ms Sampling duration in milliseconds (must be > 0)
+ * @return Error code
+ */
+pm_error_t pm_start_sampling_for(pm_handle_t handle, int duration_ms);
+
 /**
  * @brief Stop sampling
  *
@@ -190,6 +384,40 @@ pm_error_t pm_stop_sampling(pm_handle_t handle);
  */
 pm_error_t pm_is_sampling(pm_handle_t handle, bool* is_sampling);
 
+/**
+ * @brief Pause sampling
+ *
+ * The sampling thread stops taking samples but keeps running, and the
+ * statistics and history are kept, so pm_resume_sampling() continues the
+ * same run. No sample is published after the call returns. pm_is_sampling()
+ * keeps reporting true while paused. The deadline of pm_start_sampling_for()
+ * keeps running during a pause. Pausing while paused has no effect.
+ *
+ * @param handle Library handle
+ * @return Error code, PM_ERROR_NOT_RUNNING if sampling is stopped
+ */
+pm_error_t pm_pause_sampling(pm_handle_t handle);
+
+/**
+ * @brief Resume paused sampling
+ *
+ * The pause is not counted as a sampling interval. Resuming while not
+ * paused has no effect.
+ *
+ * @param handle Library handle
+ * @return Error code, PM_ERROR_NOT_RUNNING if sampling is stopped
+ */
+pm_error_t pm_resume_sampling(pm_handle_t handle);
+
+/**
+ * @brief Check if sampling is paused
+ *
+ * @param handle Library handle
+ * @param[out] paused Set to true if sampling is active and paused
+ * @return Error code
+ */
+pm_error_t pm_is_paused(pm_handle_t handle, bool* paused);
+
 /**
  * @brief Get the latest power data
  *
@@ -197,7 +425,14 @@ pm_error_t pm_is_sampling(pm_handle_t handle, bool* is_sampling);
  * in the output structure to point to the library's internal buffer.
  * The caller must NOT free or modify the sensors pointer, as it points to
  * internal memory managed by the library. The pointer is only valid until
- * the next call to this function or pm_cleanup().
+ * the next call to this function or pm_cleanup(). While sampling, the
+ * sampling thread rewrites the memory it points to; use
+ * pm_copy_latest_data() to read a consistent sample.
+ *
+ * Sensors are ordered by ascending hwmon index, followed by the virtual
+ * PS_TOTAL_POWER, PL_TOTAL_POWER and TOTAL_POWER sensors. The order is
+ * fixed for the lifetime of the handle and identical to the order used by
+ * pm_get_statistics() and pm_get_sensor_names().
  *
  * @param handle Library handle
  * @param[out] data Pointer to store the data. The sensors pointer in this
@@ -206,6 +441,72 @@ pm_error_t pm_is_sampling(pm_handle_t handle, bool* is_sampling);
  */
 pm_error_t pm_get_latest_data(pm_handle_t handle, pm_power_data_t* data);
 
+/**
+ * @brief Copy the latest power data into caller storage
+ *
+ * Copies the same data as pm_get_latest_data() while holding the lock the
+ * sampling thread publishes samples under, so the copy is one consistent
+ * sample and safe to read while sampling.
+ *
+ * @param handle Library handle
+ * @param[out] buf Array receiving the data of every sensor, in the order
+ *                 of pm_get_latest_data()
+ * @param len Number of entries of buf, at least pm_get_sensor_count()
+ * @param[out] total Receives the legacy total
+ * @return Error code, PM_ERROR_INVALID_ARGUMENT if buf is too small
+ */
+pm_error_t pm_copy_latest_data(pm_handle_t handle, pm_sensor_data_t* buf, int len, pm_sensor_data_t* total);
+
+/**
+ * @brief Get the latest power data, telling whether a sample has been taken
+ *
+ * Reports whether the sampling thread has published a sample since
+ * sampling was last started and, if so, copies it into a caller-provided
+ * buffer while holding the data lock. Until then, the buffer is left as it
+ * was. After sampling stops, the last sample of the run stays available.
+ *
+ * @param handle Library handle
+ * @param[in,out] data Caller-provided buffer, as for pm_read_once()
+ * @param[out] available Set to true once the current or last run has
+ *                       published a sample
+ * @return Error code
+ */
+pm_error_t pm_try_get_latest_data(pm_handle_t handle, pm_power_data_t* data, bool* available);
+
+/**
+ * @brief Read all sensors once, synchronously
+ *
+ * Performs a single read of every sensor on the calling thread, without
+ * the sampling thread. It works whether or not sampling is active, and
+ * neither the latest data nor the statistics of the handle are changed.
+ * The call blocks for the duration of the sensor file I/O.
+ *
+ * @param handle Library handle
+ * @param[in,out] data On input, sensors must point to a caller-provided
+ *                     array and sensor_count must hold its capacity. On
+ *                     output, sensor_count holds the number of sensors; if
+ *                     the capacity was too small, nothing else is written
+ *                     and PM_ERROR_INVALID_ARGUMENT is returned.
+ * @return Error code
+ */
+pm_error_t pm_read_once(pm_handle_t handle, pm_power_data_t* data);
+
+/**
+ * @brief Wait for the sampling thread to publish the next sample
+ *
+ * Blocks until a sample taken after the call is published, then copies it.
+ * Successive calls therefore never return the same sample twice, although
+ * samples published between calls are skipped.
+ *
+ * @param handle Library handle
+ * @param[in,out] data Caller-provided buffer, as for pm_read_once()
+ * @param timeout_ms Maximum time to wait in milliseconds
+ * @return Error code; PM_ERROR_TIMEOUT if no sample was published in time,
+ *         PM_ERROR_NOT_RUNNING if sampling is not active or is stopped
+ *         while waiting
+ */
+pm_error_t pm_wait_for_next_sample(pm_handle_t handle, pm_power_data_t* data, int timeout_ms);
+
 /**
  * @brief Get the power statistics
  *
@@ -213,7 +514,12 @@ pm_error_t pm_get_latest_data(pm_handle_t handle, pm_power_data_t* data);
  * in the output structure to point to the library's internal buffer.
  * The caller must NOT free or modify the sensors pointer, as it points to
  * internal memory managed by the library. The pointer is only valid until
- * the next call to this function or pm_cleanup().
+ * the next call to this function or pm_cleanup(). While sampling, the
+ * sampling thread rewrites the memory it points to; use
+ * pm_copy_statistics() to read consistent statistics.
+ *
+ * Entry i of the statistics array describes the same sensor as entry i of
+ * the data array returned by pm_get_latest_data().
  *
  * @param handle Library handle
  * @param[out] stats Pointer to store the statistics. The sensors pointer in this
@@ -222,6 +528,22 @@ pm_error_t pm_get_latest_data(pm_handle_t handle, pm_power_data_t* data);
  */
 pm_error_t pm_get_statistics(pm_handle_t handle, pm_power_stats_t* stats);
 
+/**
+ * @brief Copy the power statistics into caller storage
+ *
+ * Copies the same statistics as pm_get_statistics() while holding the lock
+ * the sampling thread updates them under, so the copy is consistent and
+ * safe to read while sampling.
+ *
+ * @param handle Library handle
+ * @param[out] buf Array receiving the statistics of every sensor, in the
+ *                 order of pm_get_statistics()
+ * @param len Number of entries of buf, at least pm_get_sensor_count()
+ * @param[out] total Receives the total statistics
+ * @return Error code, PM_ERROR_INVALID_ARGUMENT if buf is too small
+ */
+pm_error_t pm_copy_statistics(pm_handle_t handle, pm_sensor_stats_t* buf, int len, pm_sensor_stats_t* total);
+
 /**
  * @brief Reset the statistics
  *
@@ -232,6 +554,60 @@ pm_error_t pm_get_statistics(pm_handle_t handle, pm_power_stats_t* stats);
  */
 pm_error_t pm_reset_statistics(pm_handle_t handle);
 
+/**
+ * @brief Get the statistics of a single sensor
+ *
+ * @param handle Library handle
+ * @param sensor_name Name of the sensor, as returned by pm_get_sensor_names()
+ * @param[out] stats Pointer to store a copy of the statistics
+ * @return Error code; PM_ERROR_NO_SENSORS if no sensor has the given name
+ */
+pm_error_t pm_get_sensor_statistics(pm_handle_t handle, const char* sensor_name, pm_sensor_stats_t* stats);
+
+/**
+ * @brief Reset the statistics of a single sensor
+ *
+ * The statistics of all other sensors, including the virtual totals, are
+ * left untouched. If several sensors share the name, all of them are reset.
+ * The virtual TOTAL_POWER, PS_TOTAL_POWER and PL_TOTAL_POWER sensors cannot
+ * be reset on their own; use pm_reset_statistics().
+ *
+ * @param handle Library handle
+ * @param sensor_name Name of the sensor, as returned by pm_get_sensor_names()
+ * @return Error code; PM_ERROR_NO_SENSORS if no sensor has the given name,
+ *         PM_ERROR_INVALID_ARGUMENT if it names a virtual sensor
+ */
+pm_error_t pm_reset_sensor_statistics(pm_handle_t handle, const char* sensor_name);
+
+/**
+ * @brief Get the statistics of the interval between samples
+ *
+ * Covers the intervals actually achieved between consecutive samples of
+ * the sampling thread, in microseconds, measured from the sample
+ * timestamps. The gap between two sampling runs is not counted. Reset by
+ * pm_reset_statistics().
+ *
+ * @param handle Library handle
+ * @param[out] stats Pointer to store a copy of the interval statistics
+ * @return Error code
+ */
+pm_error_t pm_get_interval_statistics(pm_handle_t handle, pm_stats_t* stats);
+
+/**
+ * @brief Check that the sampling thread is alive and producing readings
+ *
+ * A sample counts as successful when at least one physical sensor could be
+ * read. Until the first successful sample of a run, the age is measured
+ * from the start of sampling (or from pm_init() if sampling never started).
+ *
+ * @param handle Library handle
+ * @param[out] alive Whether the sampling thread is running
+ * @param[out] last_sample_age_ns Time since the last successful sample
+ * @return Error code
+ */
+pm_error_t pm_get_sampling_health(pm_handle_t handle, bool* alive,
+                                  uint64_t* last_sample_age_ns);
+
 /**
  * @brief Get power summary (PS_TOTAL_POWER, PL_TOTAL_POWER, TOTAL_POWER)
  *
@@ -247,6 +623,8 @@ pm_error_t pm_get_power_summary(pm_handle_t handle, pm_power_summary_t* summary)
  * @brief Get power summary statistics (PS_TOTAL_POWER, PL_TOTAL_POWER, TOTAL_POWER)
  *
  * This function returns the power statistics for PS, PL and Total.
+ * All three are updated by the same sample and copied under one lock, so
+ * their sample counts are always equal.
  *
  * @param handle Library handle
  * @param[out] summary_stats Pointer to store the summary statistics
@@ -276,6 +654,84 @@ pm_error_t pm_get_sensor_count(pm_handle_t handle, int* count);
  */
 pm_error_t pm_get_sensor_names(pm_handle_t handle, char** names, int* count);
 
+/**
+ * @brief Copy the sensor names into a flat caller-owned buffer
+ *
+ * Each name is written NUL-terminated into its own slot of
+ * PM_SENSOR_NAME_LEN bytes, in the order of pm_get_latest_data(). Unlike
+ * pm_get_sensor_names() no per-name allocations are needed.
+ *
+ * @param handle Library handle
+ * @param[out] names Buffer of at least count * PM_SENSOR_NAME_LEN bytes
+ * @param[inout] count On input: number of slots in the buffer; On output:
+ *                     number of names copied
+ * @return Error code
+ */
+pm_error_t pm_copy_sensor_names(pm_handle_t handle, char* names, int* count);
+
+/**
+ * @brief Set the number of samples retained in the history
+ *
+ * The sampling thread appends every sample to a ring buffer of the given
+ * capacity; once it is full, the oldest sample is dropped. A capacity of 0,
+ * the default, disables the history. Changing the capacity discards the
+ * samples retained so far.
+ *
+ * @param handle Library handle
+ * @param capacity Number of samples to retain, or 0 to disable the history
+ * @return Error code
+ */
+pm_error_t pm_set_history_capacity(pm_handle_t handle, int capacity);
+
+/**
+ * @brief Copy the samples retained in the history
+ *
+ * Samples are copied oldest first. Each sample consists of one legacy total
+ * and pm_get_sensor_count() sensor readings, in the same order as
+ * pm_get_latest_data(). The history itself is left unchanged.
+ *
+ * @param handle Library handle
+ * @param[out] total Array receiving the total of each sample
+ * @param[out] sensors Array receiving the sensor readings of each sample,
+ *                     with room for count * pm_get_sensor_count() entries
+ * @param[in,out] count On input: number of samples the arrays can hold;
+ *                      On output: number of samples copied. If more samples
+ *                      are retained, only the newest ones are copied.
+ * @return Error code
+ */
+pm_error_t pm_get_history(pm_handle_t handle, pm_sensor_data_t* total, pm_sensor_data_t* sensors, int* count);
+
+/**
+ * @brief Set the warning and critical thresholds of a sensor
+ *
+ * The thresholds are reported in the warning_threshold and
+ * critical_threshold fields of the sensor data, both in watts. They apply to
+ * every sensor with the given name and are kept until changed again.
+ *
+ * @param handle Library handle
+ * @param sensor_name Name of the sensor, as returned by pm_get_sensor_names()
+ * @param warning Warning threshold in watts
+ * @param critical Critical threshold in watts, must not be below warning
+ * @return Error code; PM_ERROR_NO_SENSORS if no sensor has the given name
+ */
+pm_error_t pm_set_thresholds(pm_handle_t handle, const char* sensor_name, double warning, double critical);
+
+/**
+ * @brief Set the per-sample callback
+ *
+ * The callback runs on the sampling thread after each sample has been
+ * published, outside of the library's internal lock, so it may call the
+ * pm_get_* functions. It must not call pm_stop_sampling() or pm_cleanup().
+ * A callback that is already running may still complete after this function
+ * returns; stop sampling before releasing resources used by user_data.
+ *
+ * @param handle Library handle
+ * @param callback Callback to invoke, or NULL to remove the current callback
+ * @param user_data Opaque pointer passed to the callback
+ * @return Error code
+ */
+pm_error_t pm_set_sample_callback(pm_handle_t handle, pm_sample_callback_t callback, void* user_data);
+
 /**
  * @brief Get a human-readable error message for an error code
  *
@@ -284,6 +740,66 @@ pm_error_t pm_get_sensor_names(pm_handle_t handle, char** names, int* count);
  */
 const char* pm_error_string(pm_error_t error);
 
+/**
+ * @brief Get the cause of the most recent failed file access
+ *
+ * Library calls that touch sysfs record the errno and path of the last file
+ * or directory they failed to open, per thread, like errno itself. Call this
+ * on the same thread right after a call failed, e.g. with
+ * PM_ERROR_NO_SENSORS or PM_ERROR_FILE_ACCESS from pm_init(); the record is
+ * cleared by reading it and when a new handle is initialized. Failed reads
+ * of the sampling thread are not recorded, they mark the sensor offline.
+ *
+ * @param[out] errnum errno of the failure, or 0 if none was recorded
+ * @param[out] path Buffer receiving the path, NUL-terminated and truncated
+ *                  to path_len; empty if none was recorded
+ * @param path_len Size of the path buffer in bytes
+ * @return Error code
+ */
+pm_error_t pm_get_last_file_error(int* errnum, char* path, int path_len);
+
+#ifdef XLNPWMON_MOCK
+/**
+ * @brief Take a synthetic sensor offline or bring it back
+ *
+ * Only available in mock builds, to exercise the handling of sensors that
+ * stop responding. An offline sensor fails every read until it is brought
+ * back.
+ *
+ * @param handle Library handle
+ * @param sensor_name Name of a physical sensor, as returned by
+ *                    pm_get_sensor_names()
+ * @param online Whether the sensor can be read
+ * @return Error code; PM_ERROR_NO_SENSORS if no physical sensor has the name
+ */
+pm_error_t pm_mock_set_sensor_online(pm_handle_t handle, const char* sensor_name, bool online);
+
+/**
+ * @brief Feed power readings straight into the statistics of a sensor
+ *
+ * Only available in mock builds, to exercise the accumulation of long
+ * captures without sampling for as long. Adds count samples of the given
+ * power to the power statistics of the sensor, as the sampling thread would.
+ *
+ * @param handle Library handle
+ * @param sensor_name Name of a sensor, as returned by pm_get_sensor_names()
+ * @param power Power reading in watts
+ * @param count Number of samples to add
+ * @return Error code; PM_ERROR_NO_SENSORS if no sensor has the name
+ */
+pm_error_t pm_mock_add_power_samples(pm_handle_t handle, const char* sensor_name, double power, uint64_t count);
+#endif
+
+/**
+ * @brief Get the version of the linked library
+ *
+ * Compare with XLNPWMON_VERSION to detect a header/library mismatch when
+ * linking dynamically.
+ *
+ * @return Version string such as "0.0.3", owned by the library
+ */
+const char* pm_version(void);
+
 #ifdef __cplusplus
 }
 #endif
@@ -4,25 +4,37 @@
  * @author Implementation for Xilinx ZCU102
  *
  * This implementation uses the Linux hwmon interface to read power data
- * from INA226 sensors on the ZCU102 board.
+ * from INA226 sensors on the ZCU102 board. When compiled with XLNPWMON_MOCK
+ * defined, hwmon is replaced by a fixed set of synthetic sensors so the
+ * library can be tested without the hardware.
  */
 
+// clock_gettime() is not declared in strict C99 mode, and CPU affinity is a
+// GNU extension
+#define _GNU_SOURCE
+
 #include "xlnpwmon/xlnpwmon.h"
 #include <dirent.h>
 #include <errno.h>
+#include <limits.h>
 #include <math.h>
 #include <pthread.h>
+#include <sched.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 #include <sys/stat.h>
+#include <time.h>
 #include <unistd.h>
 
-#define HWMON_PATH "/sys/class/hwmon"
+#define DEFAULT_SYSFS_ROOT "/sys"
+#define SYSFS_ROOT_ENV "XLNPWMON_SYSFS_ROOT" // Overrides DEFAULT_SYSFS_ROOT
+#define HWMON_SUBDIR "/class/hwmon"
 #define MAX_SENSORS 32
 #define MAX_PHYSICAL_SENSORS 29 // Max physical sensors (before virtual ones)
 #define VIRTUAL_SENSOR_COUNT 3  // Number of virtual sensors
-#define DEFAULT_SAMPLING_FREQ 1 // Hz
+#define MIN_SAMPLING_FREQ 1     // Hz
+#define MAX_SAMPLING_FREQ 10000 // Hz, well above what hwmon reads can keep up
 
 // Virtual sensor indices (appended after physical sensors)
 #define VIRTUAL_PS_TOTAL_IDX 0 // PS_TOTAL_POWER index in virtual array
@@ -120,6 +132,8 @@ static bool is_pl_sensor(const char *sensor_name) {
 typedef struct {
   char name[64];          // Sensor name (e.g., ina226_u76)
   char hwmon_path[256];   // Path to hwmon device
+  char device_id[PM_DEVICE_ID_LEN]; // Parent device the sensor sits on
+  char device_path[256];  // Resolved sysfs path of the parent device
   char voltage_file[512]; // Path to voltage input file
   char current_file[512]; // Path to current input file
   char power_file[512];   // Path to power input file
@@ -127,6 +141,27 @@ typedef struct {
   bool online;            // Whether sensor is accessible
 } pm_sensor_info_t;
 
+/**
+ * @brief Compensated running sum behind the total of a pm_stats_t
+ *
+ * The total is reported as sum + compensation; keeping the low-order bits
+ * that each addition to sum loses stops the total from drifting over long
+ * captures. Reset implicitly when the statistics restart at count 0.
+ */
+typedef struct {
+  double sum;          // Plain running sum
+  double compensation; // Rounding error accumulated by sum
+} pm_sum_t;
+
+/**
+ * @brief Compensated sums of the statistics of one sensor
+ */
+typedef struct {
+  pm_sum_t voltage;
+  pm_sum_t current;
+  pm_sum_t power;
+} pm_sensor_sums_t;
+
 /**
  * @brief Library handle internal structure
  */
@@ -142,15 +177,166 @@ struct pm_handle_s {
   pm_sensor_stats_t
       stats[MAX_SENSORS + VIRTUAL_SENSOR_COUNT]; // Per-sensor statistics
   pm_sensor_stats_t total_stats;                 // Total statistics
+  pm_sensor_sums_t sums[MAX_SENSORS + VIRTUAL_SENSOR_COUNT]; // Behind stats
+  pm_sensor_sums_t total_sums;   // Behind total_stats
+  pm_stats_t interval_stats;     // Inter-sample intervals in microseconds
+  pm_sum_t interval_sum;         // Behind interval_stats
+  uint64_t last_sample_ns;       // Timestamp of the previous sample
+  bool has_last_sample;          // Whether this run has taken a sample yet
+  uint64_t last_success_ns; // Monotonic time of the last successful sample
 
   pthread_t sampling_thread;  // Sampling thread
+  bool thread_joinable;       // Thread not joined yet (guarded by control_mutex)
+  cpu_set_t thread_affinity;  // CPUs of the sampling thread (guarded by control_mutex)
+  bool has_thread_affinity;   // Whether thread_affinity applies, else any CPU
+  int thread_priority;        // SCHED_FIFO priority, 0 for the default policy
   pthread_mutex_t data_mutex; // Mutex for data access
-  bool is_sampling;           // Sampling active flag
-  bool stop_sampling;         // Stop request flag
+  pthread_mutex_t control_mutex; // Serializes starting and stopping
+  pthread_cond_t sample_cond; // Signaled on every sample and on stop requests
+  pthread_cond_t control_cond; // Wakes the sampling thread on control changes
+  uint64_t sample_seq;        // Samples published so far (guarded by data_mutex)
+  uint64_t run_start_seq;     // sample_seq when the current run started
+  bool is_sampling;           // Sampling active flag (guarded by data_mutex)
+  bool stop_sampling;         // Stop request flag (guarded by data_mutex)
+  bool paused;                // Pause request flag (guarded by data_mutex)
+  uint64_t stop_at_ns; // Monotonic time to stop sampling at, 0 for never
+
+  int sampling_frequency; // Sampling frequency in Hz, rounded if set as a period
+  uint64_t sampling_period_ns; // Time between sample starts
+
+  pm_sample_callback_t sample_callback; // Per-sample callback (optional)
+  void *sample_callback_data;           // User data for the callback
+
+  uint64_t epoch_ns; // Monotonic time of pm_init, the origin of timestamps
+
+  // Ring buffer of recent samples (guarded by data_mutex, empty if capacity 0)
+  pm_sensor_data_t *history_sensors; // capacity * sensor_count readings
+  pm_sensor_data_t *history_total;   // capacity legacy totals
+  int history_capacity;              // Maximum number of samples retained
+  int history_start;                 // Index of the oldest sample
+  int history_count;                 // Number of samples retained
+};
+
+/**
+ * @brief Get the current monotonic time in nanoseconds
+ */
+static uint64_t monotonic_ns(void) {
+  struct timespec ts;
+  clock_gettime(CLOCK_MONOTONIC, &ts);
+  return (uint64_t)ts.tv_sec * 1000000000ULL + (uint64_t)ts.tv_nsec;
+}
+
+/**
+ * @brief Convert a monotonic time in nanoseconds to a timespec deadline
+ */
+static struct timespec timespec_from_ns(uint64_t ns) {
+  struct timespec ts;
+  ts.tv_sec = (time_t)(ns / 1000000000ULL);
+  ts.tv_nsec = (long)(ns % 1000000000ULL);
+  return ts;
+}
+
+/**
+ * @brief Initialize a condition variable whose timed waits use the monotonic
+ * clock, like the timestamps
+ */
+static bool init_monotonic_cond(pthread_cond_t *cond) {
+  pthread_condattr_t cond_attr;
+  if (pthread_condattr_init(&cond_attr) != 0) {
+    return false;
+  }
+  bool ok = pthread_condattr_setclock(&cond_attr, CLOCK_MONOTONIC) == 0 &&
+            pthread_cond_init(cond, &cond_attr) == 0;
+  pthread_condattr_destroy(&cond_attr);
+  return ok;
+}
+
+/**
+ * Most recent failed file access of a library call on this thread, reported
+ * by pm_get_last_file_error(). Thread-local because the handle does not exist
+ * yet when initialization fails.
+ */
+static __thread int last_file_errno;
+static __thread char last_file_path[PATH_MAX];
 
-  int sampling_frequency; // Sampling frequency in Hz
+#ifdef XLNPWMON_MOCK
+
+/**
+ * @brief Synthetic sensors reported by the mock backend
+ *
+ * The readings are constant and exactly representable in binary, so the
+ * statistics of a mock capture are known exactly. One PS and two PL rails
+ * are simulated, which also exercises the virtual total sensors.
+ */
+static const struct {
+  const char *name;
+  double voltage;
+  double current;
+  double power;
+} mock_sensors[] = {
+    {"ina226_u76", 0.75, 2.0, 1.5},   // VCCPSINTFP (PS)
+    {"ina226_u79", 1.0, 4.0, 4.0},    // VCCINT (PL)
+    {"ina226_u16", 3.25, 0.5, 1.625}, // VCC3V3 (PL)
 };
 
+#define MOCK_SENSOR_COUNT                                                     \
+  ((int)(sizeof(mock_sensors) / sizeof(mock_sensors[0])))
+
+/**
+ * @brief Report the synthetic sensors instead of scanning hwmon
+ */
+static int discover_sensors(const char *hwmon_dir, pm_sensor_info_t *sensors,
+                            int max_sensors) {
+  (void)hwmon_dir;
+  int count = 0;
+  for (; count < MOCK_SENSOR_COUNT && count < max_sensors; count++) {
+    pm_sensor_info_t *sensor = &sensors[count];
+    strncpy(sensor->name, mock_sensors[count].name, sizeof(sensor->name) - 1);
+    snprintf(sensor->hwmon_path, sizeof(sensor->hwmon_path), "mock/hwmon%d",
+             count);
+    strncpy(sensor->device_id, "mock", sizeof(sensor->device_id) - 1);
+    strncpy(sensor->device_path, "mock", sizeof(sensor->device_path) - 1);
+    sensor->type = PM_SENSOR_TYPE_I2C;
+    sensor->online = true;
+  }
+  return count;
+}
+
+/**
+ * @brief Produce the fixed reading of a synthetic sensor
+ */
+static bool read_sensor_data(const pm_sensor_info_t *info,
+                             pm_sensor_data_t *data) {
+  for (int i = 0; i < MOCK_SENSOR_COUNT; i++) {
+    if (strcmp(info->name, mock_sensors[i].name) != 0) {
+      continue;
+    }
+    data->voltage = mock_sensors[i].voltage;
+    data->current = mock_sensors[i].current;
+    data->power = mock_sensors[i].power;
+
+    const char *friendly_name = get_friendly_name(info->name);
+    strncpy(data->name, friendly_name, sizeof(data->name) - 1);
+    data->type = info->type;
+    data->online = true;
+    strncpy(data->status, "OK", sizeof(data->status) - 1);
+    return true;
+  }
+  return false;
+}
+
+#else
+
+/**
+ * @brief Remember errno and the path of a failed file access for
+ * pm_get_last_file_error()
+ */
+static void record_file_error(const char *path) {
+  last_file_errno = errno;
+  strncpy(last_file_path, path, sizeof(last_file_path) - 1);
+  last_file_path[sizeof(last_file_path) - 1] = '\0';
+}
+
 /**
  * @brief Read a value from a file
  */
@@ -171,12 +357,55 @@ static bool read_file_value(const char *path, long *value) {
  */
 static bool file_exists(const char *path) { return access(path, R_OK) == 0; }
 
+/**
+ * @brief Extract the numeric index of a hwmon directory (e.g. 12 for hwmon12)
+ */
+static long hwmon_index(const char *hwmon_path) {
+  const char *base = strrchr(hwmon_path, '/');
+  base = base ? base + 1 : hwmon_path;
+  return strtol(base + 5, NULL, 10); // skip "hwmon"
+}
+
+/**
+ * @brief Order sensors by ascending hwmon index
+ */
+static int compare_sensors(const void *a, const void *b) {
+  long ia = hwmon_index(((const pm_sensor_info_t *)a)->hwmon_path);
+  long ib = hwmon_index(((const pm_sensor_info_t *)b)->hwmon_path);
+  return (ia > ib) - (ia < ib);
+}
+
+/**
+ * @brief Fill in the parent device of a sensor
+ *
+ * The hwmon "device" link points at the sensor chip (e.g. .../i2c-3/3-0040),
+ * whose parent is the bus it sits on. Sensors without such a link are
+ * attributed to the hwmon class directory itself.
+ */
+static void resolve_device(pm_sensor_info_t *sensor, const char *hwmon_dir) {
+  char link[512];
+  char resolved[PATH_MAX];
+  snprintf(link, sizeof(link), "%s/device/..", sensor->hwmon_path);
+
+  const char *path = realpath(link, resolved) ? resolved : hwmon_dir;
+  const char *base = strrchr(path, '/');
+  base = (base && base[1]) ? base + 1 : path;
+
+  strncpy(sensor->device_id, base, sizeof(sensor->device_id) - 1);
+  strncpy(sensor->device_path, path, sizeof(sensor->device_path) - 1);
+}
+
 /**
  * @brief Discover INA226 sensors in hwmon
+ *
+ * Sensors are returned sorted by hwmon index so that the sensor order is
+ * stable regardless of the order in which readdir() lists the entries.
  */
-static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
-  DIR *dir = opendir(HWMON_PATH);
+static int discover_sensors(const char *hwmon_dir, pm_sensor_info_t *sensors,
+                            int max_sensors) {
+  DIR *dir = opendir(hwmon_dir);
   if (!dir) {
+    record_file_error(hwmon_dir);
     return 0;
   }
 
@@ -189,7 +418,7 @@ static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
     }
 
     char hwmon_path[256];
-    snprintf(hwmon_path, sizeof(hwmon_path), "%s/%s", HWMON_PATH,
+    snprintf(hwmon_path, sizeof(hwmon_path), "%s/%s", hwmon_dir,
              entry->d_name);
 
     // Read device name
@@ -198,6 +427,7 @@ static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
 
     FILE *fp = fopen(name_file, "r");
     if (!fp) {
+      record_file_error(name_file);
       continue;
     }
 
@@ -218,6 +448,8 @@ static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
     strncpy(sensor->name, name, sizeof(sensor->name) - 1);
     strncpy(sensor->hwmon_path, hwmon_path, sizeof(sensor->hwmon_path) - 1);
 
+    resolve_device(sensor, hwmon_dir);
+
     // Build file paths
     snprintf(sensor->voltage_file, sizeof(sensor->voltage_file), "%s/in2_input",
              hwmon_path);
@@ -233,6 +465,8 @@ static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
   }
 
   closedir(dir);
+
+  qsort(sensors, count, sizeof(pm_sensor_info_t), compare_sensors);
   return count;
 }
 
@@ -241,10 +475,6 @@ static int discover_sensors(pm_sensor_info_t *sensors, int max_sensors) {
  */
 static bool read_sensor_data(const pm_sensor_info_t *info,
                              pm_sensor_data_t *data) {
-  if (!info->online) {
-    return false;
-  }
-
   long voltage_raw = 0, current_raw = 0, power_raw = 0;
 
   // Read values (return false if any read fails)
@@ -273,24 +503,71 @@ static bool read_sensor_data(const pm_sensor_info_t *info,
   return true;
 }
 
+#endif // XLNPWMON_MOCK
+
+/**
+ * @brief Build the hwmon class directory below a sysfs root
+ *
+ * A NULL root falls back to $XLNPWMON_SYSFS_ROOT and then to /sys, so
+ * sensors can be read from a fixture tree instead of the hardware.
+ */
+static void hwmon_dir_for(const char *sysfs_root, char *dir, size_t size) {
+  if (!sysfs_root || !sysfs_root[0]) {
+    sysfs_root = getenv(SYSFS_ROOT_ENV);
+  }
+  if (!sysfs_root || !sysfs_root[0]) {
+    sysfs_root = DEFAULT_SYSFS_ROOT;
+  }
+  snprintf(dir, size, "%s%s", sysfs_root, HWMON_SUBDIR);
+}
+
+/**
+ * @brief Add a value to a compensated sum
+ *
+ * Neumaier's variant of Kahan summation, which also stays exact when the
+ * value is larger than the sum so far. Relies on strict IEEE arithmetic;
+ * -ffast-math would optimize the compensation away.
+ */
+static void add_to_sum(pm_sum_t *sum, double value) {
+  double t = sum->sum + value;
+  if (fabs(sum->sum) >= fabs(value)) {
+    sum->compensation += (sum->sum - t) + value;
+  } else {
+    sum->compensation += (value - t) + sum->sum;
+  }
+  sum->sum = t;
+}
+
 /**
  * @brief Update statistics with new sample
  */
-static void update_stats(pm_stats_t *stats, double value) {
+static void update_stats(pm_stats_t *stats, pm_sum_t *sum, double value) {
   if (stats->count == 0) {
     stats->min = value;
     stats->max = value;
     stats->avg = value;
     stats->total = value;
     stats->count = 1;
+    stats->sum_sq_dev = 0.0;
+    stats->stddev = 0.0;
+    sum->sum = value;
+    sum->compensation = 0.0;
   } else {
     if (value < stats->min)
       stats->min = value;
     if (value > stats->max)
       stats->max = value;
-    stats->total += value;
+    double previous_avg = stats->avg;
+    add_to_sum(sum, value);
+    stats->total = sum->sum + sum->compensation;
     stats->count++;
     stats->avg = stats->total / stats->count;
+    // Welford's update, which unlike a plain sum of squares does not lose
+    // the spread of small fluctuations on a large average. While all samples
+    // are equal the spread is zero, even if rounding moved the average.
+    if (stats->min != stats->max)
+      stats->sum_sq_dev += (value - previous_avg) * (value - stats->avg);
+    stats->stddev = sqrt(stats->sum_sq_dev / stats->count);
   }
 }
 
@@ -298,11 +575,128 @@ static void update_stats(pm_stats_t *stats, double value) {
  * @brief Update sensor statistics
  */
 static void update_sensor_stats(pm_sensor_stats_t *sensor_stats,
+                                pm_sensor_sums_t *sums,
                                 const pm_sensor_data_t *data) {
   strncpy(sensor_stats->name, data->name, sizeof(sensor_stats->name) - 1);
-  update_stats(&sensor_stats->voltage, data->voltage);
-  update_stats(&sensor_stats->current, data->current);
-  update_stats(&sensor_stats->power, data->power);
+  update_stats(&sensor_stats->voltage, &sums->voltage, data->voltage);
+  update_stats(&sensor_stats->current, &sums->current, data->current);
+  update_stats(&sensor_stats->power, &sums->power, data->power);
+}
+
+/**
+ * @brief Read every physical sensor and derive the virtual sensors
+ *
+ * Fills the physical and virtual entries of @p sensors and @p total_data.
+ * Sensors that cannot be read keep their previous values but are marked
+ * offline until they can be read again. The caller is
+ * responsible for any locking of the output buffers.
+ *
+ * @param handle Library handle
+ * @param online Optional per physical sensor accessibility; NULL reads it
+ *               from the sensor table, which requires holding data_mutex
+ * @param sensors Output array with room for handle->sensor_count entries
+ * @param total_data Output for the legacy total
+ * @param read_ok Optional output, set per physical sensor to whether it was read
+ */
+static void read_all_sensors(pm_handle_t handle, const bool *online,
+                             pm_sensor_data_t *sensors,
+                             pm_sensor_data_t *total_data, bool *read_ok) {
+  double total_voltage = 0.0;
+  double total_current = 0.0;
+  double total_power = 0.0;
+
+  double ps_voltage = 0.0;
+  double ps_current = 0.0;
+  double ps_power = 0.0;
+  int ps_count = 0;
+
+  double pl_voltage = 0.0;
+  double pl_current = 0.0;
+  double pl_power = 0.0;
+  int pl_count = 0;
+
+  int online_count = 0;
+
+  for (int i = 0; i < handle->physical_sensor_count; i++) {
+    pm_sensor_data_t *data = &sensors[i];
+    bool accessible = online ? online[i] : handle->sensors[i].online;
+    bool ok = accessible && read_sensor_data(&handle->sensors[i], data);
+    if (ok) {
+      data->timestamp_ns = monotonic_ns() - handle->epoch_ns;
+    } else {
+      data->online = false;
+    }
+    if (read_ok) {
+      read_ok[i] = ok;
+    }
+
+    if (ok) {
+      total_voltage += data->voltage;
+      total_current += data->current;
+      total_power += data->power;
+      online_count++;
+
+      // Categorize by PS/PL
+      if (is_ps_sensor(handle->sensors[i].name)) {
+        ps_voltage += data->voltage;
+        ps_current += data->current;
+        ps_power += data->power;
+        ps_count++;
+      } else if (is_pl_sensor(handle->sensors[i].name)) {
+        pl_voltage += data->voltage;
+        pl_current += data->current;
+        pl_power += data->power;
+        pl_count++;
+      }
+    }
+  }
+
+  // Virtual sensors are derived once all physical sensors have been read
+  uint64_t timestamp_ns = monotonic_ns() - handle->epoch_ns;
+
+  // Update virtual sensors
+  int virtual_base = handle->physical_sensor_count;
+
+  // 1. PS_TOTAL_POWER
+  pm_sensor_data_t *ps_total = &sensors[virtual_base + VIRTUAL_PS_TOTAL_IDX];
+  strncpy(ps_total->name, "PS_TOTAL_POWER", sizeof(ps_total->name) - 1);
+  ps_total->voltage = ps_count > 0 ? ps_voltage / ps_count : 0.0;
+  ps_total->current = ps_current;
+  ps_total->power = ps_power;
+  ps_total->online = (ps_count > 0);
+  ps_total->type = PM_SENSOR_TYPE_SYSTEM;
+  strncpy(ps_total->status, "OK", sizeof(ps_total->status) - 1);
+  ps_total->timestamp_ns = timestamp_ns;
+
+  // 2. PL_TOTAL_POWER
+  pm_sensor_data_t *pl_total = &sensors[virtual_base + VIRTUAL_PL_TOTAL_IDX];
+  strncpy(pl_total->name, "PL_TOTAL_POWER", sizeof(pl_total->name) - 1);
+  pl_total->voltage = pl_count > 0 ? pl_voltage / pl_count : 0.0;
+  pl_total->current = pl_current;
+  pl_total->power = pl_power;
+  pl_total->online = (pl_count > 0);
+  pl_total->type = PM_SENSOR_TYPE_SYSTEM;
+  strncpy(pl_total->status, "OK", sizeof(pl_total->status) - 1);
+  pl_total->timestamp_ns = timestamp_ns;
+
+  // 3. TOTAL_POWER
+  pm_sensor_data_t *total = &sensors[virtual_base + VIRTUAL_TOTAL_IDX];
+  strncpy(total->name, "TOTAL_POWER", sizeof(total->name) - 1);
+  total->voltage = online_count > 0 ? total_voltage / online_count : 0.0;
+  total->current = total_current;
+  total->power = total_power;
+  total->online = (online_count > 0);
+  total->type = PM_SENSOR_TYPE_SYSTEM;
+  strncpy(total->status, "OK", sizeof(total->status) - 1);
+  total->timestamp_ns = timestamp_ns;
+
+  // Update legacy total_data (for backward compatibility)
+  total_data->voltage = online_count > 0 ? total_voltage / online_count : 0.0;
+  total_data->current = total_current;
+  total_data->power = total_power;
+  total_data->online = (online_count > 0);
+  total_data->timestamp_ns = timestamp_ns;
+  strncpy(total_data->name, "Total", sizeof(total_data->name) - 1);
 }
 
 /**
@@ -310,121 +704,191 @@ static void update_sensor_stats(pm_sensor_stats_t *sensor_stats,
  */
 static void *sampling_thread_func(void *arg) {
   pm_handle_t handle = (pm_handle_t)arg;
+  bool read_ok[MAX_SENSORS];
 
-  while (!handle->stop_sampling) {
+  for (;;) {
+    uint64_t started_ns = monotonic_ns();
     pthread_mutex_lock(&handle->data_mutex);
+    if (handle->stop_sampling) {
+      pthread_mutex_unlock(&handle->data_mutex);
+      break;
+    }
+    if (handle->stop_at_ns != 0 && started_ns >= handle->stop_at_ns) {
+      // The duration given to pm_start_sampling_for() has elapsed. The
+      // thread is joined by the next start or stop call.
+      handle->is_sampling = false;
+      pthread_cond_broadcast(&handle->sample_cond);
+      pthread_mutex_unlock(&handle->data_mutex);
+      break;
+    }
+    if (handle->paused) {
+      // Sleep until resumed or stopped, or until the duration has elapsed
+      if (handle->stop_at_ns != 0) {
+        struct timespec deadline = timespec_from_ns(handle->stop_at_ns);
+        pthread_cond_timedwait(&handle->control_cond, &handle->data_mutex,
+                               &deadline);
+      } else {
+        pthread_cond_wait(&handle->control_cond, &handle->data_mutex);
+      }
+      pthread_mutex_unlock(&handle->data_mutex);
+      continue;
+    }
 
-    // Read all physical sensors
-    double total_voltage = 0.0;
-    double total_current = 0.0;
-    double total_power = 0.0;
-
-    double ps_voltage = 0.0;
-    double ps_current = 0.0;
-    double ps_power = 0.0;
-    int ps_count = 0;
-
-    double pl_voltage = 0.0;
-    double pl_current = 0.0;
-    double pl_power = 0.0;
-    int pl_count = 0;
-
-    int online_count = 0;
+    read_all_sensors(handle, NULL, handle->current_data, &handle->total_data,
+                     read_ok);
 
+    // Update per-sensor statistics of the sensors that were read
     for (int i = 0; i < handle->physical_sensor_count; i++) {
-      pm_sensor_data_t *data = &handle->current_data[i];
-
-      if (read_sensor_data(&handle->sensors[i], data)) {
-        total_voltage += data->voltage;
-        total_current += data->current;
-        total_power += data->power;
-        online_count++;
-
-        // Categorize by PS/PL
-        if (is_ps_sensor(handle->sensors[i].name)) {
-          ps_voltage += data->voltage;
-          ps_current += data->current;
-          ps_power += data->power;
-          ps_count++;
-        } else if (is_pl_sensor(handle->sensors[i].name)) {
-          pl_voltage += data->voltage;
-          pl_current += data->current;
-          pl_power += data->power;
-          pl_count++;
-        }
-
-        // Update per-sensor statistics
-        update_sensor_stats(&handle->stats[i], data);
+      if (read_ok[i]) {
+        update_sensor_stats(&handle->stats[i], &handle->sums[i],
+                            &handle->current_data[i]);
+        handle->last_success_ns = started_ns;
       }
     }
 
-    // Update virtual sensors
-    int virtual_base = handle->physical_sensor_count;
-
-    // 1. PS_TOTAL_POWER
-    pm_sensor_data_t *ps_total =
-        &handle->current_data[virtual_base + VIRTUAL_PS_TOTAL_IDX];
-    strncpy(ps_total->name, "PS_TOTAL_POWER", sizeof(ps_total->name) - 1);
-    ps_total->voltage = ps_count > 0 ? ps_voltage / ps_count : 0.0;
-    ps_total->current = ps_current;
-    ps_total->power = ps_power;
-    ps_total->online = (ps_count > 0);
-    ps_total->type = PM_SENSOR_TYPE_SYSTEM;
-    strncpy(ps_total->status, "OK", sizeof(ps_total->status) - 1);
-    update_sensor_stats(&handle->stats[virtual_base + VIRTUAL_PS_TOTAL_IDX],
-                        ps_total);
-
-    // 2. PL_TOTAL_POWER
-    pm_sensor_data_t *pl_total =
-        &handle->current_data[virtual_base + VIRTUAL_PL_TOTAL_IDX];
-    strncpy(pl_total->name, "PL_TOTAL_POWER", sizeof(pl_total->name) - 1);
-    pl_total->voltage = pl_count > 0 ? pl_voltage / pl_count : 0.0;
-    pl_total->current = pl_current;
-    pl_total->power = pl_power;
-    pl_total->online = (pl_count > 0);
-    pl_total->type = PM_SENSOR_TYPE_SYSTEM;
-    strncpy(pl_total->status, "OK", sizeof(pl_total->status) - 1);
-    update_sensor_stats(&handle->stats[virtual_base + VIRTUAL_PL_TOTAL_IDX],
-                        pl_total);
-
-    // 3. TOTAL_POWER
-    pm_sensor_data_t *total =
-        &handle->current_data[virtual_base + VIRTUAL_TOTAL_IDX];
-    strncpy(total->name, "TOTAL_POWER", sizeof(total->name) - 1);
-    total->voltage = online_count > 0 ? total_voltage / online_count : 0.0;
-    total->current = total_current;
-    total->power = total_power;
-    total->online = (online_count > 0);
-    total->type = PM_SENSOR_TYPE_SYSTEM;
-    strncpy(total->status, "OK", sizeof(total->status) - 1);
-    update_sensor_stats(&handle->stats[virtual_base + VIRTUAL_TOTAL_IDX],
-                        total);
-
-    // Update legacy total_data (for backward compatibility)
-    handle->total_data.voltage =
-        online_count > 0 ? total_voltage / online_count : 0.0;
-    handle->total_data.current = total_current;
-    handle->total_data.power = total_power;
-    handle->total_data.online = (online_count > 0);
-    strncpy(handle->total_data.name, "Total",
-            sizeof(handle->total_data.name) - 1);
+    // Update virtual sensor statistics
+    for (int i = handle->physical_sensor_count; i < handle->sensor_count; i++) {
+      update_sensor_stats(&handle->stats[i], &handle->sums[i],
+                          &handle->current_data[i]);
+    }
+
+    // Track the interval actually achieved between consecutive samples
+    uint64_t sample_ns = handle->total_data.timestamp_ns;
+    if (handle->has_last_sample) {
+      update_stats(&handle->interval_stats, &handle->interval_sum,
+                   (double)(sample_ns - handle->last_sample_ns) / 1000.0);
+    }
+    handle->last_sample_ns = sample_ns;
+    handle->has_last_sample = true;
 
     // Update total statistics
-    update_sensor_stats(&handle->total_stats, &handle->total_data);
+    update_sensor_stats(&handle->total_stats, &handle->total_sums,
+                        &handle->total_data);
+
+    // Append the sample to the history, dropping the oldest when full
+    if (handle->history_capacity > 0) {
+      int slot = (handle->history_start + handle->history_count) %
+                 handle->history_capacity;
+      if (handle->history_count == handle->history_capacity) {
+        handle->history_start =
+            (handle->history_start + 1) % handle->history_capacity;
+      } else {
+        handle->history_count++;
+      }
+      memcpy(&handle->history_sensors[slot * handle->sensor_count],
+             handle->current_data,
+             sizeof(pm_sensor_data_t) * handle->sensor_count);
+      handle->history_total[slot] = handle->total_data;
+    }
+
+    // Wake up threads waiting for the next sample
+    handle->sample_seq++;
+    pthread_cond_broadcast(&handle->sample_cond);
+
+    // Snapshot the sample for the callback, which runs outside the lock
+    pm_sample_callback_t callback = handle->sample_callback;
+    void *callback_data = handle->sample_callback_data;
+    pm_sensor_data_t snapshot[MAX_SENSORS + VIRTUAL_SENSOR_COUNT];
+    pm_power_data_t sample;
+    if (callback) {
+      memcpy(snapshot, handle->current_data,
+             sizeof(pm_sensor_data_t) * handle->sensor_count);
+      sample.total = handle->total_data;
+      sample.sensors = snapshot;
+      sample.sensor_count = handle->sensor_count;
+    }
 
     pthread_mutex_unlock(&handle->data_mutex);
 
-    // Sleep based on sampling frequency
-    usleep(1000000 / handle->sampling_frequency);
+    if (callback) {
+      callback(&sample, callback_data);
+    }
+
+    // Wait out the rest of the sampling period. Frequency changes and stop
+    // requests wake the wait, so a new period applies to the current one
+    pthread_mutex_lock(&handle->data_mutex);
+    while (!handle->stop_sampling) {
+      uint64_t deadline_ns = started_ns + handle->sampling_period_ns;
+      if (handle->stop_at_ns != 0 && handle->stop_at_ns < deadline_ns) {
+        deadline_ns = handle->stop_at_ns;
+      }
+      if (monotonic_ns() >= deadline_ns) {
+        break;
+      }
+      struct timespec deadline = timespec_from_ns(deadline_ns);
+      pthread_cond_timedwait(&handle->control_cond, &handle->data_mutex,
+                             &deadline);
+    }
+    pthread_mutex_unlock(&handle->data_mutex);
   }
 
   return NULL;
 }
 
 /**
- * @brief Initialize the power monitor
+ * @brief Keep only the sensors of one device, returning the new count
+ */
+static int filter_sensors(pm_sensor_info_t *sensors, int count,
+                          const char *device_id) {
+  int kept = 0;
+  for (int i = 0; i < count; i++) {
+    if (strcmp(sensors[i].device_id, device_id) == 0) {
+      if (kept != i) {
+        sensors[kept] = sensors[i];
+      }
+      kept++;
+    }
+  }
+  return kept;
+}
+
+/**
+ * @brief List the devices that carry power sensors
+ */
+pm_error_t pm_enumerate_devices(pm_device_info_t *devices, int *count) {
+  if (!devices || !count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pm_sensor_info_t *sensors = calloc(MAX_PHYSICAL_SENSORS, sizeof(*sensors));
+  if (!sensors) {
+    return PM_ERROR_MEMORY;
+  }
+  char hwmon_dir[256];
+  hwmon_dir_for(NULL, hwmon_dir, sizeof(hwmon_dir));
+  int sensor_count = discover_sensors(hwmon_dir, sensors, MAX_PHYSICAL_SENSORS);
+
+  int max_count = *count;
+  int device_count = 0;
+  for (int i = 0; i < sensor_count; i++) {
+    int d = 0;
+    while (d < device_count && strcmp(devices[d].id, sensors[i].device_id) != 0) {
+      d++;
+    }
+    if (d == device_count) {
+      if (device_count == max_count) {
+        continue;
+      }
+      memset(&devices[d], 0, sizeof(devices[d]));
+      strncpy(devices[d].id, sensors[i].device_id, sizeof(devices[d].id) - 1);
+      strncpy(devices[d].path, sensors[i].device_path,
+              sizeof(devices[d].path) - 1);
+      device_count++;
+    }
+    devices[d].sensor_count++;
+  }
+
+  free(sensors);
+  *count = device_count;
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Allocate a handle for the sensors below sysfs_root (NULL for the
+ * default) that belong to device_id (NULL for all)
  */
-pm_error_t pm_init(pm_handle_t *handle) {
+static pm_error_t init_handle(pm_handle_t *handle, const char *sysfs_root,
+                              const char *device_id) {
   if (!handle) {
     return PM_ERROR_INIT_FAILED;
   }
@@ -436,18 +900,54 @@ pm_error_t pm_init(pm_handle_t *handle) {
   }
 
   memset(h, 0, sizeof(struct pm_handle_s));
+  last_file_errno = 0;
+  h->epoch_ns = monotonic_ns();
+  h->last_success_ns = h->epoch_ns;
 
-  // Initialize mutex
+  // Initialize mutexes
   if (pthread_mutex_init(&h->data_mutex, NULL) != 0) {
     free(h);
     return PM_ERROR_INIT_FAILED;
   }
+  if (pthread_mutex_init(&h->control_mutex, NULL) != 0) {
+    pthread_mutex_destroy(&h->data_mutex);
+    free(h);
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  if (!init_monotonic_cond(&h->sample_cond)) {
+    pthread_mutex_destroy(&h->control_mutex);
+    pthread_mutex_destroy(&h->data_mutex);
+    free(h);
+    return PM_ERROR_INIT_FAILED;
+  }
+  if (!init_monotonic_cond(&h->control_cond)) {
+    pthread_cond_destroy(&h->sample_cond);
+    pthread_mutex_destroy(&h->control_mutex);
+    pthread_mutex_destroy(&h->data_mutex);
+    free(h);
+    return PM_ERROR_INIT_FAILED;
+  }
 
   // Discover sensors
-  h->physical_sensor_count = discover_sensors(h->sensors, MAX_PHYSICAL_SENSORS);
+  char hwmon_dir[256];
+  hwmon_dir_for(sysfs_root, hwmon_dir, sizeof(hwmon_dir));
+  h->physical_sensor_count =
+      discover_sensors(hwmon_dir, h->sensors, MAX_PHYSICAL_SENSORS);
+  if (device_id) {
+    h->physical_sensor_count =
+        filter_sensors(h->sensors, h->physical_sensor_count, device_id);
+  }
   if (h->physical_sensor_count == 0) {
+    pthread_cond_destroy(&h->control_cond);
+    pthread_cond_destroy(&h->sample_cond);
+    pthread_mutex_destroy(&h->control_mutex);
     pthread_mutex_destroy(&h->data_mutex);
     free(h);
+    // Sensors that may be there but cannot be read are not "no sensors"
+    if (last_file_errno == EACCES || last_file_errno == EPERM) {
+      return PM_ERROR_FILE_ACCESS;
+    }
     return PM_ERROR_NO_SENSORS;
   }
 
@@ -495,7 +995,8 @@ pm_error_t pm_init(pm_handle_t *handle) {
   strncpy(h->total_data.name, "Total", sizeof(h->total_data.name) - 1);
   strncpy(h->total_stats.name, "Total", sizeof(h->total_stats.name) - 1);
 
-  h->sampling_frequency = DEFAULT_SAMPLING_FREQ;
+  h->sampling_frequency = PM_DEFAULT_SAMPLING_FREQUENCY_HZ;
+  h->sampling_period_ns = 1000000000ULL / PM_DEFAULT_SAMPLING_FREQUENCY_HZ;
   h->is_sampling = false;
   h->stop_sampling = false;
 
@@ -503,6 +1004,28 @@ pm_error_t pm_init(pm_handle_t *handle) {
   return PM_SUCCESS;
 }
 
+/**
+ * @brief Initialize the power monitor
+ */
+pm_error_t pm_init(pm_handle_t *handle) { return pm_init_device(handle, NULL); }
+
+/**
+ * @brief Initialize the library for the sensors of one device
+ */
+pm_error_t pm_init_device(pm_handle_t *handle, const char *device_id) {
+  return init_handle(handle, NULL, device_id);
+}
+
+/**
+ * @brief Initialize the library from the sensors below a sysfs root
+ */
+pm_error_t pm_init_sysfs(pm_handle_t *handle, const char *sysfs_root) {
+  if (!sysfs_root) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+  return init_handle(handle, sysfs_root, NULL);
+}
+
 /**
  * @brief Clean up resources
  */
@@ -512,11 +1035,14 @@ pm_error_t pm_cleanup(pm_handle_t handle) {
   }
 
   // Stop sampling if active
-  if (handle->is_sampling) {
-    pm_stop_sampling(handle);
-  }
+  pm_stop_sampling(handle);
 
+  pthread_cond_destroy(&handle->control_cond);
+  pthread_cond_destroy(&handle->sample_cond);
+  pthread_mutex_destroy(&handle->control_mutex);
   pthread_mutex_destroy(&handle->data_mutex);
+  free(handle->history_sensors);
+  free(handle->history_total);
   free(handle);
 
   return PM_SUCCESS;
@@ -526,16 +1052,33 @@ pm_error_t pm_cleanup(pm_handle_t handle) {
  * @brief Set the sampling frequency
  */
 pm_error_t pm_set_sampling_frequency(pm_handle_t handle, int frequency_hz) {
+  int previous_hz;
+  return pm_swap_sampling_frequency(handle, frequency_hz, &previous_hz);
+}
+
+/**
+ * @brief Set the sampling frequency and get the one it replaces
+ */
+pm_error_t pm_swap_sampling_frequency(pm_handle_t handle, int frequency_hz,
+                                      int *previous_hz) {
   if (!handle) {
     return PM_ERROR_NOT_INITIALIZED;
   }
 
-  if (frequency_hz <= 0) {
+  if (!previous_hz) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  if (frequency_hz < MIN_SAMPLING_FREQ || frequency_hz > MAX_SAMPLING_FREQ) {
     return PM_ERROR_INVALID_FREQUENCY;
   }
 
   pthread_mutex_lock(&handle->data_mutex);
+  *previous_hz = handle->sampling_frequency;
   handle->sampling_frequency = frequency_hz;
+  handle->sampling_period_ns = 1000000000ULL / (uint64_t)frequency_hz;
+  // Let a running sampling thread recompute its current period
+  pthread_cond_broadcast(&handle->control_cond);
   pthread_mutex_unlock(&handle->data_mutex);
 
   return PM_SUCCESS;
@@ -561,105 +1104,562 @@ pm_error_t pm_get_sampling_frequency(pm_handle_t handle, int *frequency_hz) {
 }
 
 /**
- * @brief Start sampling
+ * @brief Get the range of accepted sampling frequencies
  */
-pm_error_t pm_start_sampling(pm_handle_t handle) {
+pm_error_t pm_get_frequency_limits(pm_handle_t handle, int *min_hz,
+                                   int *max_hz) {
   if (!handle) {
     return PM_ERROR_NOT_INITIALIZED;
   }
 
-  if (handle->is_sampling) {
-    return PM_ERROR_ALREADY_RUNNING;
-  }
-
-  handle->stop_sampling = false;
-
-  if (pthread_create(&handle->sampling_thread, NULL, sampling_thread_func,
-                     handle) != 0) {
-    return PM_ERROR_THREAD;
+  if (!min_hz || !max_hz) {
+    return PM_ERROR_INVALID_ARGUMENT;
   }
 
-  handle->is_sampling = true;
+  *min_hz = MIN_SAMPLING_FREQ;
+  *max_hz = MAX_SAMPLING_FREQ;
   return PM_SUCCESS;
 }
 
 /**
- * @brief Stop sampling
+ * @brief Set the time between samples
  */
-pm_error_t pm_stop_sampling(pm_handle_t handle) {
+pm_error_t pm_set_sampling_interval(pm_handle_t handle, uint64_t interval_ns) {
   if (!handle) {
     return PM_ERROR_NOT_INITIALIZED;
   }
 
-  if (!handle->is_sampling) {
-    return PM_ERROR_NOT_RUNNING;
+  if (interval_ns < 1000000000ULL / MAX_SAMPLING_FREQ ||
+      interval_ns > 1000000000ULL / MIN_SAMPLING_FREQ) {
+    return PM_ERROR_INVALID_FREQUENCY;
   }
 
-  handle->stop_sampling = true;
-  pthread_join(handle->sampling_thread, NULL);
-  handle->is_sampling = false;
+  // Report the nearest whole rate as the frequency
+  uint64_t frequency_hz = (1000000000ULL + interval_ns / 2) / interval_ns;
+
+  pthread_mutex_lock(&handle->data_mutex);
+  handle->sampling_period_ns = interval_ns;
+  handle->sampling_frequency = (int)frequency_hz;
+  pthread_cond_broadcast(&handle->control_cond);
+  pthread_mutex_unlock(&handle->data_mutex);
 
   return PM_SUCCESS;
 }
 
 /**
- * @brief Check if sampling is active
+ * @brief Get the time between samples
  */
-pm_error_t pm_is_sampling(pm_handle_t handle, bool *is_sampling) {
+pm_error_t pm_get_sampling_interval(pm_handle_t handle, uint64_t *interval_ns) {
   if (!handle) {
     return PM_ERROR_NOT_INITIALIZED;
   }
 
-  if (!is_sampling) {
-    return PM_ERROR_INIT_FAILED;
+  if (!interval_ns) {
+    return PM_ERROR_INVALID_ARGUMENT;
   }
 
-  *is_sampling = handle->is_sampling;
+  pthread_mutex_lock(&handle->data_mutex);
+  *interval_ns = handle->sampling_period_ns;
+  pthread_mutex_unlock(&handle->data_mutex);
+
   return PM_SUCCESS;
 }
 
 /**
- * @brief Get the latest power data
+ * @brief Prepare the attributes the sampling thread is created with
+ *
+ * Must be called with control_mutex held.
  */
-pm_error_t pm_get_latest_data(pm_handle_t handle, pm_power_data_t *data) {
-  if (!handle) {
-    return PM_ERROR_NOT_INITIALIZED;
+static bool init_thread_attr(pm_handle_t handle, pthread_attr_t *attr) {
+  if (pthread_attr_init(attr) != 0) {
+    return false;
   }
-
-  if (!data) {
-    return PM_ERROR_INIT_FAILED;
+  bool ok = true;
+  if (handle->has_thread_affinity) {
+    ok = pthread_attr_setaffinity_np(attr, sizeof(cpu_set_t),
+                                     &handle->thread_affinity) == 0;
+  }
+  if (ok && handle->thread_priority > 0) {
+    struct sched_param param = {.sched_priority = handle->thread_priority};
+    ok = pthread_attr_setinheritsched(attr, PTHREAD_EXPLICIT_SCHED) == 0 &&
+         pthread_attr_setschedpolicy(attr, SCHED_FIFO) == 0 &&
+         pthread_attr_setschedparam(attr, &param) == 0;
   }
+  if (!ok) {
+    pthread_attr_destroy(attr);
+  }
+  return ok;
+}
 
+/**
+ * @brief Check whether the sampling thread is running
+ *
+ * Must be called with control_mutex held.
+ */
+static bool sampling_thread_running(pm_handle_t handle) {
   pthread_mutex_lock(&handle->data_mutex);
-
-  // Copy total data
-  data->total = handle->total_data;
-
-  // Set pointer to internal sensor data
-  data->sensors = handle->current_data;
-  data->sensor_count = handle->sensor_count;
-
+  bool running = handle->is_sampling;
   pthread_mutex_unlock(&handle->data_mutex);
-
-  return PM_SUCCESS;
+  return running && handle->thread_joinable;
 }
 
 /**
- * @brief Get the power statistics
+ * @brief Set the CPUs the sampling thread may run on
  */
-pm_error_t pm_get_statistics(pm_handle_t handle, pm_power_stats_t *stats) {
+pm_error_t pm_set_sampling_thread_affinity(pm_handle_t handle, const int *cpus,
+                                           int count) {
   if (!handle) {
     return PM_ERROR_NOT_INITIALIZED;
   }
 
-  if (!stats) {
-    return PM_ERROR_INIT_FAILED;
+  if (count < 0 || (count > 0 && !cpus)) {
+    return PM_ERROR_INVALID_ARGUMENT;
   }
 
-  pthread_mutex_lock(&handle->data_mutex);
+  cpu_set_t set;
+  CPU_ZERO(&set);
+  for (int i = 0; i < count; i++) {
+    if (cpus[i] < 0 || cpus[i] >= CPU_SETSIZE) {
+      return PM_ERROR_INVALID_ARGUMENT;
+    }
+    CPU_SET(cpus[i], &set);
+  }
 
-  // Copy total statistics
-  stats->total = handle->total_stats;
+  pthread_mutex_lock(&handle->control_mutex);
+  if (sampling_thread_running(handle)) {
+    // An empty list allows every CPU again
+    cpu_set_t applied = set;
+    if (count == 0) {
+      for (int cpu = 0; cpu < CPU_SETSIZE; cpu++) {
+        CPU_SET(cpu, &applied);
+      }
+    }
+    if (pthread_setaffinity_np(handle->sampling_thread, sizeof(cpu_set_t),
+                               &applied) != 0) {
+      pthread_mutex_unlock(&handle->control_mutex);
+      return PM_ERROR_THREAD;
+    }
+  }
+  handle->thread_affinity = set;
+  handle->has_thread_affinity = count > 0;
+  pthread_mutex_unlock(&handle->control_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Set the real-time priority of the sampling thread
+ */
+pm_error_t pm_set_sampling_thread_priority(pm_handle_t handle, int priority) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (priority < 0 || priority > sched_get_priority_max(SCHED_FIFO)) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pthread_mutex_lock(&handle->control_mutex);
+  if (sampling_thread_running(handle)) {
+    struct sched_param param = {.sched_priority = priority};
+    if (pthread_setschedparam(handle->sampling_thread,
+                              priority > 0 ? SCHED_FIFO : SCHED_OTHER,
+                              &param) != 0) {
+      pthread_mutex_unlock(&handle->control_mutex);
+      return PM_ERROR_THREAD;
+    }
+  }
+  handle->thread_priority = priority;
+  pthread_mutex_unlock(&handle->control_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Start the sampling thread, which stops itself after duration_ms if
+ * positive
+ */
+static pm_error_t start_sampling(pm_handle_t handle, int duration_ms) {
+  pthread_mutex_lock(&handle->control_mutex);
+
+  pthread_mutex_lock(&handle->data_mutex);
+  bool running = handle->is_sampling;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  if (running) {
+    pthread_mutex_unlock(&handle->control_mutex);
+    return PM_ERROR_ALREADY_RUNNING;
+  }
+
+  // Reap a thread that stopped itself after its duration elapsed
+  if (handle->thread_joinable) {
+    pthread_join(handle->sampling_thread, NULL);
+    handle->thread_joinable = false;
+  }
+
+  // Mark sampling active before the thread runs, so a short duration
+  // cannot expire before the flag is set
+  pthread_mutex_lock(&handle->data_mutex);
+  handle->stop_sampling = false;
+  handle->paused = false;
+  handle->has_last_sample = false; // Do not count the gap between runs
+  handle->run_start_seq = handle->sample_seq;
+  handle->last_success_ns = monotonic_ns();
+  handle->stop_at_ns =
+      duration_ms > 0
+          ? monotonic_ns() + (uint64_t)duration_ms * 1000000ULL
+          : 0;
+  handle->is_sampling = true;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  pthread_attr_t attr;
+  bool created = init_thread_attr(handle, &attr);
+  if (created) {
+    created = pthread_create(&handle->sampling_thread, &attr,
+                             sampling_thread_func, handle) == 0;
+    pthread_attr_destroy(&attr);
+  }
+  if (!created) {
+    pthread_mutex_lock(&handle->data_mutex);
+    handle->is_sampling = false;
+    pthread_mutex_unlock(&handle->data_mutex);
+    pthread_mutex_unlock(&handle->control_mutex);
+    return PM_ERROR_THREAD;
+  }
+  handle->thread_joinable = true;
+
+  pthread_mutex_unlock(&handle->control_mutex);
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Start sampling
+ */
+pm_error_t pm_start_sampling(pm_handle_t handle) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  return start_sampling(handle, 0);
+}
+
+/**
+ * @brief Start sampling for a fixed duration
+ */
+pm_error_t pm_start_sampling_for(pm_handle_t handle, int duration_ms) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (duration_ms <= 0) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  return start_sampling(handle, duration_ms);
+}
+
+/**
+ * @brief Stop sampling
+ */
+pm_error_t pm_stop_sampling(pm_handle_t handle) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  pthread_mutex_lock(&handle->control_mutex);
+
+  pthread_mutex_lock(&handle->data_mutex);
+  bool running = handle->is_sampling;
+  handle->stop_sampling = true;
+  pthread_cond_broadcast(&handle->sample_cond);
+  pthread_cond_broadcast(&handle->control_cond);
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  // Also reaps a thread that already stopped itself after its duration
+  if (handle->thread_joinable) {
+    pthread_join(handle->sampling_thread, NULL);
+    handle->thread_joinable = false;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  handle->is_sampling = false;
+  handle->paused = false;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  pthread_mutex_unlock(&handle->control_mutex);
+  return running ? PM_SUCCESS : PM_ERROR_NOT_RUNNING;
+}
+
+/**
+ * @brief Pause or resume the sampling thread
+ */
+static pm_error_t set_paused(pm_handle_t handle, bool paused) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  if (!handle->is_sampling) {
+    pthread_mutex_unlock(&handle->data_mutex);
+    return PM_ERROR_NOT_RUNNING;
+  }
+  if (handle->paused && !paused) {
+    // Do not count the pause as a sampling interval or as a stall
+    handle->has_last_sample = false;
+    handle->last_success_ns = monotonic_ns();
+  }
+  handle->paused = paused;
+  pthread_cond_broadcast(&handle->control_cond);
+  pthread_mutex_unlock(&handle->data_mutex);
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Pause sampling
+ */
+pm_error_t pm_pause_sampling(pm_handle_t handle) {
+  return set_paused(handle, true);
+}
+
+/**
+ * @brief Resume paused sampling
+ */
+pm_error_t pm_resume_sampling(pm_handle_t handle) {
+  return set_paused(handle, false);
+}
+
+/**
+ * @brief Check if sampling is paused
+ */
+pm_error_t pm_is_paused(pm_handle_t handle, bool *paused) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!paused) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  *paused = handle->is_sampling && handle->paused;
+  pthread_mutex_unlock(&handle->data_mutex);
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Check if sampling is active
+ */
+pm_error_t pm_is_sampling(pm_handle_t handle, bool *is_sampling) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!is_sampling) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  *is_sampling = handle->is_sampling;
+  pthread_mutex_unlock(&handle->data_mutex);
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Read all sensors once without the sampling thread
+ */
+pm_error_t pm_read_once(pm_handle_t handle, pm_power_data_t *data) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!data || !data->sensors) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  int capacity = data->sensor_count;
+  data->sensor_count = handle->sensor_count;
+  if (capacity < handle->sensor_count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  // Start from the current data so names, types and thresholds are set
+  pm_sensor_data_t sensors[MAX_SENSORS + VIRTUAL_SENSOR_COUNT];
+  pm_sensor_data_t total;
+  bool online[MAX_SENSORS];
+  pthread_mutex_lock(&handle->data_mutex);
+  memcpy(sensors, handle->current_data,
+         sizeof(pm_sensor_data_t) * handle->sensor_count);
+  total = handle->total_data;
+  for (int i = 0; i < handle->physical_sensor_count; i++) {
+    online[i] = handle->sensors[i].online;
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  // Apart from the online flags copied above, the sensor table is fixed
+  // after pm_init, so the sensors can be read without holding the lock
+  read_all_sensors(handle, online, sensors, &total, NULL);
+
+  memcpy(data->sensors, sensors,
+         sizeof(pm_sensor_data_t) * handle->sensor_count);
+  data->total = total;
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Wait for the sampling thread to publish the next sample
+ */
+pm_error_t pm_wait_for_next_sample(pm_handle_t handle, pm_power_data_t *data,
+                                   int timeout_ms) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!data || !data->sensors) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  if (timeout_ms < 0) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  int capacity = data->sensor_count;
+  data->sensor_count = handle->sensor_count;
+  if (capacity < handle->sensor_count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  struct timespec deadline =
+      timespec_from_ns(monotonic_ns() + (uint64_t)timeout_ms * 1000000ULL);
+
+  pm_error_t error = PM_SUCCESS;
+  pthread_mutex_lock(&handle->data_mutex);
+  uint64_t seq = handle->sample_seq;
+  while (handle->sample_seq == seq) {
+    if (!handle->is_sampling || handle->stop_sampling) {
+      error = PM_ERROR_NOT_RUNNING;
+      break;
+    }
+    if (pthread_cond_timedwait(&handle->sample_cond, &handle->data_mutex,
+                               &deadline) == ETIMEDOUT) {
+      error = handle->sample_seq == seq ? PM_ERROR_TIMEOUT : PM_SUCCESS;
+      break;
+    }
+  }
+
+  if (error == PM_SUCCESS) {
+    memcpy(data->sensors, handle->current_data,
+           sizeof(pm_sensor_data_t) * handle->sensor_count);
+    data->total = handle->total_data;
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return error;
+}
+
+/**
+ * @brief Get the latest power data
+ */
+pm_error_t pm_get_latest_data(pm_handle_t handle, pm_power_data_t *data) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!data) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+
+  // Copy total data
+  data->total = handle->total_data;
+
+  // Set pointer to internal sensor data
+  data->sensors = handle->current_data;
+  data->sensor_count = handle->sensor_count;
+
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Copy the latest power data into caller storage
+ */
+pm_error_t pm_copy_latest_data(pm_handle_t handle, pm_sensor_data_t *buf,
+                               int len, pm_sensor_data_t *total) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!buf || !total) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  // The sensor count is fixed after pm_init, so it can be checked unlocked
+  if (len < handle->sensor_count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  memcpy(buf, handle->current_data,
+         sizeof(pm_sensor_data_t) * handle->sensor_count);
+  *total = handle->total_data;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Get the latest power data if the current run has published any
+ */
+pm_error_t pm_try_get_latest_data(pm_handle_t handle, pm_power_data_t *data,
+                                  bool *available) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!data || !data->sensors || !available) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  int capacity = data->sensor_count;
+  data->sensor_count = handle->sensor_count;
+  if (capacity < handle->sensor_count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+
+  // Nothing has been published since sampling was last started
+  *available = handle->sample_seq != handle->run_start_seq;
+  if (*available) {
+    memcpy(data->sensors, handle->current_data,
+           sizeof(pm_sensor_data_t) * handle->sensor_count);
+    data->total = handle->total_data;
+  }
+
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Get the power statistics
+ */
+pm_error_t pm_get_statistics(pm_handle_t handle, pm_power_stats_t *stats) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!stats) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+
+  // Copy total statistics
+  stats->total = handle->total_stats;
 
   // Set pointer to internal sensor statistics
   stats->sensors = handle->stats;
@@ -670,6 +1670,32 @@ pm_error_t pm_get_statistics(pm_handle_t handle, pm_power_stats_t *stats) {
   return PM_SUCCESS;
 }
 
+/**
+ * @brief Copy the power statistics into caller storage
+ */
+pm_error_t pm_copy_statistics(pm_handle_t handle, pm_sensor_stats_t *buf,
+                              int len, pm_sensor_stats_t *total) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!buf || !total) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  if (len < handle->sensor_count) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  memcpy(buf, handle->stats,
+         sizeof(pm_sensor_stats_t) * handle->sensor_count);
+  *total = handle->total_stats;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
 /**
  * @brief Reset the statistics
  */
@@ -690,12 +1716,96 @@ pm_error_t pm_reset_statistics(pm_handle_t handle) {
     memset(&handle->stats[i].current, 0, sizeof(pm_stats_t));
     memset(&handle->stats[i].power, 0, sizeof(pm_stats_t));
   }
+  memset(&handle->interval_stats, 0, sizeof(pm_stats_t));
+
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Get the statistics of the interval between samples
+ */
+pm_error_t pm_get_interval_statistics(pm_handle_t handle, pm_stats_t *stats) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!stats) {
+    return PM_ERROR_INIT_FAILED;
+  }
 
+  pthread_mutex_lock(&handle->data_mutex);
+  *stats = handle->interval_stats;
   pthread_mutex_unlock(&handle->data_mutex);
 
   return PM_SUCCESS;
 }
 
+/**
+ * @brief Get the statistics of a single sensor
+ */
+pm_error_t pm_get_sensor_statistics(pm_handle_t handle, const char *sensor_name,
+                                    pm_sensor_stats_t *stats) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!sensor_name || !stats) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pm_error_t error = PM_ERROR_NO_SENSORS;
+  pthread_mutex_lock(&handle->data_mutex);
+  for (int i = 0; i < handle->sensor_count; i++) {
+    if (strncmp(handle->stats[i].name, sensor_name,
+                sizeof(handle->stats[i].name)) == 0) {
+      *stats = handle->stats[i];
+      error = PM_SUCCESS;
+      break;
+    }
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return error;
+}
+
+/**
+ * @brief Reset the statistics of a single sensor
+ */
+pm_error_t pm_reset_sensor_statistics(pm_handle_t handle,
+                                      const char *sensor_name) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!sensor_name) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pm_error_t error = PM_ERROR_NO_SENSORS;
+  pthread_mutex_lock(&handle->data_mutex);
+  for (int i = 0; i < handle->sensor_count; i++) {
+    if (strncmp(handle->stats[i].name, sensor_name,
+                sizeof(handle->stats[i].name)) != 0) {
+      continue;
+    }
+    // The virtual totals are derived from the physical sensors; resetting
+    // one alone would leave it out of step with its inputs
+    if (i >= handle->physical_sensor_count) {
+      error = PM_ERROR_INVALID_ARGUMENT;
+      break;
+    }
+    memset(&handle->stats[i].voltage, 0, sizeof(pm_stats_t));
+    memset(&handle->stats[i].current, 0, sizeof(pm_stats_t));
+    memset(&handle->stats[i].power, 0, sizeof(pm_stats_t));
+    error = PM_SUCCESS;
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return error;
+}
+
 /**
  * @brief Get power summary (PS_TOTAL_POWER, PL_TOTAL_POWER, TOTAL_POWER)
  */
@@ -762,6 +1872,23 @@ pm_error_t pm_get_sensor_count(pm_handle_t handle, int *count) {
   return PM_SUCCESS;
 }
 
+/**
+ * @brief Get the name of the sensor at an index of the sensor data array
+ */
+static const char *sensor_name_at(pm_handle_t handle, int index) {
+  if (index < handle->physical_sensor_count) {
+    return get_friendly_name(handle->sensors[index].name);
+  }
+  switch (index - handle->physical_sensor_count) {
+  case VIRTUAL_PS_TOTAL_IDX:
+    return "PS_TOTAL_POWER";
+  case VIRTUAL_PL_TOTAL_IDX:
+    return "PL_TOTAL_POWER";
+  default:
+    return "TOTAL_POWER";
+  }
+}
+
 /**
  * @brief Get the sensor names
  */
@@ -778,29 +1905,170 @@ pm_error_t pm_get_sensor_names(pm_handle_t handle, char **names, int *count) {
   int actual_count =
       (handle->sensor_count < max_count) ? handle->sensor_count : max_count;
 
-  // Physical sensors
-  int i = 0;
-  for (; i < handle->physical_sensor_count && i < actual_count; i++) {
-    const char *friendly_name = get_friendly_name(handle->sensors[i].name);
-    strncpy(names[i], friendly_name, 63);
+  for (int i = 0; i < actual_count; i++) {
+    strncpy(names[i], sensor_name_at(handle, i), 63);
     names[i][63] = '\0';
   }
 
-  // Virtual sensors
-  if (i < actual_count) {
-    strncpy(names[i++], "PS_TOTAL_POWER", 63);
+  *count = actual_count;
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Copy the sensor names into a flat caller-owned buffer
+ */
+pm_error_t pm_copy_sensor_names(pm_handle_t handle, char *names, int *count) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
   }
-  if (i < actual_count) {
-    strncpy(names[i++], "PL_TOTAL_POWER", 63);
+
+  if (!names || !count) {
+    return PM_ERROR_INIT_FAILED;
   }
-  if (i < actual_count) {
-    strncpy(names[i++], "TOTAL_POWER", 63);
+
+  int max_count = *count;
+  int actual_count =
+      (handle->sensor_count < max_count) ? handle->sensor_count : max_count;
+
+  for (int i = 0; i < actual_count; i++) {
+    char *slot = names + (size_t)i * PM_SENSOR_NAME_LEN;
+    strncpy(slot, sensor_name_at(handle, i), PM_SENSOR_NAME_LEN - 1);
+    slot[PM_SENSOR_NAME_LEN - 1] = '\0';
   }
 
   *count = actual_count;
   return PM_SUCCESS;
 }
 
+/**
+ * @brief Set the number of samples retained in the history
+ */
+pm_error_t pm_set_history_capacity(pm_handle_t handle, int capacity) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (capacity < 0) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  if ((size_t)capacity >
+      SIZE_MAX / (sizeof(pm_sensor_data_t) * handle->sensor_count)) {
+    return PM_ERROR_MEMORY;
+  }
+
+  // Allocate outside the lock so sampling is not held up
+  pm_sensor_data_t *sensors = NULL;
+  pm_sensor_data_t *total = NULL;
+  if (capacity > 0) {
+    sensors = (pm_sensor_data_t *)malloc(sizeof(pm_sensor_data_t) * capacity *
+                                         handle->sensor_count);
+    total = (pm_sensor_data_t *)malloc(sizeof(pm_sensor_data_t) * capacity);
+    if (!sensors || !total) {
+      free(sensors);
+      free(total);
+      return PM_ERROR_MEMORY;
+    }
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  pm_sensor_data_t *old_sensors = handle->history_sensors;
+  pm_sensor_data_t *old_total = handle->history_total;
+  handle->history_sensors = sensors;
+  handle->history_total = total;
+  handle->history_capacity = capacity;
+  handle->history_start = 0;
+  handle->history_count = 0;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  free(old_sensors);
+  free(old_total);
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Copy the samples retained in the history
+ */
+pm_error_t pm_get_history(pm_handle_t handle, pm_sensor_data_t *total,
+                          pm_sensor_data_t *sensors, int *count) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!total || !sensors || !count || *count < 0) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+
+  // Copy the newest samples that fit, oldest first
+  int copied = handle->history_count < *count ? handle->history_count : *count;
+  int skipped = handle->history_count - copied;
+  for (int i = 0; i < copied; i++) {
+    int slot = (handle->history_start + skipped + i) % handle->history_capacity;
+    memcpy(&sensors[i * handle->sensor_count],
+           &handle->history_sensors[slot * handle->sensor_count],
+           sizeof(pm_sensor_data_t) * handle->sensor_count);
+    total[i] = handle->history_total[slot];
+  }
+
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  *count = copied;
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Set the warning and critical thresholds of a sensor
+ */
+pm_error_t pm_set_thresholds(pm_handle_t handle, const char *sensor_name,
+                             double warning, double critical) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!sensor_name) {
+    return PM_ERROR_INIT_FAILED;
+  }
+
+  if (!(warning <= critical)) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  // The sampling thread never writes the thresholds, so they stay in place
+  bool found = false;
+  pthread_mutex_lock(&handle->data_mutex);
+  for (int i = 0; i < handle->sensor_count; i++) {
+    pm_sensor_data_t *data = &handle->current_data[i];
+    if (strncmp(data->name, sensor_name, sizeof(data->name)) == 0) {
+      data->warning_threshold = warning;
+      data->critical_threshold = critical;
+      found = true;
+    }
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return found ? PM_SUCCESS : PM_ERROR_NO_SENSORS;
+}
+
+/**
+ * @brief Set the per-sample callback
+ */
+pm_error_t pm_set_sample_callback(pm_handle_t handle,
+                                  pm_sample_callback_t callback,
+                                  void *user_data) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  pthread_mutex_lock(&handle->data_mutex);
+  handle->sample_callback = callback;
+  handle->sample_callback_data = callback ? user_data : NULL;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
 /**
  * @brief Get a human-readable error message for an error code
  */
@@ -826,7 +2094,117 @@ const char *pm_error_string(pm_error_t error) {
     return "Memory allocation error";
   case PM_ERROR_THREAD:
     return "Thread creation/management error";
+  case PM_ERROR_INVALID_ARGUMENT:
+    return "Invalid argument";
+  case PM_ERROR_TIMEOUT:
+    return "Timed out waiting for a sample";
   default:
     return "Unknown error";
   }
-}
\ No newline at end of file
+}
+
+/**
+ * @brief Get and clear the most recent failed file access on this thread
+ */
+pm_error_t pm_get_last_file_error(int *errnum, char *path, int path_len) {
+  if (!errnum || (!path && path_len > 0) || path_len < 0) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  *errnum = last_file_errno;
+  if (path_len > 0) {
+    strncpy(path, last_file_errno ? last_file_path : "", (size_t)path_len - 1);
+    path[path_len - 1] = '\0';
+  }
+  last_file_errno = 0;
+
+  return PM_SUCCESS;
+}
+
+/**
+ * @brief Check that the sampling thread is alive and producing readings
+ */
+pm_error_t pm_get_sampling_health(pm_handle_t handle, bool *alive,
+                                  uint64_t *last_sample_age_ns) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!alive || !last_sample_age_ns) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  uint64_t now_ns = monotonic_ns();
+  pthread_mutex_lock(&handle->data_mutex);
+  *alive = handle->is_sampling;
+  *last_sample_age_ns = now_ns > handle->last_success_ns
+                            ? now_ns - handle->last_success_ns
+                            : 0;
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return PM_SUCCESS;
+}
+
+#ifdef XLNPWMON_MOCK
+/**
+ * @brief Take a synthetic sensor offline or bring it back
+ */
+pm_error_t pm_mock_set_sensor_online(pm_handle_t handle,
+                                     const char *sensor_name, bool online) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!sensor_name) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  bool found = false;
+  pthread_mutex_lock(&handle->data_mutex);
+  for (int i = 0; i < handle->physical_sensor_count; i++) {
+    const pm_sensor_data_t *data = &handle->current_data[i];
+    if (strncmp(data->name, sensor_name, sizeof(data->name)) == 0) {
+      handle->sensors[i].online = online;
+      found = true;
+    }
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return found ? PM_SUCCESS : PM_ERROR_NO_SENSORS;
+}
+
+/**
+ * @brief Feed power readings straight into the statistics of a sensor
+ */
+pm_error_t pm_mock_add_power_samples(pm_handle_t handle,
+                                     const char *sensor_name, double power,
+                                     uint64_t count) {
+  if (!handle) {
+    return PM_ERROR_NOT_INITIALIZED;
+  }
+
+  if (!sensor_name) {
+    return PM_ERROR_INVALID_ARGUMENT;
+  }
+
+  bool found = false;
+  pthread_mutex_lock(&handle->data_mutex);
+  for (int i = 0; i < handle->sensor_count; i++) {
+    if (strncmp(handle->stats[i].name, sensor_name,
+                sizeof(handle->stats[i].name)) == 0) {
+      for (uint64_t n = 0; n < count; n++) {
+        update_stats(&handle->stats[i].power, &handle->sums[i].power, power);
+      }
+      found = true;
+    }
+  }
+  pthread_mutex_unlock(&handle->data_mutex);
+
+  return found ? PM_SUCCESS : PM_ERROR_NO_SENSORS;
+}
+#endif // XLNPWMON_MOCK
+
+/**
+ * @brief Get the version of the linked library
+ */
+const char *pm_version(void) { return XLNPWMON_VERSION; }
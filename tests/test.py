@@ -90,6 +90,7 @@ class TestJetPwMon(unittest.TestCase):
             self.assertIn('status', sensor)
             self.assertIn('warning_threshold', sensor)
             self.assertIn('critical_threshold', sensor)
+            self.assertIn('timestamp_ns', sensor)
             
         # Stop sampling
         self.monitor.stop_sampling()
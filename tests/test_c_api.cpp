@@ -53,6 +53,11 @@ TEST_F(JetPwMonCAPITest, SamplingFrequency) {
     int get_freq = 0;
     pm_error_t err;
 
+    // A new handle starts at the default frequency
+    err = pm_get_sampling_frequency(handle_, &get_freq);
+    ASSERT_EQ(PM_SUCCESS, err) << "Failed to get sampling frequency: " << pm_error_string(err);
+    EXPECT_EQ(PM_DEFAULT_SAMPLING_FREQUENCY_HZ, get_freq) << "New handle does not use the default frequency.";
+
     // Test setting a valid frequency
     err = pm_set_sampling_frequency(handle_, set_freq);
     ASSERT_EQ(PM_SUCCESS, err) << "Failed to set valid sampling frequency: " << pm_error_string(err);